@@ -0,0 +1,416 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use chrono::NaiveDate;
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::competition::discipline::Discipline;
+use crate::competition::performance::Performance;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::gender::Gender;
+use crate::convention::convention::Convention;
+use crate::country::country::Country;
+use crate::export::privacy::BirthdayPrecision;
+use crate::person::person::Person;
+use crate::person::person_search::PersonSearchIndex;
+use crate::record::personal_record::personal_records;
+use crate::record::record_table::{compute_record_table, RecordScope};
+use crate::result::age_group::AgeGroup;
+use crate::result::result_direction::ResultDirections;
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Query parameters shared by every endpoint: a name to search for, filters on results and pagination.
+#[derive(Debug, Default, Deserialize)]
+pub struct ApiQuery {
+    pub name: Option<String>,
+    pub birth_year: Option<i32>,
+    pub discipline: Option<String>,
+    pub gender: Option<String>,
+    pub age_group: Option<String>,
+    pub country: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// A slice of a list, along with the total number of items matching the query.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    total: usize,
+    offset: usize,
+    limit: usize,
+    items: Vec<T>,
+}
+
+impl<T> Page<T> {
+    fn paginate(items: impl IntoIterator<Item=T>, query: &ApiQuery) -> Self {
+        let items: Vec<T> = items.into_iter().collect();
+        let offset = query.offset.unwrap_or_default();
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        Self { total: items.len(), offset, limit, items: items.into_iter().skip(offset).take(limit).collect() }
+    }
+}
+
+/// Filters on the discipline, the gender, the age group and the country of results. Values are parsed the same way as
+/// in results files, so that e.g. `100 m`, `Age 13-14` or `France` match `100m`, `13 - 14` or `FR`.
+#[derive(Debug, Default)]
+pub struct ResultFilter {
+    discipline: Option<Discipline>,
+    gender: Option<Gender>,
+    age_group: Option<AgeGroup>,
+    country: Option<Country>,
+}
+
+impl ResultFilter {
+    pub fn from_query(query: &ApiQuery) -> Result<Self, String> {
+        Ok(Self {
+            discipline: query.discipline.as_deref().map(Discipline::from_string),
+            gender: query.gender.as_deref().map(Gender::from_string).transpose()?,
+            age_group: query.age_group.as_deref().map(AgeGroup::from_string),
+            country: query.country.as_deref().map(Country::from_string).transpose()?,
+        })
+    }
+
+    fn matches(&self, result: &ApiResult) -> bool {
+        self.matches_category(&result.discipline, &result.gender, result.age_group_key.as_ref(), &result.country)
+    }
+
+    fn matches_category(&self, discipline: &str, gender: &str, age_group: Option<&AgeGroup>, country: &str) -> bool {
+        self.discipline.as_ref().is_none_or(|expected| expected.name() == discipline)
+            && self.gender.as_ref().is_none_or(|expected| expected.to_string() == gender)
+            && self.age_group.as_ref().is_none_or(|expected| Some(expected) == age_group)
+            && self.country.as_ref().is_none_or(|expected| expected.code() == country)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiConvention {
+    tag: String,
+    name: String,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    location: Option<String>,
+    competitions: usize,
+}
+
+/// A result, with both its overall and age group places, and the country and id of the person who got it when known.
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiResult {
+    convention: String,
+    competition: String,
+    discipline: String,
+    overall_place: String,
+    age_group_place: String,
+    age_group: String,
+    result: String,
+    details: String,
+    competitor: String,
+    gender: String,
+    country: String,
+    person_id: Option<String>,
+    #[serde(skip)]
+    age_group_key: Option<AgeGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiCompetition {
+    name: String,
+    discipline: String,
+    results: Vec<ApiResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiPersonalRecord {
+    discipline: String,
+    result: String,
+    convention: String,
+    competition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiPersonSummary {
+    id: String,
+    name: String,
+    countries: Vec<String>,
+    conventions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiPerson {
+    #[serde(flatten)]
+    summary: ApiPersonSummary,
+    personal_records: Vec<ApiPersonalRecord>,
+    results: Vec<ApiResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ApiRecord {
+    discipline: String,
+    gender: String,
+    age_group: String,
+    country: String,
+    holder_id: String,
+    holder: String,
+    result: String,
+    convention: String,
+    competition: String,
+    #[serde(skip)]
+    age_group_key: Option<AgeGroup>,
+}
+
+/// The extracted data, detached from the conventions and people it was computed from so that it can be shared
+/// between requests, and queried the way the endpoints need it.
+#[derive(Debug, Default)]
+pub struct ApiDataset {
+    conventions: Vec<ApiConvention>,
+    competitions: HashMap<String, Vec<ApiCompetition>>,
+    people: Vec<ApiPerson>,
+    people_index: PersonSearchIndex<usize>,
+    records: Vec<ApiRecord>,
+    birthday_precision: BirthdayPrecision,
+}
+
+impl ApiDataset {
+    pub fn new(conventions: &HashSet<Convention>, conventions_results: &HashMap<&Convention, ResultStore>, people: &[Person], result_directions: &ResultDirections) -> Self {
+        let people_by_registration: HashMap<(&str, u16), &Person> = people.iter()
+            .flat_map(|person| person.registrations_id().iter()
+                .flat_map(move |(convention, ids)| ids.iter().map(move |id| ((convention.tag().as_str(), *id), person))))
+            .collect();
+        let to_api_result = |convention: &Convention, performance: &Performance| -> ApiResult {
+            let person = performance.ids().iter()
+                .find_map(|id| people_by_registration.get(&(convention.tag().as_str(), *id)));
+            ApiResult::new(convention, performance, person.copied())
+        };
+
+        let mut sorted_conventions: Vec<&Convention> = conventions.iter().collect();
+        sorted_conventions.sort_by(|a, b| a.compare_chronologically(b));
+        let empty_store = ResultStore::default();
+        let mut api_conventions = vec![];
+        let mut competitions = HashMap::new();
+        for convention in sorted_conventions {
+            let store = conventions_results.get(convention).unwrap_or(&empty_store);
+            let mut results_by_competition: BTreeMap<String, Vec<ApiResult>> = BTreeMap::new();
+            for competition in store.competitions() {
+                let results = Performance::link_results(store.by_competition(competition)).iter()
+                    .map(|performance| to_api_result(convention, performance))
+                    .collect();
+                results_by_competition.insert(competition.name().clone(), results);
+            }
+            let convention_competitions: Vec<ApiCompetition> = results_by_competition.into_iter()
+                .map(|(name, results)| ApiCompetition {
+                    discipline: Discipline::from_string(&name).name().clone(),
+                    name,
+                    results,
+                })
+                .collect();
+            api_conventions.push(ApiConvention {
+                tag: convention.tag().clone(),
+                name: convention.name().clone(),
+                start_date: *convention.start_date(),
+                end_date: *convention.end_date(),
+                location: convention.location().clone(),
+                competitions: convention_competitions.len(),
+            });
+            competitions.insert(convention.tag().clone(), convention_competitions);
+        }
+
+        let mut sorted_people: Vec<&Person> = people.iter().collect();
+        sorted_people.sort_by(|a, b| a.identity().person_name().name().cmp(b.identity().person_name().name()));
+        let people_index = PersonSearchIndex::new(sorted_people.iter().enumerate()
+            .map(|(index, person)| (index, person.identity().person_name(), *person.identity().birthday())));
+        let api_people: Vec<ApiPerson> = sorted_people.into_iter()
+            .map(|person| {
+                let mut conventions: Vec<&&Convention> = person.registrations_id().keys().collect();
+                conventions.sort_by(|a, b| a.compare_chronologically(b));
+                let mut performances: Vec<(&&Convention, &Performance)> = person.performances().iter()
+                    .flat_map(|(convention, performances)| performances.iter().map(move |performance| (convention, performance)))
+                    .collect();
+                performances.sort_by(|(a, a_performance), (b, b_performance)| a.compare_chronologically(b)
+                    .then_with(|| a_performance.competition().name().cmp(b_performance.competition().name())));
+                let countries: BTreeSet<String> = person.countries().values().map(|country| country.code().to_string()).collect();
+                ApiPerson {
+                    summary: ApiPersonSummary {
+                        id: person.id().to_string(),
+                        name: person.identity().person_name().name().to_string(),
+                        countries: countries.into_iter().collect(),
+                        conventions: conventions.iter().map(|convention| convention.tag().clone()).collect(),
+                    },
+                    personal_records: personal_records(person, result_directions).iter()
+                        .map(|record| ApiPersonalRecord {
+                            discipline: record.discipline().name().clone(),
+                            result: record.best().performance().result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                            convention: record.best().convention().tag().clone(),
+                            competition: record.best().performance().competition().name().clone(),
+                        })
+                        .collect(),
+                    results: performances.into_iter()
+                        .map(|(convention, performance)| ApiResult::new(convention, performance, Some(person)))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let records = compute_record_table(people, RecordScope::AllTime, result_directions).records().iter()
+            .map(|record| ApiRecord {
+                discipline: record.category().discipline().name().clone(),
+                gender: record.category().gender().clone().unwrap_or_default(),
+                age_group: record.category().age_group().clone().unwrap_or_default(),
+                country: record.category().country().clone().unwrap_or_default(),
+                holder_id: record.holder().id().to_string(),
+                holder: record.holder().identity().person_name().name().to_string(),
+                result: record.mark().performance().result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                convention: record.mark().convention().tag().clone(),
+                competition: record.mark().performance().competition().name().clone(),
+                age_group_key: record.category().age_group().as_deref().map(AgeGroup::from_string),
+            })
+            .collect();
+
+        Self { conventions: api_conventions, competitions, people: api_people, people_index, records, birthday_precision: BirthdayPrecision::default() }
+    }
+
+    /// People can't be searched by birth year when birthdays are hidden, see [crate::export::privacy::PrivacyPolicy].
+    pub fn with_birthday_precision(self, birthday_precision: BirthdayPrecision) -> Self {
+        Self { birthday_precision, ..self }
+    }
+
+    /// Conventions, chronologically, that have at least one result matching the filter.
+    pub fn conventions(&self, query: &ApiQuery, filter: &ResultFilter) -> Page<&ApiConvention> {
+        let conventions = self.conventions.iter()
+            .filter(|convention| self.competitions.get(&convention.tag).is_some_and(|competitions| competitions.iter()
+                .any(|competition| competition.results.iter().any(|result| filter.matches(result)))));
+        Page::paginate(conventions, query)
+    }
+
+    /// Competitions of the convention, by name, with their results matching the filter. `None` if the convention is unknown.
+    pub fn competitions(&self, convention_tag: &str, query: &ApiQuery, filter: &ResultFilter) -> Option<Page<ApiCompetition>> {
+        let competitions = self.competitions.get(convention_tag)?.iter()
+            .map(|competition| ApiCompetition {
+                name: competition.name.clone(),
+                discipline: competition.discipline.clone(),
+                results: competition.results.iter().filter(|result| filter.matches(result)).cloned().collect(),
+            })
+            .filter(|competition| !competition.results.is_empty());
+        Some(Page::paginate(competitions, query))
+    }
+
+    /// People who have at least one result matching the filter, by name, or from the best match to the worst
+    /// when searched by name or birth year (see [PersonSearchIndex]).
+    pub fn people(&self, query: &ApiQuery, filter: &ResultFilter) -> Page<&ApiPersonSummary> {
+        let birth_year = query.birth_year.filter(|_| self.birthday_precision != BirthdayPrecision::Hidden);
+        let people: Vec<&ApiPerson> = if query.name.is_some() || birth_year.is_some() {
+            self.people_index.search(query.name.as_deref().unwrap_or_default(), birth_year).iter()
+                .map(|hit| &self.people[*hit.item()])
+                .collect()
+        } else {
+            self.people.iter().collect()
+        };
+        let people = people.into_iter()
+            .filter(|person| person.results.iter().any(|result| filter.matches(result)))
+            .map(|person| &person.summary);
+        Page::paginate(people, query)
+    }
+
+    /// The person with their results matching the filter. `None` if no one has this id.
+    pub fn person(&self, id: &str, filter: &ResultFilter) -> Option<ApiPerson> {
+        self.people.iter()
+            .find(|person| person.summary.id == id)
+            .map(|person| ApiPerson {
+                summary: person.summary.clone(),
+                personal_records: person.personal_records.clone(),
+                results: person.results.iter().filter(|result| filter.matches(result)).cloned().collect(),
+            })
+    }
+
+    /// All-time records whose category matches the filter: filtering on a gender gives the records of that gender.
+    pub fn records(&self, query: &ApiQuery, filter: &ResultFilter) -> Page<&ApiRecord> {
+        let records = self.records.iter()
+            .filter(|record| filter.matches_category(&record.discipline, &record.gender, record.age_group_key.as_ref(), &record.country))
+            .filter(|record| filter.gender.is_some() || record.gender.is_empty())
+            .filter(|record| filter.age_group.is_some() || record.age_group.is_empty())
+            .filter(|record| filter.country.is_some() || record.country.is_empty());
+        Page::paginate(records, query)
+    }
+}
+
+impl ApiResult {
+    fn new(convention: &Convention, performance: &Performance, person: Option<&Person>) -> Self {
+        Self {
+            convention: convention.tag().clone(),
+            competition: performance.competition().name().clone(),
+            discipline: Discipline::from_competition(performance.competition()).name().clone(),
+            overall_place: performance.overall_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+            age_group_place: performance.age_group_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+            age_group: performance.age_group().as_ref().map(|age_group| age_group.to_string()).unwrap_or_default(),
+            result: performance.result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+            details: performance.details().clone().unwrap_or_default(),
+            competitor: performance.competitor().name().map(str::to_string)
+                .or_else(|| person.map(|person| person.identity().person_name().name().to_string()))
+                .unwrap_or_default(),
+            gender: performance.gender().map(|gender| gender.to_string()).unwrap_or_default(),
+            country: person.and_then(|person| person.country_at(convention)).map(|country| country.code().to_string()).unwrap_or_default(),
+            person_id: person.map(|person| person.id().to_string()),
+            age_group_key: performance.age_group().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::api::dataset::{ApiDataset, ApiQuery, ResultFilter};
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::result_store::ResultStore;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::result::result_direction::ResultDirections;
+    use crate::result::result_type::ResultType;
+
+    fn create_result(id: u16, name: &str, gender: &str, competition: &str, result_type: ResultType) -> CompetitionResult {
+        CompetitionResult::create_test_instance(id, competition, "1", "00:14.990")
+            .with_result_type(result_type)
+            .with_age_group("13-14")
+            .with_individual_competitor(name, Some(gender), None)
+    }
+
+    #[test]
+    fn should_filter_and_paginate_results() {
+        let convention = Convention::create_test_instance("cfm2023");
+        let results = vec![
+            create_result(1, "Jean Dupont", "Male", "100m Male", ResultType::Overall),
+            create_result(1, "Jean Dupont", "Male", "100m Male", ResultType::AgeGroup),
+            create_result(2, "Anna Müller", "Female", "100m Female", ResultType::Overall),
+            create_result(2, "Anna Müller", "Female", "Slow Forward", ResultType::Overall),
+        ];
+        let people = vec![
+            Person::create_test_instance("Jean Dupont").with_birthday(2010, 1, 1)
+                .with_registrations_id(&convention, vec![1]).with_results(&convention, &results[..2]).with_country(&convention, "France"),
+            Person::create_test_instance("Anna Müller").with_birthday(2010, 1, 1)
+                .with_registrations_id(&convention, vec![2]).with_results(&convention, &results[2..]).with_country(&convention, "Germany"),
+        ];
+        let conventions_results = HashMap::from([(&convention, ResultStore::new(results))]);
+        let dataset = ApiDataset::new(&HashSet::from([convention.clone()]), &conventions_results, &people, &ResultDirections::default());
+
+        let query = ApiQuery { discipline: Some("100 m".to_string()), country: Some("Deutschland".to_string()), ..ApiQuery::default() };
+        let competitions = dataset.competitions("cfm2023", &query, &ResultFilter::from_query(&query).unwrap()).unwrap();
+        assert_eq!(competitions.items.len(), 1);
+        assert_eq!(competitions.items[0].results()[0].competitor(), "Anna Müller");
+
+        let query = ApiQuery { name: Some("mul an".to_string()), ..ApiQuery::default() };
+        let found_people = dataset.people(&query, &ResultFilter::from_query(&query).unwrap());
+        assert_eq!(found_people.items.iter().map(|person| person.name().as_str()).collect::<Vec<&str>>(), vec!["Anna Müller"]);
+
+        let query = ApiQuery { limit: Some(1), offset: Some(1), ..ApiQuery::default() };
+        let all_competitions = dataset.competitions("cfm2023", &query, &ResultFilter::default()).unwrap();
+        assert_eq!((all_competitions.total, all_competitions.items.len()), (3, 1));
+        assert_eq!(all_competitions.items[0].name(), "100m Male");
+        assert_eq!(all_competitions.items[0].results()[0].age_group_place(), "1");
+        assert!(dataset.competitions("unknown", &query, &ResultFilter::default()).is_none());
+
+        let query = ApiQuery { gender: Some("female".to_string()), ..ApiQuery::default() };
+        let records = dataset.records(&query, &ResultFilter::from_query(&query).unwrap());
+        assert!(records.items.iter().all(|record| record.gender() == "Female" && record.country().is_empty()));
+        assert!(ResultFilter::from_query(&ApiQuery { country: Some("Atlantis".to_string()), ..ApiQuery::default() }).is_err());
+    }
+}
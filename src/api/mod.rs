@@ -0,0 +1,2 @@
+pub mod dataset;
+pub mod server;
\ No newline at end of file
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use log::{error, info};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::dataset::{ApiDataset, ApiQuery, ResultFilter};
+
+pub const DEFAULT_SERVE_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Serve the dataset as read-only JSON endpoints on `address` until the process is stopped.
+pub async fn serve(address: &str, dataset: ApiDataset) -> Result<(), ()> {
+    let listener = tokio::net::TcpListener::bind(address).await.map_err(|error| {
+        error!("Can't listen [address: {address}]");
+        error!("{error}");
+    })?;
+    info!("Serving results [address: {address}]");
+    axum::serve(listener, router(dataset)).await.map_err(|error| {
+        error!("Server stopped [address: {address}]");
+        error!("{error}");
+    })
+}
+
+fn router(dataset: ApiDataset) -> Router {
+    Router::new()
+        .route("/conventions", get(get_conventions))
+        .route("/conventions/{tag}/competitions", get(get_competitions))
+        .route("/people", get(get_people))
+        .route("/people/{id}", get(get_person))
+        .route("/records", get(get_records))
+        .with_state(Arc::new(dataset))
+}
+
+async fn get_conventions(State(dataset): State<Arc<ApiDataset>>, Query(query): Query<ApiQuery>) -> Response {
+    match ResultFilter::from_query(&query) {
+        Ok(filter) => { to_response(dataset.conventions(&query, &filter)) }
+        Err(error) => { to_error_response(StatusCode::BAD_REQUEST, &error) }
+    }
+}
+
+async fn get_competitions(State(dataset): State<Arc<ApiDataset>>, Path(tag): Path<String>, Query(query): Query<ApiQuery>) -> Response {
+    match ResultFilter::from_query(&query) {
+        Ok(filter) => {
+            match dataset.competitions(&tag, &query, &filter) {
+                Some(competitions) => { to_response(competitions) }
+                None => { to_error_response(StatusCode::NOT_FOUND, &format!("Unknown convention [tag: {tag}]")) }
+            }
+        }
+        Err(error) => { to_error_response(StatusCode::BAD_REQUEST, &error) }
+    }
+}
+
+async fn get_people(State(dataset): State<Arc<ApiDataset>>, Query(query): Query<ApiQuery>) -> Response {
+    match ResultFilter::from_query(&query) {
+        Ok(filter) => { to_response(dataset.people(&query, &filter)) }
+        Err(error) => { to_error_response(StatusCode::BAD_REQUEST, &error) }
+    }
+}
+
+async fn get_person(State(dataset): State<Arc<ApiDataset>>, Path(id): Path<String>, Query(query): Query<ApiQuery>) -> Response {
+    match ResultFilter::from_query(&query) {
+        Ok(filter) => {
+            match dataset.person(&id, &filter) {
+                Some(person) => { to_response(person) }
+                None => { to_error_response(StatusCode::NOT_FOUND, &format!("Unknown person [id: {id}]")) }
+            }
+        }
+        Err(error) => { to_error_response(StatusCode::BAD_REQUEST, &error) }
+    }
+}
+
+async fn get_records(State(dataset): State<Arc<ApiDataset>>, Query(query): Query<ApiQuery>) -> Response {
+    match ResultFilter::from_query(&query) {
+        Ok(filter) => { to_response(dataset.records(&query, &filter)) }
+        Err(error) => { to_error_response(StatusCode::BAD_REQUEST, &error) }
+    }
+}
+
+fn to_response<T: Serialize>(value: T) -> Response {
+    Json(value).into_response()
+}
+
+fn to_error_response(status: StatusCode, error: &str) -> Response {
+    (status, Json(json!({ "error": error }))).into_response()
+}
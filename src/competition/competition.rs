@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+use derive_getters::Getters;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Getters)]
 pub struct Competition {
     name: String,
 }
@@ -7,4 +9,4 @@ impl Competition {
     pub fn new(name: &str) -> Self {
         Self { name: name.to_string() }
     }
-}
\ No newline at end of file
+}
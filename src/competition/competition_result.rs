@@ -1,66 +1,115 @@
 use derive_getters::Getters;
 
 use crate::competition::competition::Competition;
+use crate::competitor::competitor::Competitor;
+use crate::competitor::gender::Gender;
 use crate::result::age_group::AgeGroup;
 use crate::result::place::Place;
 use crate::result::result_type::ResultType;
 use crate::result::result_value::ResultValue;
 
 /// A [CompetitionResult] is defined by a competition, a place,
-/// a result type, a result, optional details and an age group.
+/// a result type, a result, optional details, an age group and the competitor who achieved it.
 #[derive(Debug, Clone, PartialEq, Getters)]
 pub struct CompetitionResult {
-    id: u16,
     competition: Competition,
     place: Place,
     result_type: ResultType,
     result: Option<ResultValue>,
     details: Option<String>,
     age_group: Option<AgeGroup>,
+    competitor: Competitor,
 }
 
 impl CompetitionResult {
     pub fn new(
-        id: u16,
         competition: Competition,
         place: Place,
         result_type: ResultType,
         result: Option<ResultValue>,
         details: Option<String>,
         age_group: Option<AgeGroup>,
+        competitor: Competitor,
     ) -> Self {
         Self {
-            id,
             competition,
             place,
             result_type,
             result,
             details,
             age_group,
+            competitor,
         }
     }
+
+    pub fn with_competitor(self, competitor: Competitor) -> Self {
+        Self { competitor, ..self }
+    }
+
+    pub fn with_details(self, details: Option<String>) -> Self {
+        Self { details, ..self }
+    }
+
+    pub fn ids(&self) -> Vec<u16> {
+        self.competitor.ids()
+    }
+
+    pub fn gender(&self) -> Option<&Gender> {
+        self.competitor.gender()
+    }
+
+    /// Tell whether both results are the same performance, whatever the registration id they have been recorded for.
+    pub fn is_same_performance(&self, other: &CompetitionResult) -> bool {
+        self.competition == other.competition
+            && self.place == other.place
+            && self.result_type == other.result_type
+            && self.result == other.result
+            && self.details == other.details
+            && self.age_group == other.age_group
+            && self.gender() == other.gender()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::competition::competition::Competition;
     use crate::competition::competition_result::CompetitionResult;
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::gender::Gender;
+    use crate::competitor::individual_competitor::IndividualCompetitor;
+    use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
     use crate::result::age_group::AgeGroup;
     use crate::result::place::Place;
     use crate::result::result_type::ResultType;
     use crate::result::result_value::ResultValue;
 
     impl CompetitionResult {
-        pub fn create_test_instance() -> Self {
+        /// An overall result without age group of the competitor registered with `id`, who has no registrant.
+        pub fn create_test_instance(id: u16, competition: &str, place: &str, value: &str) -> Self {
             CompetitionResult::new(
-                1,
-                Competition::new("Competition"),
-                Place::from_string("1").unwrap(),
-                ResultType::from_string("Overall").unwrap(),
-                Some(ResultValue::from_string("00:14:99")),
+                Competition::new(competition),
+                Place::from_string(place).unwrap(),
+                ResultType::Overall,
+                Some(ResultValue::from_string(value)),
+                None,
                 None,
-                Some(AgeGroup::from_string("Senior")),
+                Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(id)),
             )
         }
+
+        pub fn with_result_type(self, result_type: ResultType) -> Self {
+            Self { result_type, ..self }
+        }
+
+        pub fn with_age_group(self, age_group: &str) -> Self {
+            Self { age_group: Some(AgeGroup::from_string(age_group)), ..self }
+        }
+
+        /// The competitor becomes the registrant with the same id.
+        pub fn with_individual_competitor(self, name: &str, gender: Option<&str>, age: Option<u8>) -> Self {
+            let gender = gender.map(|gender| Gender::from_string(gender).unwrap());
+            let competitor = IndividualCompetitor::new(self.ids()[0], name.to_string(), gender, age);
+            self.with_competitor(Competitor::IndividualCompetitor(competitor))
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use derive_getters::Getters;
+use deunicode::deunicode;
+use serde::Serialize;
+
+use crate::competition::competition::Competition;
+
+/// Words that split a same discipline into several competitions but are irrelevant to compare performances.
+const IGNORED_WORDS: [&str; 10] = ["male", "female", "men", "women", "mixed", "boys", "girls", "man", "woman", "gender"];
+
+/// Units that may be detached from the distance they qualify (e.g. "100 m").
+const DISTANCE_UNITS: [(&str, &str); 5] = [("m", "m"), ("meters", "m"), ("km", "km"), ("k", "km"), ("miles", "mi")];
+
+/// A [Discipline] is the canonical name of a [Competition].
+/// Different conventions name the same discipline differently (e.g. "100m Male" and "100 m"),
+/// so disciplines are what should be used to compare results across conventions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Getters, Serialize)]
+pub struct Discipline {
+    name: String,
+}
+
+impl Discipline {
+    pub fn from_competition(competition: &Competition) -> Self {
+        Self::from_string(competition.name())
+    }
+
+    pub fn from_string(competition_name: &str) -> Self {
+        let sanitized_name: String = deunicode(competition_name)
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '.' { c } else { ' ' })
+            .collect();
+
+        let mut words: Vec<String> = vec![];
+        for word in sanitized_name.split_whitespace() {
+            if IGNORED_WORDS.contains(&word) {
+                continue;
+            }
+            let unit = DISTANCE_UNITS.iter().find(|(alias, _)| *alias == word);
+            match (unit, words.last_mut()) {
+                (Some((_, unit)), Some(previous_word)) if previous_word.parse::<f32>().is_ok() => {
+                    previous_word.push_str(unit);
+                }
+                _ => { words.push(normalize_attached_unit(word)); }
+            }
+        }
+
+        Self { name: words.join(" ") }
+    }
+}
+
+/// Replace aliases of units attached to a distance (e.g. "10k") with their canonical form (e.g. "10km").
+fn normalize_attached_unit(word: &str) -> String {
+    let digits_count = word.chars().take_while(|c| c.is_ascii_digit() || *c == '.').count();
+    if digits_count == 0 || digits_count == word.len() {
+        return word.to_string();
+    }
+
+    let (distance, unit) = word.split_at(digits_count);
+    match DISTANCE_UNITS.iter().find(|(alias, _)| *alias == unit) {
+        None => { word.to_string() }
+        Some((_, unit)) => { format!("{distance}{unit}") }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition::Competition;
+    use crate::competition::discipline::Discipline;
+
+    #[test]
+    fn should_ignore_gender_and_case() {
+        assert_eq!(Discipline::from_string("100m Male"), Discipline::from_string("100M - women"));
+    }
+
+    #[test]
+    fn should_join_detached_unit() {
+        assert_eq!(Discipline::from_string("100 meters"), Discipline::from_string("100m"));
+    }
+
+    #[test]
+    fn should_normalize_unit_aliases() {
+        assert_eq!(Discipline::from_string("10k Standard"), Discipline::from_string("10 km (Standard)"));
+    }
+
+    #[test]
+    fn should_ignore_accents() {
+        assert_eq!(Discipline::from_competition(&Competition::new("Saut en hauteur")), Discipline::from_string("Saut en Hautéur"));
+    }
+
+    #[test]
+    fn should_keep_different_disciplines_apart() {
+        assert_ne!(Discipline::from_string("100m"), Discipline::from_string("400m"));
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+
+use crate::competition::discipline::Discipline;
+
+const DISCIPLINE_FAMILIES_FILE: &str = "discipline_families.json";
+
+/// Disciplines that are alike (e.g. track races, road races, freestyle) are gathered into a family,
+/// so that they can be rated together without mixing up disciplines that have nothing in common.
+#[derive(Debug, Default)]
+pub struct DisciplineFamilies {
+    families: HashMap<Discipline, String>,
+}
+
+impl DisciplineFamilies {
+    /// Load the families from `{folder}/discipline_families.json`, which lists the disciplines of each family.
+    /// If there is none, disciplines don't belong to any family.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{DISCIPLINE_FAMILIES_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No discipline families [filepath: {filepath}]");
+                return DisciplineFamilies::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        match serde_json::from_reader::<_, BTreeMap<String, Vec<String>>>(reader) {
+            Ok(raw_families) => {
+                let families = Self::from_raw_families(raw_families);
+                info!("Discipline families loaded [families: {}]", families.names().len());
+                families
+            }
+            Err(error) => {
+                warn!("Can't read discipline families, disciplines won't be gathered [filepath: {filepath}]");
+                warn!("{}", error);
+                DisciplineFamilies::default()
+            }
+        }
+    }
+
+    pub fn from_raw_families(raw_families: BTreeMap<String, Vec<String>>) -> Self {
+        let mut families = HashMap::new();
+        for (family, disciplines) in raw_families {
+            for discipline in disciplines {
+                let discipline = Discipline::from_string(&discipline);
+                if let Some(other_family) = families.insert(discipline.clone(), family.clone()) {
+                    warn!("Discipline listed in several families, the last one is kept [discipline: {}, families: {other_family}, {family}]", discipline.name());
+                }
+            }
+        }
+
+        Self { families }
+    }
+
+    pub fn family_of(&self, discipline: &Discipline) -> Option<&String> {
+        self.families.get(discipline)
+    }
+
+    /// The names of the families, sorted.
+    pub fn names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.families.values().collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::competition::discipline::Discipline;
+    use crate::competition::discipline_family::DisciplineFamilies;
+
+    #[test]
+    fn should_find_family_of_discipline() {
+        let families = DisciplineFamilies::from_raw_families(BTreeMap::from([
+            ("Track".to_string(), vec!["100m".to_string(), "400 m".to_string()]),
+            ("Road".to_string(), vec!["10k".to_string()]),
+        ]));
+
+        assert_eq!(families.family_of(&Discipline::from_string("400m Male")), Some(&"Track".to_string()));
+        assert_eq!(families.family_of(&Discipline::from_string("10 km")), Some(&"Road".to_string()));
+        assert_eq!(families.family_of(&Discipline::from_string("Freestyle")), None);
+        assert_eq!(families.names(), vec!["Road", "Track"]);
+    }
+}
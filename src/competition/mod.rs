@@ -1,2 +1,6 @@
 pub mod competition;
-pub mod competition_result;
\ No newline at end of file
+pub mod competition_result;
+pub mod discipline;
+pub mod result_store;
+pub mod performance;
+pub mod discipline_family;
\ No newline at end of file
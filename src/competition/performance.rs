@@ -0,0 +1,138 @@
+use derive_getters::Getters;
+
+use crate::competition::competition::Competition;
+use crate::competition::competition_result::CompetitionResult;
+use crate::competitor::competitor::Competitor;
+use crate::competitor::gender::Gender;
+use crate::result::age_group::AgeGroup;
+use crate::result::place::Place;
+use crate::result::result_type::ResultType;
+use crate::result::result_value::ResultValue;
+
+/// A [Performance] is what a competitor achieved in a competition, along with their overall and age group places.
+/// Results sheets list the same performance once per [ResultType], these [CompetitionResult]s are linked into a single performance
+/// so that it isn't counted twice.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Performance {
+    competition: Competition,
+    competitor: Competitor,
+    result: Option<ResultValue>,
+    details: Option<String>,
+    age_group: Option<AgeGroup>,
+    overall_place: Option<Place>,
+    age_group_place: Option<Place>,
+}
+
+impl Performance {
+    pub fn from_result(result: &CompetitionResult) -> Self {
+        let mut performance = Self {
+            competition: result.competition().clone(),
+            competitor: result.competitor().clone(),
+            result: result.result().clone(),
+            details: result.details().clone(),
+            age_group: result.age_group().clone(),
+            overall_place: None,
+            age_group_place: None,
+        };
+        *performance.place_mut(*result.result_type()) = Some(result.place().clone());
+        performance
+    }
+
+    /// Link the results of a convention into performances, keeping the order of the results.
+    pub fn link_results<'r>(results: impl IntoIterator<Item=&'r CompetitionResult>) -> Vec<Self> {
+        let mut performances: Vec<Performance> = vec![];
+        for result in results {
+            let linked = performances.iter_mut().any(|performance| performance.try_link(result));
+            if !linked {
+                performances.push(Performance::from_result(result));
+            }
+        }
+        performances
+    }
+
+    pub fn with_competitor(self, competitor: Competitor) -> Self {
+        Self { competitor, ..self }
+    }
+
+    pub fn with_details(self, details: Option<String>) -> Self {
+        Self { details, ..self }
+    }
+
+    pub fn place(&self, result_type: ResultType) -> Option<&Place> {
+        match result_type {
+            ResultType::Overall => { self.overall_place.as_ref() }
+            ResultType::AgeGroup => { self.age_group_place.as_ref() }
+        }
+    }
+
+    pub fn ids(&self) -> Vec<u16> {
+        self.competitor.ids()
+    }
+
+    pub fn gender(&self) -> Option<&Gender> {
+        self.competitor.gender()
+    }
+
+    fn place_mut(&mut self, result_type: ResultType) -> &mut Option<Place> {
+        match result_type {
+            ResultType::Overall => { &mut self.overall_place }
+            ResultType::AgeGroup => { &mut self.age_group_place }
+        }
+    }
+
+    /// Link the result to this performance if it is the same performance with a place of another type.
+    fn try_link(&mut self, result: &CompetitionResult) -> bool {
+        let is_same_performance = self.competition == *result.competition()
+            && self.competitor.ids() == result.ids()
+            && self.result == *result.result();
+        if !is_same_performance || self.place(*result.result_type()).is_some() {
+            return false;
+        }
+
+        *self.place_mut(*result.result_type()) = Some(result.place().clone());
+        if self.age_group.is_none() {
+            self.age_group = result.age_group().clone();
+        }
+        if self.details.is_none() {
+            self.details = result.details().clone();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::performance::Performance;
+    use crate::result::age_group::AgeGroup;
+    use crate::result::place::Place;
+    use crate::result::result_type::ResultType;
+
+    #[test]
+    fn should_link_overall_and_age_group_places() {
+        let results = [
+            CompetitionResult::create_test_instance(1, "100m", "3", "00:14.990"),
+            CompetitionResult::create_test_instance(2, "100m", "1", "00:13.990"),
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990").with_result_type(ResultType::AgeGroup).with_age_group("13-14"),
+        ];
+
+        let performances = Performance::link_results(&results);
+
+        assert_eq!(performances.len(), 2);
+        assert_eq!(performances[0].overall_place(), &Some(Place::Rank(3)));
+        assert_eq!(performances[0].age_group_place(), &Some(Place::Rank(1)));
+        assert_eq!(performances[0].age_group(), &Some(AgeGroup::from_string("13-14")));
+        assert_eq!(performances[1].age_group_place(), &None);
+    }
+
+    #[test]
+    fn should_not_link_different_performances() {
+        let results = [
+            CompetitionResult::create_test_instance(1, "100m", "3", "00:14.990"),
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:15.990").with_result_type(ResultType::AgeGroup),
+            CompetitionResult::create_test_instance(1, "100m", "3", "00:14.990"),
+        ];
+
+        assert_eq!(Performance::link_results(&results).len(), 3);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::competition::competition::Competition;
+use crate::competition::competition_result::CompetitionResult;
+use crate::result::age_group::AgeGroup;
+
+/// The results of a convention, indexed by registrant id (a team result being indexed under the id of each member), by competition and by age group
+/// so that they don't have to be scanned every time a subset is needed.
+#[derive(Debug, Default)]
+pub struct ResultStore {
+    results: Vec<CompetitionResult>,
+    by_id: HashMap<u16, Vec<usize>>,
+    by_competition: HashMap<Competition, Vec<usize>>,
+    by_age_group: HashMap<AgeGroup, Vec<usize>>,
+}
+
+impl ResultStore {
+    pub fn new(results: Vec<CompetitionResult>) -> Self {
+        let mut by_id: HashMap<u16, Vec<usize>> = HashMap::new();
+        let mut by_competition: HashMap<Competition, Vec<usize>> = HashMap::new();
+        let mut by_age_group: HashMap<AgeGroup, Vec<usize>> = HashMap::new();
+        for (index, result) in results.iter().enumerate() {
+            for id in result.ids() {
+                by_id.entry(id).or_default().push(index);
+            }
+            by_competition.entry(result.competition().clone()).or_default().push(index);
+            if let Some(age_group) = result.age_group() {
+                by_age_group.entry(age_group.clone()).or_default().push(index);
+            }
+        }
+
+        Self { results, by_id, by_competition, by_age_group }
+    }
+
+    pub fn results(&self) -> &[CompetitionResult] {
+        &self.results
+    }
+
+    /// Every competition that has results, in no particular order.
+    pub fn competitions(&self) -> impl Iterator<Item=&Competition> {
+        self.by_competition.keys()
+    }
+
+    pub fn by_id(&self, id: u16) -> impl Iterator<Item=&CompetitionResult> {
+        self.get_all(self.by_id.get(&id))
+    }
+
+    pub fn by_competition(&self, competition: &Competition) -> impl Iterator<Item=&CompetitionResult> {
+        self.get_all(self.by_competition.get(competition))
+    }
+
+    pub fn by_age_group(&self, age_group: &AgeGroup) -> impl Iterator<Item=&CompetitionResult> {
+        self.get_all(self.by_age_group.get(age_group))
+    }
+
+    fn get_all<'s>(&'s self, indices: Option<&'s Vec<usize>>) -> impl Iterator<Item=&'s CompetitionResult> {
+        indices.into_iter()
+            .flatten()
+            .map(|index| &self.results[*index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::competition::competition::Competition;
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::result_store::ResultStore;
+    use crate::result::age_group::AgeGroup;
+
+    #[test]
+    fn should_index_results() {
+        let store = ResultStore::new(vec![
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990").with_age_group("Senior"),
+            CompetitionResult::create_test_instance(1, "Marathon", "1", "00:14.990").with_age_group("Senior"),
+            CompetitionResult::create_test_instance(2, "100m", "1", "00:14.990").with_age_group("Junior"),
+        ]);
+
+        assert_eq!(store.results().len(), 3);
+        assert_eq!(store.by_id(1).count(), 2);
+        assert_eq!(store.by_id(3).count(), 0);
+        assert_eq!(store.by_competition(&Competition::new("100m")).flat_map(|result| result.ids()).collect::<Vec<u16>>(), vec![1, 2]);
+        assert_eq!(store.by_age_group(&AgeGroup::from_string("Junior")).flat_map(|result| result.ids()).collect::<Vec<u16>>(), vec![2]);
+        assert_eq!(store.competitions().count(), 2);
+    }
+
+    /// Compare gathering the results of every registrant and of every competition using the store with scanning every result each time.
+    /// Run it with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_results_gathering() {
+        const CONVENTIONS_COUNT: u16 = 15;
+        const REGISTRANTS_COUNT: u16 = 1000;
+        const RESULTS_PER_REGISTRANT: u16 = 5;
+
+        let conventions_results: Vec<Vec<CompetitionResult>> = (0..CONVENTIONS_COUNT)
+            .map(|_| {
+                (0..REGISTRANTS_COUNT)
+                    .flat_map(|id| (0..RESULTS_PER_REGISTRANT)
+                        .map(move |competition| CompetitionResult::create_test_instance(id, &format!("Competition{competition}"), "1", "00:14.990")))
+                    .collect()
+            })
+            .collect();
+        let competitions: Vec<Competition> = (0..RESULTS_PER_REGISTRANT).map(|competition| Competition::new(&format!("Competition{competition}"))).collect();
+
+        let start = Instant::now();
+        let mut scanned_results = vec![];
+        for results in &conventions_results {
+            for id in 0..REGISTRANTS_COUNT {
+                scanned_results.push(results.iter().filter(|result| result.ids().contains(&id)).cloned().collect::<Vec<CompetitionResult>>());
+            }
+            for competition in &competitions {
+                scanned_results.push(results.iter().filter(|result| result.competition() == competition).cloned().collect::<Vec<CompetitionResult>>());
+            }
+        }
+        let scan_duration = start.elapsed();
+
+        let start = Instant::now();
+        let mut stored_results = vec![];
+        for results in conventions_results {
+            let store = ResultStore::new(results);
+            for id in 0..REGISTRANTS_COUNT {
+                stored_results.push(store.by_id(id).cloned().collect::<Vec<CompetitionResult>>());
+            }
+            for competition in &competitions {
+                stored_results.push(store.by_competition(competition).cloned().collect::<Vec<CompetitionResult>>());
+            }
+        }
+        let store_duration = start.elapsed();
+
+        println!("Scanning results: {scan_duration:?}, indexing then getting results: {store_duration:?}");
+        assert_eq!(scanned_results, stored_results);
+        assert!(store_duration < scan_duration);
+    }
+}
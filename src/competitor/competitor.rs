@@ -1,3 +1,4 @@
+use crate::competitor::gender::Gender;
 use crate::competitor::individual_competitor::IndividualCompetitor;
 use crate::competitor::team::Team;
 use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
@@ -8,4 +9,31 @@ pub enum Competitor {
     IndividualCompetitor(IndividualCompetitor),
     Team(Team),
     UnknownIndividualCompetitor(UnknownIndividualCompetitor),
+}
+
+impl Competitor {
+    /// The registration ids of the competitor, one for an individual and one for each member of a team.
+    pub fn ids(&self) -> Vec<u16> {
+        match self {
+            Competitor::IndividualCompetitor(competitor) => { vec![*competitor.id()] }
+            Competitor::Team(team) => { team.members().iter().flat_map(Competitor::ids).collect() }
+            Competitor::UnknownIndividualCompetitor(competitor) => { vec![*competitor.id()] }
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Competitor::IndividualCompetitor(competitor) => { Some(competitor.name()) }
+            Competitor::Team(team) => { Some(team.name()) }
+            Competitor::UnknownIndividualCompetitor(competitor) => { competitor.name().as_deref() }
+        }
+    }
+
+    pub fn gender(&self) -> Option<&Gender> {
+        match self {
+            Competitor::IndividualCompetitor(competitor) => { competitor.gender().as_ref() }
+            Competitor::Team(team) => { team.gender().as_ref() }
+            Competitor::UnknownIndividualCompetitor(competitor) => { competitor.gender().as_ref() }
+        }
+    }
 }
\ No newline at end of file
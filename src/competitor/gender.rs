@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 const AUTHORIZED_GENDER: [&str; 4] = ["Male", "Female", "(mixed)", "(n/a)"];
 
 /// A gender may only be one of [AUTHORIZED_GENDER].
@@ -16,4 +18,10 @@ impl Gender {
 
         Err(format!("Invalid gender [gender: {}]", gender))
     }
-}
\ No newline at end of file
+}
+
+impl Display for Gender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.gender)
+    }
+}
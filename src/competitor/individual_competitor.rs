@@ -2,17 +2,22 @@ use derive_getters::Getters;
 
 use crate::competitor::gender::Gender;
 
+/// A registered competitor, with their gender and age at the competition as written in the results sheet.
 #[derive(Debug, Getters, PartialEq, Eq, Hash, Clone)]
 pub struct IndividualCompetitor {
     id: u16,
     name: String,
-    gender: Gender,
-    age: u8,
+    gender: Option<Gender>,
+    age: Option<u8>,
 }
 
 impl IndividualCompetitor {
-    pub fn new(id: u16, name: String, gender: Gender, age: u8) -> Self {
+    pub fn new(id: u16, name: String, gender: Option<Gender>, age: Option<u8>) -> Self {
         Self { id, name, gender, age }
     }
+
+    pub fn with_name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
 }
 
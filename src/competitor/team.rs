@@ -1,15 +1,79 @@
+use std::collections::HashMap;
+
 use derive_getters::Getters;
+use itertools::Itertools;
+use serde::Serialize;
 
+use crate::competition::performance::Performance;
+use crate::competition::result_store::ResultStore;
 use crate::competitor::competitor::Competitor;
+use crate::competitor::gender::Gender;
+use crate::convention::convention::Convention;
+use crate::export::{write_csv_file, write_json_file, write_markdown_file};
+
+const TEAM_RESULTS_FILENAME: &str = "team_results";
 
 #[derive(Debug, Getters, PartialEq, Eq, Hash, Clone)]
 pub struct Team {
     name: String,
+    gender: Option<Gender>,
     members: Vec<Competitor>,
 }
 
 impl Team {
-    pub fn new(name: String, members: Vec<Competitor>) -> Self {
-        Self { name, members }
+    pub fn new(name: String, gender: Option<Gender>, members: Vec<Competitor>) -> Self {
+        Self { name, gender, members }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+struct ExportedTeamResult<'a> {
+    convention: &'a str,
+    competition: String,
+    overall_place: String,
+    age_group_place: String,
+    result: String,
+    team: String,
+    gender: String,
+    members: String,
+}
+
+/// Export every relay, team and pairs result along with everyone who took part in it,
+/// as JSON, CSV and Markdown in `{folder}/team_results.{json,csv,md}`.
+/// A team ranked both overall and in its age group is listed once, with both places.
+/// Unregistered members are shown by their id.
+pub fn export_team_results(folder: &str, conventions_results: &HashMap<&Convention, ResultStore>) -> Result<(), ()> {
+    let mut conventions: Vec<&&Convention> = conventions_results.keys().collect();
+    conventions.sort_by(|a, b| a.compare_chronologically(b));
+
+    let mut exported_results = vec![];
+    for convention in conventions {
+        let team_results = conventions_results[convention].results().iter()
+            .filter(|result| matches!(result.competitor(), Competitor::Team(_)));
+        for performance in Performance::link_results(team_results) {
+            let Competitor::Team(team) = performance.competitor() else { continue; };
+            let members: Vec<String> = team.members().iter()
+                .map(|member| match member.name() {
+                    None => { format!("#{}", member.ids().iter().join(", #")) }
+                    Some(name) => { name.to_string() }
+                })
+                .collect();
+            exported_results.push(ExportedTeamResult {
+                convention: convention.tag(),
+                competition: performance.competition().name().clone(),
+                overall_place: performance.overall_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+                age_group_place: performance.age_group_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+                result: performance.result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                team: team.name().clone(),
+                gender: team.gender().as_ref().map(|gender| gender.to_string()).unwrap_or_default(),
+                members: members.join(", "),
+            });
+        }
+    }
+
+    let json_result = write_json_file(folder, &format!("{TEAM_RESULTS_FILENAME}.json"), &exported_results);
+    let csv_result = write_csv_file(folder, &format!("{TEAM_RESULTS_FILENAME}.csv"), &exported_results);
+    let markdown_result = write_markdown_file(folder, &format!("{TEAM_RESULTS_FILENAME}.md"), "Team results", &exported_results);
+
+    json_result.and(csv_result).and(markdown_result)
+}
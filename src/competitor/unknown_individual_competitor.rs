@@ -1,13 +1,25 @@
 use derive_getters::Getters;
 
-/// A competitor that takes part only to team competitions may be unknown (no name, no gender, no age)
-#[derive(Debug, Getters, PartialEq, Eq, Hash, Copy, Clone)]
+use crate::competitor::gender::Gender;
+
+/// A competitor whose id has no registrant, e.g. one that takes part only to team competitions.
+/// Their name, gender and age are only known from the results sheet, if ever.
+#[derive(Debug, Getters, PartialEq, Eq, Hash, Clone)]
 pub struct UnknownIndividualCompetitor {
     id: u16,
+    name: Option<String>,
+    gender: Option<Gender>,
+    age: Option<u8>,
 }
 
 impl UnknownIndividualCompetitor {
     pub fn new(id: u16) -> Self {
-        Self { id }
+        Self { id, name: None, gender: None, age: None }
+    }
+
+    /// The competitor as written in the results sheet, an empty name or a zero age being unknown.
+    pub fn with_sheet_details(self, name: &str, gender: Option<Gender>, age: u8) -> Self {
+        let name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+        Self { name, gender, age: Some(age).filter(|age| *age > 0), ..self }
     }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, Write};
 
+use chrono::NaiveDate;
 use derive_getters::Getters;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
@@ -11,16 +13,75 @@ use serde_json::json;
 use crate::utils::create_folder;
 
 const CONVENTIONS_FILE: &str = "conventions.json";
+const CONVENTIONS_OVERRIDES_FILE: &str = "conventions_overrides.json";
 
 #[derive(Debug, Clone, Getters, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct Convention {
     tag: String,
     name: String,
+    #[serde(default)]
+    start_date: Option<NaiveDate>,
+    #[serde(default)]
+    end_date: Option<NaiveDate>,
+    #[serde(default)]
+    location: Option<String>,
 }
 
 impl Convention {
-    pub fn new(tag: String, name: String) -> Self {
-        Self { tag, name }
+    pub fn new(tag: String, name: String, start_date: Option<NaiveDate>, end_date: Option<NaiveDate>, location: Option<String>) -> Self {
+        Self { tag, name, start_date, end_date, location }
+    }
+
+    /// Conventions are sorted by start date. Conventions without date come last, sorted by tag.
+    pub fn compare_chronologically(&self, other: &Self) -> Ordering {
+        match (self.start_date, other.start_date) {
+            (Some(self_date), Some(other_date)) => { self_date.cmp(&other_date).then_with(|| self.tag.cmp(&other.tag)) }
+            (Some(_), None) => { Ordering::Less }
+            (None, Some(_)) => { Ordering::Greater }
+            (None, None) => { self.tag.cmp(&other.tag) }
+        }
+    }
+
+    /// Conventions downloaded before their details were scraped have neither dates nor location.
+    pub fn has_no_details(&self) -> bool {
+        self.start_date.is_none() && self.end_date.is_none() && self.location.is_none()
+    }
+
+    /// Fill in the details that are missing, keeping the known ones.
+    pub fn with_missing_details(self, start_date: Option<NaiveDate>, end_date: Option<NaiveDate>, location: Option<String>) -> Self {
+        Self {
+            start_date: self.start_date.or(start_date),
+            end_date: self.end_date.or(end_date),
+            location: self.location.or(location),
+            ..self
+        }
+    }
+}
+
+/// Manual details of a convention, which take precedence over the downloaded ones.
+/// A convention that has been imported without being downloaded can be declared this way, provided a name is given.
+#[derive(Debug, Deserialize)]
+struct ConventionOverride {
+    tag: String,
+    name: Option<String>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    location: Option<String>,
+}
+
+impl ConventionOverride {
+    fn apply(self, convention: Option<Convention>) -> Option<Convention> {
+        let (name, start_date, end_date, location) = match convention {
+            None => { (self.name?, None, None, None) }
+            Some(convention) => { (convention.name, convention.start_date, convention.end_date, convention.location) }
+        };
+        Some(Convention::new(
+            self.tag,
+            name,
+            self.start_date.or(start_date),
+            self.end_date.or(end_date),
+            self.location.or(location),
+        ))
     }
 }
 
@@ -70,12 +131,58 @@ pub fn load_conventions_from_folder(folder: &str, conventions_tag: &Vec<String>)
     conventions_with_data
 }
 
-pub fn compute_conventions_to_download<'a>(already_downloaded_conventions: &HashMap<String, Convention>, required_conventions: &'a Vec<String>) -> HashSet<&'a String> {
+/// Apply the overrides from `{folder}/conventions_overrides.json` to the loaded conventions.
+/// Overrides of conventions that are not required or whose data does not exist are ignored.
+pub fn apply_conventions_overrides(folder: &str, conventions: &mut HashMap<String, Convention>, conventions_tag: &[String]) {
+    let filepath = format!("{folder}/{CONVENTIONS_OVERRIDES_FILE}");
+    let file = match File::open(&filepath) {
+        Ok(file) => { file }
+        Err(_) => {
+            debug!("No convention override [filepath: {filepath}]");
+            return;
+        }
+    };
+    let reader = BufReader::new(file);
+
+    let overrides: Vec<ConventionOverride> = match serde_json::from_reader(reader) {
+        Ok(overrides) => { overrides }
+        Err(error) => {
+            warn!("Can't read conventions overrides, they will be ignored [filepath: {filepath}]");
+            warn!("{}", error);
+            return;
+        }
+    };
+    for convention_override in overrides {
+        let tag = convention_override.tag.clone();
+        if !conventions_tag.contains(&tag) {
+            continue;
+        }
+        let convention = conventions.remove(&tag);
+        let is_imported = convention.is_none();
+        match convention_override.apply(convention) {
+            None => { warn!("Can't import convention without name [convention: {tag}]"); }
+            Some(convention) => {
+                if is_imported && !check_convention_data_exists(folder, &convention) {
+                    continue;
+                }
+                debug!("Convention overridden [convention: {:?}]", convention);
+                conventions.insert(tag, convention);
+            }
+        }
+    }
+}
+
+/// The required conventions that haven't been downloaded yet, or all of them when they have to be refreshed,
+/// e.g. because their results changed on UDA since they were downloaded.
+pub fn compute_conventions_to_download<'a>(already_downloaded_conventions: &HashMap<String, Convention>, required_conventions: &'a Vec<String>, refresh: bool) -> HashSet<&'a String> {
     let mut conventions_to_download = HashSet::new();
 
     for convention_tag in required_conventions {
         if !already_downloaded_conventions.contains_key(convention_tag) {
             conventions_to_download.insert(convention_tag);
+        } else if refresh {
+            debug!("Convention already exists locally, it will be downloaded again [convention:{convention_tag}]");
+            conventions_to_download.insert(convention_tag);
         } else {
             debug!("Convention already exists locally [convention:{convention_tag}]");
         }
@@ -85,6 +192,19 @@ pub fn compute_conventions_to_download<'a>(already_downloaded_conventions: &Hash
     conventions_to_download
 }
 
+/// The cached conventions whose details are missing and that won't be downloaded again, so that only their details are fetched.
+/// Tags are owned since the cached conventions are updated with the fetched details.
+pub fn compute_conventions_to_backfill(already_downloaded_conventions: &HashMap<String, Convention>, conventions_to_download: &HashSet<&String>) -> HashSet<String> {
+    let conventions_to_backfill: HashSet<String> = already_downloaded_conventions.iter()
+        .filter(|(tag, convention)| convention.has_no_details() && !conventions_to_download.contains(tag))
+        .map(|(tag, _)| tag.clone())
+        .collect();
+    if !conventions_to_backfill.is_empty() {
+        info!("Conventions to backfill details of: {:?}", conventions_to_backfill);
+    }
+    conventions_to_backfill
+}
+
 fn check_convention_data_exists(folder: &str, convention: &Convention) -> bool {
     let mut errors = vec![];
     let results_file_path = format!("{folder}/{}/results.xls", convention.tag());
@@ -108,4 +228,96 @@ fn check_convention_data_exists(folder: &str, convention: &Convention) -> bool {
         }
         false
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::collections::{HashMap, HashSet};
+
+    use chrono::NaiveDate;
+
+    use crate::convention::convention::{compute_conventions_to_backfill, compute_conventions_to_download, Convention, ConventionOverride};
+
+    impl Convention {
+        pub fn create_test_instance(tag: &str) -> Self {
+            Convention::new(tag.to_string(), tag.to_uppercase(), None, None, None)
+        }
+    }
+
+    fn create_convention(tag: &str, start_date: Option<NaiveDate>) -> Convention {
+        Convention::new(tag.to_string(), tag.to_string(), start_date, start_date, None)
+    }
+
+    #[test]
+    fn should_sort_conventions_by_date_then_undated_by_tag() {
+        let older = create_convention("zzz", NaiveDate::from_ymd_opt(2020, 7, 16));
+        let newer = create_convention("aaa", NaiveDate::from_ymd_opt(2022, 7, 16));
+        let undated = create_convention("bbb", None);
+
+        assert_eq!(older.compare_chronologically(&newer), Ordering::Less);
+        assert_eq!(newer.compare_chronologically(&undated), Ordering::Less);
+        assert_eq!(undated.compare_chronologically(&create_convention("ccc", None)), Ordering::Less);
+    }
+
+    #[test]
+    fn should_override_only_given_details() {
+        let convention = Convention::new("cfm2023".to_string(), "CFM 2023".to_string(), None, None, Some("Paris".to_string()));
+        let convention_override = ConventionOverride {
+            tag: "cfm2023".to_string(),
+            name: None,
+            start_date: NaiveDate::from_ymd_opt(2023, 5, 18),
+            end_date: None,
+            location: None,
+        };
+
+        let convention = convention_override.apply(Some(convention)).unwrap();
+
+        assert_eq!(convention.name(), "CFM 2023");
+        assert_eq!(convention.start_date(), &NaiveDate::from_ymd_opt(2023, 5, 18));
+        assert_eq!(convention.location(), &Some("Paris".to_string()));
+    }
+
+    #[test]
+    fn should_not_import_convention_without_name() {
+        let convention_override = ConventionOverride {
+            tag: "cfm2023".to_string(),
+            name: None,
+            start_date: None,
+            end_date: None,
+            location: None,
+        };
+
+        assert_eq!(convention_override.apply(None), None);
+    }
+
+    #[test]
+    fn should_download_cached_conventions_again_when_refreshing() {
+        let cached_conventions = HashMap::from([("cfm2022".to_string(), Convention::create_test_instance("cfm2022"))]);
+        let required_conventions = vec!["cfm2022".to_string(), "cfm2023".to_string()];
+
+        let conventions_to_download = compute_conventions_to_download(&cached_conventions, &required_conventions, false);
+        let conventions_to_refresh = compute_conventions_to_download(&cached_conventions, &required_conventions, true);
+
+        assert_eq!(conventions_to_download, HashSet::from([&required_conventions[1]]));
+        assert_eq!(conventions_to_refresh, HashSet::from([&required_conventions[0], &required_conventions[1]]));
+    }
+
+    #[test]
+    fn should_only_backfill_cached_conventions_without_details() {
+        let dated_convention = create_convention("cfm2021", NaiveDate::from_ymd_opt(2021, 5, 13));
+        let cached_conventions = HashMap::from([
+            ("cfm2021".to_string(), dated_convention.clone()),
+            ("cfm2022".to_string(), Convention::create_test_instance("cfm2022")),
+            ("cfm2023".to_string(), Convention::create_test_instance("cfm2023")),
+        ]);
+        let cfm2023 = "cfm2023".to_string();
+
+        let conventions_to_backfill = compute_conventions_to_backfill(&cached_conventions, &HashSet::from([&cfm2023]));
+
+        assert_eq!(conventions_to_backfill, HashSet::from(["cfm2022".to_string()]));
+        let backfilled = dated_convention.with_missing_details(NaiveDate::from_ymd_opt(2021, 5, 12), None, Some("Paris, France".to_string()));
+        assert_eq!(backfilled.start_date(), &NaiveDate::from_ymd_opt(2021, 5, 13));
+        assert_eq!(backfilled.location(), &Some("Paris, France".to_string()));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::fs;
+
+use chrono::{DateTime, Utc};
+use derive_getters::Getters;
+use sha2::{Digest, Sha256};
+
+use crate::convention::convention::Convention;
+
+/// The data files of a convention as they were when they were read: their SHA-256 hashes tell whether
+/// the convention changed since it was last ingested, and the download time is when the results file was written.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct ConventionSnapshot {
+    results_hash: String,
+    registrants_hash: String,
+    downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl ConventionSnapshot {
+    pub fn new(results_hash: String, registrants_hash: String, downloaded_at: Option<DateTime<Utc>>) -> Self {
+        Self { results_hash, registrants_hash, downloaded_at }
+    }
+
+    /// Take a snapshot of `{folder}/{tag}/results.xls` and `{folder}/{tag}/registrants.csv`.
+    pub fn from_folder(folder: &str, convention: &Convention) -> Result<Self, String> {
+        let results_filepath = format!("{folder}/{}/results.xls", convention.tag());
+        let registrants_filepath = format!("{folder}/{}/registrants.csv", convention.tag());
+        let downloaded_at = fs::metadata(&results_filepath)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+
+        Ok(Self {
+            results_hash: hash_file(&results_filepath)?,
+            registrants_hash: hash_file(&registrants_filepath)?,
+            downloaded_at,
+        })
+    }
+
+    /// Whether the data files are the same as the ones of the other snapshot, whenever they were downloaded.
+    pub fn has_same_content(&self, other: &Self) -> bool {
+        self.results_hash == other.results_hash && self.registrants_hash == other.registrants_hash
+    }
+}
+
+fn hash_file(filepath: &str) -> Result<String, String> {
+    let content = fs::read(filepath).map_err(|error| format!("Can't read file to hash [filepath: {filepath}]: {error}"))?;
+    Ok(format!("{:x}", Sha256::digest(content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::convention::convention_snapshot::ConventionSnapshot;
+
+    #[test]
+    fn should_ignore_download_time_when_comparing_content() {
+        let snapshot = ConventionSnapshot::new("abc".to_string(), "def".to_string(), Some(Utc.with_ymd_and_hms(2023, 5, 18, 10, 0, 0).unwrap()));
+        let downloaded_again = ConventionSnapshot::new("abc".to_string(), "def".to_string(), None);
+        let changed = ConventionSnapshot::new("abd".to_string(), "def".to_string(), None);
+
+        assert!(snapshot.has_same_content(&downloaded_again));
+        assert!(!snapshot.has_same_content(&changed));
+    }
+}
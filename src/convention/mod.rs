@@ -1 +1,2 @@
-pub mod convention;
\ No newline at end of file
+pub mod convention;
+pub mod convention_snapshot;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use deunicode::deunicode;
+
+use crate::country::iso_countries::{ISO_COUNTRIES, IsoCountry};
+
+thread_local!(static COUNTRIES_BY_ALIAS: HashMap<String, &'static IsoCountry> = index_countries_by_alias());
+
+/// A [Country] is identified by its ISO 3166 alpha-2 code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Country {
+    code: &'static str,
+}
+
+impl Country {
+    /// Tries and find a country from its name, in several languages, its historic name or its ISO 3166 code.
+    /// Case, accents and punctuation are ignored.
+    pub fn from_string(country: &str) -> Result<Self, String> {
+        let alias = sanitize_alias(country);
+        match COUNTRIES_BY_ALIAS.with(|countries| countries.get(&alias).copied()) {
+            None => { Err(format!("Unknown country [country: {}]", country)) }
+            Some(iso_country) => { Ok(Self { code: iso_country.alpha2 }) }
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        self.code
+    }
+}
+
+impl Display for Country {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+fn index_countries_by_alias() -> HashMap<String, &'static IsoCountry> {
+    let mut countries = HashMap::new();
+    for iso_country in &ISO_COUNTRIES {
+        let aliases = [iso_country.alpha2, iso_country.alpha3, iso_country.name].into_iter()
+            .chain(iso_country.aliases.iter().copied());
+        for alias in aliases {
+            countries.insert(sanitize_alias(alias), iso_country);
+        }
+    }
+    countries
+}
+
+fn sanitize_alias(alias: &str) -> String {
+    let alias: String = deunicode(alias)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    alias.split_whitespace()
+        .filter(|word| *word != "the")
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::country::country::Country;
+
+    #[test]
+    fn should_find_country_from_english_name() {
+        assert_eq!(Country::from_string("France").unwrap().code(), "FR");
+    }
+
+    #[test]
+    fn should_find_country_from_translated_name() {
+        assert_eq!(Country::from_string("Allemagne").unwrap().code(), "DE");
+        assert_eq!(Country::from_string("Österreich").unwrap().code(), "AT");
+        assert_eq!(Country::from_string("République tchèque").unwrap().code(), "CZ");
+    }
+
+    #[test]
+    fn should_find_country_from_historic_name() {
+        assert_eq!(Country::from_string("Swaziland").unwrap().code(), "SZ");
+        assert_eq!(Country::from_string("Soviet Union").unwrap().code(), "SU");
+    }
+
+    #[test]
+    fn should_find_country_from_code() {
+        assert_eq!(Country::from_string("usa").unwrap().code(), "US");
+        assert_eq!(Country::from_string("JP").unwrap().code(), "JP");
+    }
+
+    #[test]
+    fn should_ignore_case_punctuation_and_article() {
+        assert_eq!(Country::from_string("  the NETHERLANDS ").unwrap().code(), "NL");
+        assert_eq!(Country::from_string("U.S.A.").unwrap().code(), "US");
+    }
+
+    #[test]
+    fn should_not_find_unknown_country() {
+        assert!(Country::from_string("Atlantis").is_err());
+    }
+}
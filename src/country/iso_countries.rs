@@ -0,0 +1,271 @@
+/// A country as defined by ISO 3166-1, or a former country as defined by ISO 3166-3.
+/// Aliases are other names the country may be known as: translations, native names, short names and historic names.
+pub struct IsoCountry {
+    pub alpha2: &'static str,
+    pub alpha3: &'static str,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+}
+
+const fn country(alpha2: &'static str, alpha3: &'static str, name: &'static str, aliases: &'static [&'static str]) -> IsoCountry {
+    IsoCountry { alpha2, alpha3, name, aliases }
+}
+
+pub const ISO_COUNTRIES: [IsoCountry; 255] = [
+    country("AD", "AND", "Andorra", &["Andorre"]),
+    country("AE", "ARE", "United Arab Emirates", &["UAE", "Emirats arabes unis", "Vereinigte Arabische Emirate", "Emiratos Arabes Unidos"]),
+    country("AF", "AFG", "Afghanistan", &[]),
+    country("AG", "ATG", "Antigua and Barbuda", &["Antigua-et-Barbuda"]),
+    country("AI", "AIA", "Anguilla", &[]),
+    country("AL", "ALB", "Albania", &["Albanie", "Albanien", "Shqiperia"]),
+    country("AM", "ARM", "Armenia", &["Armenie", "Armenien"]),
+    country("AO", "AGO", "Angola", &[]),
+    country("AQ", "ATA", "Antarctica", &["Antarctique"]),
+    country("AR", "ARG", "Argentina", &["Argentine", "Argentinien"]),
+    country("AS", "ASM", "American Samoa", &["Samoa americaines"]),
+    country("AT", "AUT", "Austria", &["Autriche", "Osterreich", "Oesterreich"]),
+    country("AU", "AUS", "Australia", &["Australie", "Australien"]),
+    country("AW", "ABW", "Aruba", &[]),
+    country("AX", "ALA", "Aland Islands", &["Aland", "Iles Aland"]),
+    country("AZ", "AZE", "Azerbaijan", &["Azerbaidjan", "Aserbaidschan"]),
+    country("BA", "BIH", "Bosnia and Herzegovina", &["Bosnia", "Bosnie-Herzegovine", "Bosnien und Herzegowina", "Bosna i Hercegovina"]),
+    country("BB", "BRB", "Barbados", &["Barbade"]),
+    country("BD", "BGD", "Bangladesh", &[]),
+    country("BE", "BEL", "Belgium", &["Belgique", "Belgien", "Belgie", "Belgica"]),
+    country("BF", "BFA", "Burkina Faso", &["Upper Volta", "Haute-Volta"]),
+    country("BG", "BGR", "Bulgaria", &["Bulgarie", "Bulgarien", "Balgariya"]),
+    country("BH", "BHR", "Bahrain", &["Bahrein"]),
+    country("BI", "BDI", "Burundi", &[]),
+    country("BJ", "BEN", "Benin", &["Dahomey"]),
+    country("BL", "BLM", "Saint Barthelemy", &[]),
+    country("BM", "BMU", "Bermuda", &["Bermudes"]),
+    country("BN", "BRN", "Brunei Darussalam", &["Brunei"]),
+    country("BO", "BOL", "Bolivia", &["Bolivie", "Bolivien", "Plurinational State of Bolivia"]),
+    country("BQ", "BES", "Bonaire, Sint Eustatius and Saba", &["Caribbean Netherlands", "Bonaire"]),
+    country("BR", "BRA", "Brazil", &["Bresil", "Brasilien", "Brasil"]),
+    country("BS", "BHS", "Bahamas", &["The Bahamas"]),
+    country("BT", "BTN", "Bhutan", &["Bhoutan"]),
+    country("BV", "BVT", "Bouvet Island", &[]),
+    country("BW", "BWA", "Botswana", &[]),
+    country("BY", "BLR", "Belarus", &["Byelorussia", "Belorussia", "Bielorussie", "Weissrussland", "Bielorrusia"]),
+    country("BZ", "BLZ", "Belize", &["British Honduras"]),
+    country("CA", "CAN", "Canada", &["Kanada"]),
+    country("CC", "CCK", "Cocos (Keeling) Islands", &["Cocos Islands"]),
+    country("CD", "COD", "Democratic Republic of the Congo", &["DR Congo", "DRC", "Congo-Kinshasa", "Zaire", "Republique democratique du Congo"]),
+    country("CF", "CAF", "Central African Republic", &["Republique centrafricaine"]),
+    country("CG", "COG", "Congo", &["Republic of the Congo", "Congo-Brazzaville"]),
+    country("CH", "CHE", "Switzerland", &["Suisse", "Schweiz", "Svizzera", "Suiza", "Confoederatio Helvetica"]),
+    country("CI", "CIV", "Cote d'Ivoire", &["Ivory Coast"]),
+    country("CK", "COK", "Cook Islands", &["Iles Cook"]),
+    country("CL", "CHL", "Chile", &["Chili"]),
+    country("CM", "CMR", "Cameroon", &["Cameroun", "Kamerun"]),
+    country("CN", "CHN", "China", &["Chine", "People's Republic of China", "PRC", "Zhongguo", "Zhong Guo"]),
+    country("CO", "COL", "Colombia", &["Colombie", "Kolumbien"]),
+    country("CR", "CRI", "Costa Rica", &[]),
+    country("CU", "CUB", "Cuba", &["Kuba"]),
+    country("CV", "CPV", "Cabo Verde", &["Cape Verde", "Cap-Vert"]),
+    country("CW", "CUW", "Curacao", &[]),
+    country("CX", "CXR", "Christmas Island", &[]),
+    country("CY", "CYP", "Cyprus", &["Chypre", "Zypern", "Chipre"]),
+    country("CZ", "CZE", "Czechia", &["Czech Republic", "Republique tcheque", "Tchequie", "Tschechien", "Tschechische Republik", "Republica Checa", "Cesko", "Ceska republika"]),
+    country("DE", "DEU", "Germany", &["Allemagne", "Deutschland", "Alemania", "Germania", "Duitsland", "Niemcy", "Federal Republic of Germany", "West Germany", "BRD"]),
+    country("DJ", "DJI", "Djibouti", &[]),
+    country("DK", "DNK", "Denmark", &["Danemark", "Daenemark", "Danmark", "Dinamarca"]),
+    country("DM", "DMA", "Dominica", &["Dominique"]),
+    country("DO", "DOM", "Dominican Republic", &["Republique dominicaine", "Republica Dominicana"]),
+    country("DZ", "DZA", "Algeria", &["Algerie", "Algerien"]),
+    country("EC", "ECU", "Ecuador", &["Equateur"]),
+    country("EE", "EST", "Estonia", &["Estonie", "Estland", "Eesti"]),
+    country("EG", "EGY", "Egypt", &["Egypte", "Agypten", "Aegypten"]),
+    country("EH", "ESH", "Western Sahara", &["Sahara occidental"]),
+    country("ER", "ERI", "Eritrea", &["Erythree"]),
+    country("ES", "ESP", "Spain", &["Espagne", "Spanien", "Espana", "Spagna"]),
+    country("ET", "ETH", "Ethiopia", &["Ethiopie", "Athiopien"]),
+    country("FI", "FIN", "Finland", &["Finlande", "Finnland", "Suomi", "Finlandia"]),
+    country("FJ", "FJI", "Fiji", &["Fidji"]),
+    country("FK", "FLK", "Falkland Islands (Malvinas)", &["Falkland Islands", "Malvinas"]),
+    country("FM", "FSM", "Micronesia", &["Federated States of Micronesia"]),
+    country("FO", "FRO", "Faroe Islands", &["Iles Feroe", "Foroyar"]),
+    country("FR", "FRA", "France", &["Frankreich", "Francia", "Frankrijk", "Republique francaise"]),
+    country("GA", "GAB", "Gabon", &[]),
+    country("GB", "GBR", "United Kingdom", &["UK", "Great Britain", "Britain", "England", "Scotland", "Wales", "Northern Ireland", "Royaume-Uni", "Grande-Bretagne", "Vereinigtes Konigreich", "Grossbritannien", "Reino Unido", "United Kingdom of Great Britain and Northern Ireland"]),
+    country("GD", "GRD", "Grenada", &["Grenade"]),
+    country("GE", "GEO", "Georgia", &["Georgie", "Georgien", "Sakartvelo"]),
+    country("GF", "GUF", "French Guiana", &["Guyane", "Guyane francaise"]),
+    country("GG", "GGY", "Guernsey", &["Guernesey"]),
+    country("GH", "GHA", "Ghana", &["Gold Coast"]),
+    country("GI", "GIB", "Gibraltar", &[]),
+    country("GL", "GRL", "Greenland", &["Groenland", "Gronland", "Kalaallit Nunaat"]),
+    country("GM", "GMB", "Gambia", &["The Gambia", "Gambie"]),
+    country("GN", "GIN", "Guinea", &["Guinee"]),
+    country("GP", "GLP", "Guadeloupe", &[]),
+    country("GQ", "GNQ", "Equatorial Guinea", &["Guinee equatoriale"]),
+    country("GR", "GRC", "Greece", &["Grece", "Griechenland", "Grecia", "Hellas", "Ellada"]),
+    country("GS", "SGS", "South Georgia and the South Sandwich Islands", &[]),
+    country("GT", "GTM", "Guatemala", &[]),
+    country("GU", "GUM", "Guam", &[]),
+    country("GW", "GNB", "Guinea-Bissau", &["Guinee-Bissau"]),
+    country("GY", "GUY", "Guyana", &["British Guiana"]),
+    country("HK", "HKG", "Hong Kong", &["Hongkong", "Hong Kong SAR"]),
+    country("HM", "HMD", "Heard Island and McDonald Islands", &[]),
+    country("HN", "HND", "Honduras", &[]),
+    country("HR", "HRV", "Croatia", &["Croatie", "Kroatien", "Croacia", "Hrvatska"]),
+    country("HT", "HTI", "Haiti", &[]),
+    country("HU", "HUN", "Hungary", &["Hongrie", "Ungarn", "Hungria", "Magyarorszag"]),
+    country("ID", "IDN", "Indonesia", &["Indonesie", "Indonesien"]),
+    country("IE", "IRL", "Ireland", &["Irlande", "Irland", "Irlanda", "Eire", "Republic of Ireland"]),
+    country("IL", "ISR", "Israel", &[]),
+    country("IM", "IMN", "Isle of Man", &["Ile de Man"]),
+    country("IN", "IND", "India", &["Inde", "Indien", "Bharat"]),
+    country("IO", "IOT", "British Indian Ocean Territory", &[]),
+    country("IQ", "IRQ", "Iraq", &["Irak"]),
+    country("IR", "IRN", "Iran", &["Islamic Republic of Iran", "Persia"]),
+    country("IS", "ISL", "Iceland", &["Islande", "Island", "Islandia"]),
+    country("IT", "ITA", "Italy", &["Italie", "Italien", "Italia"]),
+    country("JE", "JEY", "Jersey", &[]),
+    country("JM", "JAM", "Jamaica", &["Jamaique"]),
+    country("JO", "JOR", "Jordan", &["Jordanie", "Jordanien"]),
+    country("JP", "JPN", "Japan", &["Japon", "Giappone", "Nippon", "Nihon"]),
+    country("KE", "KEN", "Kenya", &["Kenia"]),
+    country("KG", "KGZ", "Kyrgyzstan", &["Kirghizistan", "Kirgisistan"]),
+    country("KH", "KHM", "Cambodia", &["Cambodge", "Kambodscha", "Kampuchea"]),
+    country("KI", "KIR", "Kiribati", &[]),
+    country("KM", "COM", "Comoros", &["Comores"]),
+    country("KN", "KNA", "Saint Kitts and Nevis", &["Saint-Christophe-et-Nieves"]),
+    country("KP", "PRK", "North Korea", &["Democratic People's Republic of Korea", "DPRK", "Coree du Nord", "Nordkorea"]),
+    country("KR", "KOR", "South Korea", &["Korea", "Republic of Korea", "Coree du Sud", "Coree", "Sudkorea", "Suedkorea", "Corea del Sur", "Hanguk", "Daehan Minguk"]),
+    country("KW", "KWT", "Kuwait", &["Koweit"]),
+    country("KY", "CYM", "Cayman Islands", &["Iles Caimans"]),
+    country("KZ", "KAZ", "Kazakhstan", &["Kasachstan", "Qazaqstan"]),
+    country("LA", "LAO", "Lao People's Democratic Republic", &["Laos"]),
+    country("LB", "LBN", "Lebanon", &["Liban", "Libanon"]),
+    country("LC", "LCA", "Saint Lucia", &["Sainte-Lucie"]),
+    country("LI", "LIE", "Liechtenstein", &[]),
+    country("LK", "LKA", "Sri Lanka", &["Ceylon"]),
+    country("LR", "LBR", "Liberia", &[]),
+    country("LS", "LSO", "Lesotho", &["Basutoland"]),
+    country("LT", "LTU", "Lithuania", &["Lituanie", "Litauen", "Lituania", "Lietuva"]),
+    country("LU", "LUX", "Luxembourg", &["Luxemburg", "Luxemburgo", "Letzebuerg"]),
+    country("LV", "LVA", "Latvia", &["Lettonie", "Lettland", "Letonia", "Latvija"]),
+    country("LY", "LBY", "Libya", &["Libye", "Libyen"]),
+    country("MA", "MAR", "Morocco", &["Maroc", "Marokko", "Marruecos"]),
+    country("MC", "MCO", "Monaco", &[]),
+    country("MD", "MDA", "Moldova", &["Republic of Moldova", "Moldavie", "Moldawien", "Moldavia"]),
+    country("ME", "MNE", "Montenegro", &["Crna Gora"]),
+    country("MF", "MAF", "Saint Martin (French part)", &["Saint-Martin"]),
+    country("MG", "MDG", "Madagascar", &["Madagaskar"]),
+    country("MH", "MHL", "Marshall Islands", &["Iles Marshall"]),
+    country("MK", "MKD", "North Macedonia", &["Macedonia", "Republic of Macedonia", "FYROM", "Macedoine du Nord", "Macedoine", "Nordmazedonien", "Severna Makedonija"]),
+    country("ML", "MLI", "Mali", &[]),
+    country("MM", "MMR", "Myanmar", &["Burma", "Birmanie"]),
+    country("MN", "MNG", "Mongolia", &["Mongolie", "Mongolei"]),
+    country("MO", "MAC", "Macao", &["Macau"]),
+    country("MP", "MNP", "Northern Mariana Islands", &[]),
+    country("MQ", "MTQ", "Martinique", &[]),
+    country("MR", "MRT", "Mauritania", &["Mauritanie"]),
+    country("MS", "MSR", "Montserrat", &[]),
+    country("MT", "MLT", "Malta", &["Malte"]),
+    country("MU", "MUS", "Mauritius", &["Maurice", "Ile Maurice"]),
+    country("MV", "MDV", "Maldives", &[]),
+    country("MW", "MWI", "Malawi", &["Nyasaland"]),
+    country("MX", "MEX", "Mexico", &["Mexique", "Mexiko"]),
+    country("MY", "MYS", "Malaysia", &["Malaisie", "Malaysie"]),
+    country("MZ", "MOZ", "Mozambique", &["Mosambik"]),
+    country("NA", "NAM", "Namibia", &["Namibie", "South West Africa"]),
+    country("NC", "NCL", "New Caledonia", &["Nouvelle-Caledonie"]),
+    country("NE", "NER", "Niger", &[]),
+    country("NF", "NFK", "Norfolk Island", &[]),
+    country("NG", "NGA", "Nigeria", &[]),
+    country("NI", "NIC", "Nicaragua", &[]),
+    country("NL", "NLD", "Netherlands", &["The Netherlands", "Holland", "Pays-Bas", "Niederlande", "Paises Bajos", "Nederland"]),
+    country("NO", "NOR", "Norway", &["Norvege", "Norwegen", "Noruega", "Norge", "Noreg"]),
+    country("NP", "NPL", "Nepal", &[]),
+    country("NR", "NRU", "Nauru", &[]),
+    country("NU", "NIU", "Niue", &[]),
+    country("NZ", "NZL", "New Zealand", &["Nouvelle-Zelande", "Neuseeland", "Nueva Zelanda", "Aotearoa"]),
+    country("OM", "OMN", "Oman", &[]),
+    country("PA", "PAN", "Panama", &[]),
+    country("PE", "PER", "Peru", &["Perou"]),
+    country("PF", "PYF", "French Polynesia", &["Polynesie francaise", "Tahiti"]),
+    country("PG", "PNG", "Papua New Guinea", &["Papouasie-Nouvelle-Guinee"]),
+    country("PH", "PHL", "Philippines", &["Philippinen", "Filipinas", "Pilipinas"]),
+    country("PK", "PAK", "Pakistan", &[]),
+    country("PL", "POL", "Poland", &["Pologne", "Polen", "Polonia", "Polska"]),
+    country("PM", "SPM", "Saint Pierre and Miquelon", &["Saint-Pierre-et-Miquelon"]),
+    country("PN", "PCN", "Pitcairn", &["Pitcairn Islands"]),
+    country("PR", "PRI", "Puerto Rico", &["Porto Rico"]),
+    country("PS", "PSE", "Palestine", &["State of Palestine", "Palestinian Territories"]),
+    country("PT", "PRT", "Portugal", &[]),
+    country("PW", "PLW", "Palau", &[]),
+    country("PY", "PRY", "Paraguay", &[]),
+    country("QA", "QAT", "Qatar", &["Katar"]),
+    country("RE", "REU", "Reunion", &["La Reunion"]),
+    country("RO", "ROU", "Romania", &["Roumanie", "Rumanien", "Rumaenien", "Rumania", "Roumania"]),
+    country("RS", "SRB", "Serbia", &["Serbie", "Serbien", "Srbija"]),
+    country("RU", "RUS", "Russia", &["Russian Federation", "Russie", "Russland", "Rusia", "Rossiya", "Rossija"]),
+    country("RW", "RWA", "Rwanda", &["Ruanda"]),
+    country("SA", "SAU", "Saudi Arabia", &["Arabie saoudite", "Saudi-Arabien"]),
+    country("SB", "SLB", "Solomon Islands", &["Iles Salomon"]),
+    country("SC", "SYC", "Seychelles", &[]),
+    country("SD", "SDN", "Sudan", &["Soudan"]),
+    country("SE", "SWE", "Sweden", &["Suede", "Schweden", "Suecia", "Sverige"]),
+    country("SG", "SGP", "Singapore", &["Singapour", "Singapur"]),
+    country("SH", "SHN", "Saint Helena, Ascension and Tristan da Cunha", &["Saint Helena"]),
+    country("SI", "SVN", "Slovenia", &["Slovenie", "Slowenien", "Eslovenia", "Slovenija"]),
+    country("SJ", "SJM", "Svalbard and Jan Mayen", &["Svalbard"]),
+    country("SK", "SVK", "Slovakia", &["Slovaquie", "Slowakei", "Eslovaquia", "Slovensko", "Slovak Republic"]),
+    country("SL", "SLE", "Sierra Leone", &[]),
+    country("SM", "SMR", "San Marino", &["Saint-Marin"]),
+    country("SN", "SEN", "Senegal", &[]),
+    country("SO", "SOM", "Somalia", &["Somalie"]),
+    country("SR", "SUR", "Suriname", &["Surinam", "Dutch Guiana"]),
+    country("SS", "SSD", "South Sudan", &["Soudan du Sud"]),
+    country("ST", "STP", "Sao Tome and Principe", &["Sao Tome-et-Principe"]),
+    country("SV", "SLV", "El Salvador", &["Salvador"]),
+    country("SX", "SXM", "Sint Maarten (Dutch part)", &["Sint Maarten"]),
+    country("SY", "SYR", "Syria", &["Syrian Arab Republic", "Syrie", "Syrien"]),
+    country("SZ", "SWZ", "Eswatini", &["Swaziland"]),
+    country("TC", "TCA", "Turks and Caicos Islands", &[]),
+    country("TD", "TCD", "Chad", &["Tchad", "Tschad"]),
+    country("TF", "ATF", "French Southern Territories", &["Terres australes et antarctiques francaises"]),
+    country("TG", "TGO", "Togo", &[]),
+    country("TH", "THA", "Thailand", &["Thailande", "Siam", "Prathet Thai"]),
+    country("TJ", "TJK", "Tajikistan", &["Tadjikistan", "Tadschikistan"]),
+    country("TK", "TKL", "Tokelau", &[]),
+    country("TL", "TLS", "Timor-Leste", &["East Timor", "Timor oriental"]),
+    country("TM", "TKM", "Turkmenistan", &[]),
+    country("TN", "TUN", "Tunisia", &["Tunisie", "Tunesien"]),
+    country("TO", "TON", "Tonga", &[]),
+    country("TR", "TUR", "Turkiye", &["Turkey", "Turquie", "Turkei", "Tuerkei", "Turquia"]),
+    country("TT", "TTO", "Trinidad and Tobago", &["Trinite-et-Tobago"]),
+    country("TV", "TUV", "Tuvalu", &["Ellice Islands"]),
+    country("TW", "TWN", "Taiwan", &["Chinese Taipei", "Republic of China", "Taiwan, Province of China", "Taipei"]),
+    country("TZ", "TZA", "Tanzania", &["United Republic of Tanzania", "Tanzanie", "Tansania"]),
+    country("UA", "UKR", "Ukraine", &["Ukrayina", "Ucrania"]),
+    country("UG", "UGA", "Uganda", &["Ouganda"]),
+    country("UM", "UMI", "United States Minor Outlying Islands", &[]),
+    country("US", "USA", "United States of America", &["United States", "U.S.", "U.S.A.", "America", "Etats-Unis", "Etats-Unis d'Amerique", "Vereinigte Staaten", "Vereinigte Staaten von Amerika", "Estados Unidos"]),
+    country("UY", "URY", "Uruguay", &[]),
+    country("UZ", "UZB", "Uzbekistan", &["Ouzbekistan", "Usbekistan"]),
+    country("VA", "VAT", "Holy See", &["Vatican", "Vatican City", "Vatican City State"]),
+    country("VC", "VCT", "Saint Vincent and the Grenadines", &["Saint-Vincent-et-les-Grenadines"]),
+    country("VE", "VEN", "Venezuela", &["Bolivarian Republic of Venezuela"]),
+    country("VG", "VGB", "British Virgin Islands", &["Virgin Islands (British)"]),
+    country("VI", "VIR", "United States Virgin Islands", &["Virgin Islands (U.S.)", "US Virgin Islands"]),
+    country("VN", "VNM", "Viet Nam", &["Vietnam"]),
+    country("VU", "VUT", "Vanuatu", &["New Hebrides"]),
+    country("WF", "WLF", "Wallis and Futuna", &["Wallis-et-Futuna"]),
+    country("WS", "WSM", "Samoa", &["Western Samoa"]),
+    country("XK", "XKX", "Kosovo", &["Kosova"]),
+    country("YE", "YEM", "Yemen", &[]),
+    country("YT", "MYT", "Mayotte", &[]),
+    country("ZA", "ZAF", "South Africa", &["Afrique du Sud", "Sudafrika", "Suedafrika", "Sudafrica"]),
+    country("ZM", "ZMB", "Zambia", &["Zambie", "Sambia", "Northern Rhodesia"]),
+    country("ZW", "ZWE", "Zimbabwe", &["Simbabwe", "Rhodesia", "Southern Rhodesia"]),
+    // Former countries (ISO 3166-3)
+    country("SU", "SUN", "USSR", &["Soviet Union", "Union of Soviet Socialist Republics", "URSS", "UdSSR", "Sowjetunion", "CCCP"]),
+    country("YU", "YUG", "Yugoslavia", &["Yougoslavie", "Jugoslawien", "Jugoslavija"]),
+    country("CS", "CSK", "Czechoslovakia", &["Tchecoslovaquie", "Tschechoslowakei", "Ceskoslovensko"]),
+    country("DD", "DDR", "German Democratic Republic", &["East Germany", "GDR", "RDA", "Allemagne de l'Est"]),
+    country("AN", "ANT", "Netherlands Antilles", &["Antilles neerlandaises"]),
+];
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod country;
+pub mod iso_countries;
\ No newline at end of file
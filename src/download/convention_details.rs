@@ -0,0 +1,164 @@
+use std::io::Error;
+
+use chrono::NaiveDate;
+use derive_getters::Getters;
+use log::debug;
+use regex::{Captures, Regex};
+use reqwest::Client;
+use scraper::{Html, Selector};
+
+thread_local!(static TEXT_DATE_RANGE_REGEX: Regex = Regex::new(r"(?P<start_month>[A-Z][a-z]+)\.? (?P<start_day>\d{1,2})(st|nd|rd|th)?(,? (?P<start_year>\d{4}))? ?(-|–|to) ?((?P<end_month>[A-Z][a-z]+)\.? )?(?P<end_day>\d{1,2})(st|nd|rd|th)?,? (?P<end_year>\d{4})").unwrap());
+thread_local!(static NUMERIC_DATE_RANGE_REGEX: Regex = Regex::new(r"(?P<start>\d{1,2}/\d{1,2}/\d{4}) ?(-|–|to) ?(?P<end>\d{1,2}/\d{1,2}/\d{4})").unwrap());
+thread_local!(static LOCATION_REGEX: Regex = Regex::new(r"Location: *(?P<location>[^\n]+)").unwrap());
+
+const LOCATION_SELECTOR: &str = ".location, .convention-location, [itemprop=location]";
+
+/// Details of a convention that are displayed on its welcome page.
+#[derive(Debug, Default, Getters, PartialEq)]
+pub struct ConventionDetails {
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    location: Option<String>,
+}
+
+pub async fn get_convention_details(client: &Client, base_url: &str) -> Result<ConventionDetails, Error> {
+    let url = format!("{base_url}/");
+    let response = client.get(url)
+        .send()
+        .await
+        .map_err(|error| Error::other(format!("Can't get convention details: {error}")))?;
+    let body = response
+        .text()
+        .await
+        .map_err(|error| Error::other(format!("Can't get convention details: {error}")))?;
+
+    let document = Html::parse_document(&body);
+    Ok(get_convention_details_from_html(&document))
+}
+
+fn get_convention_details_from_html(document: &Html) -> ConventionDetails {
+    let text = document.root_element().text().collect::<Vec<&str>>().join("\n");
+    let (start_date, end_date) = match parse_date_range(&text) {
+        None => { (None, None) }
+        Some((start_date, end_date)) => { (Some(start_date), Some(end_date)) }
+    };
+    let location = get_location_from_html(document)
+        .or_else(|| LOCATION_REGEX.with(|regex| regex.captures(&text).map(|captures| captures["location"].trim().to_string())));
+
+    ConventionDetails { start_date, end_date, location }
+}
+
+fn get_location_from_html(document: &Html) -> Option<String> {
+    let location_selector = Selector::parse(LOCATION_SELECTOR).unwrap();
+    document.select(&location_selector)
+        .map(|element| element.text().collect::<Vec<&str>>().join(" "))
+        .map(|location| location.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .find(|location| !location.is_empty())
+}
+
+/// Try and find a date range in the following formats:
+/// - July 16 - 28, 2022
+/// - July 30 - August 6, 2022
+/// - Dec 30, 2022 - Jan 2, 2023
+/// - 16/07/2022 - 28/07/2022 or 07/16/2022 - 07/28/2022
+///
+/// Numeric dates may be day first or month first. The range is only kept if a single reading of it is valid,
+/// e.g. 16/07/2022 can only be day first, whereas 01/07/2022 - 03/07/2022 is ambiguous and skipped.
+fn parse_date_range(text: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let text_date_range = TEXT_DATE_RANGE_REGEX.with(|regex| {
+        regex.captures_iter(text).find_map(|captures| parse_text_date_range(&captures))
+    });
+    if text_date_range.is_some() {
+        return text_date_range;
+    }
+
+    NUMERIC_DATE_RANGE_REGEX.with(|regex| {
+        regex.captures_iter(text).find_map(|captures| {
+            let day_first = parse_numeric_date_range(&captures, "%d/%m/%Y");
+            let month_first = parse_numeric_date_range(&captures, "%m/%d/%Y");
+            match (day_first, month_first) {
+                (Some(day_first), Some(month_first)) if day_first != month_first => {
+                    debug!("Ambiguous numeric date range, it is ignored [start: {}, end: {}]", &captures["start"], &captures["end"]);
+                    None
+                }
+                (day_first, month_first) => { day_first.or(month_first) }
+            }
+        })
+    })
+}
+
+fn parse_numeric_date_range(captures: &Captures, format: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let start_date = NaiveDate::parse_from_str(&captures["start"], format).ok()?;
+    let end_date = NaiveDate::parse_from_str(&captures["end"], format).ok()?;
+    Some((start_date, end_date)).filter(|(start_date, end_date)| start_date <= end_date)
+}
+
+fn parse_text_date_range(captures: &Captures) -> Option<(NaiveDate, NaiveDate)> {
+    let end_year = &captures["end_year"];
+    let start_year = captures.name("start_year").map_or(end_year, |year| year.as_str());
+    let start_month = &captures["start_month"];
+    let end_month = captures.name("end_month").map_or(start_month, |month| month.as_str());
+
+    let start_date = parse_text_date(&captures["start_day"], start_month, start_year)?;
+    let end_date = parse_text_date(&captures["end_day"], end_month, end_year)?;
+    Some((start_date, end_date)).filter(|(start_date, end_date)| start_date <= end_date)
+}
+
+fn parse_text_date(day: &str, month: &str, year: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{day} {month} {year}"), "%d %B %Y").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use scraper::Html;
+
+    use crate::download::convention_details::{get_convention_details_from_html, parse_date_range};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn should_parse_date_range_within_month() {
+        assert_eq!(parse_date_range("Unicon 20, July 16 - 28, 2022"), Some((date(2022, 7, 16), date(2022, 7, 28))));
+    }
+
+    #[test]
+    fn should_parse_date_range_over_months() {
+        assert_eq!(parse_date_range("July 30th - August 6th, 2022"), Some((date(2022, 7, 30), date(2022, 8, 6))));
+    }
+
+    #[test]
+    fn should_parse_date_range_over_years() {
+        assert_eq!(parse_date_range("Dec 30, 2022 - Jan 2, 2023"), Some((date(2022, 12, 30), date(2023, 1, 2))));
+    }
+
+    #[test]
+    fn should_parse_numeric_date_range() {
+        assert_eq!(parse_date_range("From 16/07/2022 to 28/07/2022"), Some((date(2022, 7, 16), date(2022, 7, 28))));
+        assert_eq!(parse_date_range("From 07/16/2022 to 07/28/2022"), Some((date(2022, 7, 16), date(2022, 7, 28))));
+        assert_eq!(parse_date_range("From 05/05/2022 to 05/05/2022"), Some((date(2022, 5, 5), date(2022, 5, 5))));
+    }
+
+    #[test]
+    fn should_not_parse_ambiguous_numeric_date_range() {
+        assert_eq!(parse_date_range("From 01/07/2022 to 03/07/2022"), None);
+    }
+
+    #[test]
+    fn should_not_parse_date_range_when_none() {
+        assert_eq!(parse_date_range("Welcome to Unicon 20"), None);
+    }
+
+    #[test]
+    fn should_get_convention_details_from_html() {
+        let document = Html::parse_document(r#"<html><body><h1 class="title">Unicon 20</h1><p>July 16 - 28, 2022</p><p class="location">Grenoble,  France</p></body></html>"#);
+
+        let details = get_convention_details_from_html(&document);
+
+        assert_eq!(details.start_date(), &Some(date(2022, 7, 16)));
+        assert_eq!(details.end_date(), &Some(date(2022, 7, 28)));
+        assert_eq!(details.location(), &Some("Grenoble, France".to_string()));
+    }
+}
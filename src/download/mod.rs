@@ -1,16 +1,21 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{Error, ErrorKind, Write};
 
-use log::{error, info};
+use chrono::Utc;
+use log::{error, info, warn};
 use reqwest::{Client, StatusCode};
 use scraper::{Html, Selector};
 use tokio::join;
 
 use crate::convention::convention::Convention;
+use crate::download::convention_details::{ConventionDetails, get_convention_details};
+use crate::raw_result::results_snapshot::{save_existing_results_snapshot, save_results_snapshot};
 use crate::utils::{create_folder, DATA_FOLDER};
 use crate::utils::credentials::Credentials;
 
+pub mod convention_details;
+
 const UDA_DOMAIN: &str = "reg.unicycling-software.com/en";
 
 fn build_client() -> Client {
@@ -29,7 +34,7 @@ pub async fn download_data(conventions_tag: &HashSet<&String>) -> Result<Vec<Con
 
     let mut downloaded_conventions = vec![];
     for convention_tag in conventions_tag {
-        match create_folder_for_convention(&convention_tag) {
+        match create_folder_for_convention(convention_tag) {
             Ok(_) => {}
             Err(_) => { continue; }
         }
@@ -37,9 +42,15 @@ pub async fn download_data(conventions_tag: &HashSet<&String>) -> Result<Vec<Con
         let client = build_client();
         let download_result = download_data_for_convention(&client, &credentials, convention_tag).await;
         if download_result.is_ok() {
-            let convention_name = download_result.unwrap();
+            let (convention_name, details) = download_result.unwrap();
             info!("Convention has been successfully downloaded [convention: {}]", convention_name);
-            downloaded_conventions.push(Convention::new(convention_tag.to_string(), convention_name));
+            downloaded_conventions.push(Convention::new(
+                convention_tag.to_string(),
+                convention_name,
+                *details.start_date(),
+                *details.end_date(),
+                details.location().clone(),
+            ));
         } else {
             let errors = download_result.unwrap_err();
             error!("Errors encountered while downloading convention data [convention: {}]", convention_tag);
@@ -52,6 +63,27 @@ pub async fn download_data(conventions_tag: &HashSet<&String>) -> Result<Vec<Con
     Ok(downloaded_conventions)
 }
 
+/// Fetch the details of the given cached conventions from their welcome page, which doesn't require to log in,
+/// and fill in the ones that are missing. Conventions whose details can't be fetched are left as they are.
+pub async fn backfill_conventions_details(conventions: &mut HashMap<String, Convention>, conventions_tag: &HashSet<String>) {
+    let client = build_client();
+    for convention_tag in conventions_tag {
+        let base_url = format!("https://{convention_tag}.{UDA_DOMAIN}");
+        let details = match get_convention_details(&client, &base_url).await {
+            Ok(details) => { details }
+            Err(error) => {
+                warn!("Can't backfill convention details [convention: {convention_tag}]: {error}");
+                continue;
+            }
+        };
+        if let Some(convention) = conventions.remove(convention_tag) {
+            info!("Convention details backfilled [convention: {convention_tag}, details: {:?}]", details);
+            let convention = convention.with_missing_details(*details.start_date(), *details.end_date(), details.location().clone());
+            conventions.insert(convention_tag.clone(), convention);
+        }
+    }
+}
+
 fn create_folder_for_convention(convention_tag: &str) -> Result<(), ()> {
     create_folder(
         &format!("{}/{}", DATA_FOLDER, convention_tag),
@@ -60,12 +92,19 @@ fn create_folder_for_convention(convention_tag: &str) -> Result<(), ()> {
 }
 
 async fn download_data_for_convention(client: &Client, credentials: &Credentials, convention: &str)
-                                      -> Result<String, Vec<Error>> {
+                                      -> Result<(String, ConventionDetails), Vec<Error>> {
     let base_url = format!("https://{convention}.{UDA_DOMAIN}");
     let (convention_name, authenticity_token) = get_convention_name_and_authenticity_token(client, &base_url).await.or_else(|error| Err(vec![error]))?;
     login(client, &base_url, &authenticity_token, credentials).await.or_else(|error| Err(vec![error]))?;
-    let results_future = export_results(&client, &base_url, convention);
-    let registrants_futures = export_registrants(&client, &base_url, convention);
+    let details = match get_convention_details(client, &base_url).await {
+        Ok(details) => { details }
+        Err(error) => {
+            warn!("Convention details will be missing [convention: {convention}]: {error}");
+            ConventionDetails::default()
+        }
+    };
+    let results_future = export_results(client, &base_url, convention);
+    let registrants_futures = export_registrants(client, &base_url, convention);
 
     let download_results = join!(results_future, registrants_futures);
     let mut errors = vec![];
@@ -79,7 +118,7 @@ async fn download_data_for_convention(client: &Client, credentials: &Credentials
     };
 
     if errors.is_empty() {
-        Ok(String::from(convention_name))
+        Ok((convention_name, details))
     } else {
         Err(errors)
     }
@@ -168,7 +207,14 @@ async fn login(client: &Client, base_url: &str, authenticity_token: &str, creden
 
 async fn export_results(client: &Client, base_url: &str, convention: &str) -> Result<(), Error> {
     let url = format!("{base_url}/export/results.xls");
-    download_file(client, &url, &format!("{DATA_FOLDER}/{convention}/results.xls")).await
+    if save_existing_results_snapshot(DATA_FOLDER, convention).is_err() {
+        warn!("Can't snapshot results before downloading them again, they will be lost [convention: {convention}]");
+    }
+    download_file(client, &url, &format!("{DATA_FOLDER}/{convention}/results.xls")).await?;
+    if save_results_snapshot(DATA_FOLDER, convention, Utc::now()).is_err() {
+        warn!("Can't snapshot results, changes since previous download won't be tracked [convention: {convention}]");
+    }
+    Ok(())
 }
 
 async fn export_registrants(client: &Client, base_url: &str, convention: &str) -> Result<(), Error> {
@@ -0,0 +1,134 @@
+pub mod sqlite;
+pub mod privacy;
+
+use std::fs::File;
+use std::io::Write;
+
+use log::warn;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::utils::create_folder;
+
+pub const EXPORT_FOLDER: &str = "exports";
+/// Where exports are written in privacy mode, so that they don't mix with the complete ones.
+pub const PUBLIC_EXPORT_FOLDER: &str = "exports/public";
+
+/// Serialize the value as JSON and write it to `{folder}/{filename}`.
+pub fn write_json_file<T: Serialize + ?Sized>(folder: &str, filename: &str, value: &T) -> Result<(), ()> {
+    let json = json!(value);
+    write_file(folder, filename, &json.to_string())
+}
+
+/// Serialize the rows as CSV and write them to `{folder}/{filename}`.
+/// The header is built from the fields of the rows.
+pub fn write_csv_file<T: Serialize>(folder: &str, filename: &str, rows: &[T]) -> Result<(), ()> {
+    let csv = match to_csv(rows) {
+        Ok(csv) => { csv }
+        Err(error) => {
+            warn!("Can't export data as CSV [filename: {filename}]");
+            warn!("{}", error);
+            return Err(());
+        }
+    };
+    write_file(folder, filename, &csv)
+}
+
+/// Serialize the rows as a Markdown table preceded by a title and write them to `{folder}/{filename}`.
+/// The header is built from the fields of the rows.
+pub fn write_markdown_file<T: Serialize>(folder: &str, filename: &str, title: &str, rows: &[T]) -> Result<(), ()> {
+    let table = match to_markdown_table(rows) {
+        Ok(table) => { table }
+        Err(error) => {
+            warn!("Can't export data as Markdown [filename: {filename}]");
+            warn!("{}", error);
+            return Err(());
+        }
+    };
+    write_file(folder, filename, &format!("# {title}\n\n{table}"))
+}
+
+fn to_csv<T: Serialize>(rows: &[T]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row).map_err(|error| error.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|error| error.to_string())?;
+    String::from_utf8(bytes).map_err(|error| error.to_string())
+}
+
+/// Rows are serialized as CSV first, so that fields are flattened and ordered the same way in both formats.
+pub fn to_markdown_table<T: Serialize>(rows: &[T]) -> Result<String, String> {
+    let csv = to_csv(rows)?;
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers = reader.headers().map_err(|error| error.to_string())?.clone();
+    if headers.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut table = String::new();
+    table.push_str(&to_markdown_row(headers.iter()));
+    table.push_str(&to_markdown_row(headers.iter().map(|_| "---")));
+    for record in reader.records() {
+        let record = record.map_err(|error| error.to_string())?;
+        table.push_str(&to_markdown_row(record.iter()));
+    }
+
+    Ok(table)
+}
+
+fn to_markdown_row<'a>(cells: impl Iterator<Item=&'a str>) -> String {
+    let cells: Vec<String> = cells.map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Write the content as is to `{folder}/{filename}`, creating the folder if needed.
+pub fn write_file(folder: &str, filename: &str, content: &str) -> Result<(), ()> {
+    create_folder(
+        folder,
+        &format!("Can't export data because folder couldn't be created [folder: {folder}]"),
+    )?;
+    let filepath = format!("{folder}/{filename}");
+    let mut file = match File::create(&filepath) {
+        Ok(file) => { Ok(file) }
+        Err(error) => {
+            warn!("Can't export data because file couldn't be opened [filepath: {filepath}]");
+            warn!("{}", error);
+            Err(())
+        }
+    }?;
+    match file.write_all(content.as_bytes()) {
+        Ok(_) => { Ok(()) }
+        Err(error) => {
+            warn!("Can't export data [filepath: {filepath}]");
+            warn!("{}", error);
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::export::to_markdown_table;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        value: u16,
+    }
+
+    #[test]
+    fn should_render_markdown_table() {
+        let rows = [Row { name: "John Doe", value: 1 }, Row { name: "Jane | Doe", value: 2 }];
+        let expected = "| name | value |\n| --- | --- |\n| John Doe | 1 |\n| Jane \\| Doe | 2 |\n";
+        assert_eq!(to_markdown_table(&rows).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_nothing_when_no_row() {
+        let rows: [Row; 0] = [];
+        assert_eq!(to_markdown_table(&rows).unwrap(), "");
+    }
+}
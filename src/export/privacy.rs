@@ -0,0 +1,346 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+
+use chrono::{Datelike, NaiveDate};
+use log::{debug, error, info};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::competition::competition_result::CompetitionResult;
+use crate::competition::performance::Performance;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::competitor::Competitor;
+use crate::competitor::team::Team;
+use crate::convention::convention::Convention;
+use crate::person::identity::Identity;
+use crate::person::identity_overrides::RegistrationKey;
+use crate::person::person::Person;
+use crate::person::person_id_registry::PersonIdRegistry;
+use crate::person::person_name::PersonName;
+use crate::raw_result::raw_result::RawResult;
+use crate::registration::registrant::Registrant;
+
+const PRIVACY_POLICY_FILE: &str = "privacy.json";
+/// Name of an opted-out registrant whose person isn't known yet.
+const ANONYMOUS_NAME: &str = "Anonymous";
+/// Name of a team one of whose members opted out, as the name of a team may tell who its members are.
+const ANONYMOUS_TEAM_NAME: &str = "Anonymous team";
+
+/// How much of birthdays is exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BirthdayPrecision {
+    #[default]
+    Day,
+    Year,
+    Hidden,
+}
+
+impl BirthdayPrecision {
+    /// The birthday as exported, e.g. "2000-01-20", "2000" or nothing.
+    pub fn format(&self, birthday: &NaiveDate) -> String {
+        match self {
+            BirthdayPrecision::Day => { birthday.to_string() }
+            BirthdayPrecision::Year => { birthday.year().to_string() }
+            BirthdayPrecision::Hidden => { String::new() }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct RawPrivacyPolicy {
+    birthdays: BirthdayPrecision,
+    strip_details: bool,
+    opted_out_registrations: Vec<RegistrationKey>,
+    opted_out_people: Vec<Uuid>,
+}
+
+impl Default for RawPrivacyPolicy {
+    fn default() -> Self {
+        Self { birthdays: BirthdayPrecision::Year, strip_details: true, opted_out_registrations: vec![], opted_out_people: vec![] }
+    }
+}
+
+/// What is left out of public exports: birthdays are coarsened, results details are stripped
+/// and the people who opted out, by one of their registrations or by their person id, are given a pseudonym.
+/// Birthdays are only coarsened once exported, since ages are computed from them.
+#[derive(Debug)]
+pub struct PrivacyPolicy {
+    birthday_precision: BirthdayPrecision,
+    strip_details: bool,
+    opted_out_registrations: HashSet<RegistrationKey>,
+    opted_out_people: HashSet<Uuid>,
+    // The pseudonym of every registration of the people who opted out, see [PrivacyPolicy::with_person_ids]
+    pseudonyms: HashMap<RegistrationKey, String>,
+}
+
+impl PrivacyPolicy {
+    /// Load the policy from `{folder}/privacy.json`, e.g.
+    /// `{"birthdays": "year", "strip_details": true, "opted_out_registrations": [{"convention": "cfm2022", "id": 12}], "opted_out_people": []}`.
+    /// If there is none, birthdays are coarsened to the year, details are stripped and nobody has opted out.
+    /// A policy that can't be read is an error, as people who opted out would be exported.
+    pub fn load_from_folder(folder: &str) -> Result<Self, ()> {
+        let filepath = format!("{folder}/{PRIVACY_POLICY_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No privacy policy, default one will be applied [filepath: {filepath}]");
+                return Ok(Self::from_raw(RawPrivacyPolicy::default()));
+            }
+        };
+        let reader = BufReader::new(file);
+
+        let raw_policy: RawPrivacyPolicy = serde_json::from_reader(reader).map_err(|error| {
+            error!("Can't read privacy policy [filepath: {filepath}]");
+            error!("{}", error);
+        })?;
+        info!("Privacy policy loaded [birthdays: {:?}, strip_details: {}, opted_out_registrations: {}, opted_out_people: {}]",
+            raw_policy.birthdays, raw_policy.strip_details, raw_policy.opted_out_registrations.len(), raw_policy.opted_out_people.len());
+        Ok(Self::from_raw(raw_policy))
+    }
+
+    fn from_raw(raw_policy: RawPrivacyPolicy) -> Self {
+        let opted_out_registrations: HashSet<RegistrationKey> = raw_policy.opted_out_registrations.into_iter().collect();
+        let pseudonyms = opted_out_registrations.iter()
+            .map(|registration| (registration.clone(), ANONYMOUS_NAME.to_string()))
+            .collect();
+        Self {
+            birthday_precision: raw_policy.birthdays,
+            strip_details: raw_policy.strip_details,
+            opted_out_registrations,
+            opted_out_people: raw_policy.opted_out_people.into_iter().collect(),
+            pseudonyms,
+        }
+    }
+
+    pub fn birthday_precision(&self) -> BirthdayPrecision {
+        self.birthday_precision
+    }
+
+    /// Resolve who opted out from the ids of the people: every registration of a person who opted out,
+    /// by their person id or by any of their registrations, is given the pseudonym of this person.
+    pub fn with_person_ids(mut self, person_id_registry: &PersonIdRegistry) -> Self {
+        let person_ids = person_id_registry.ids();
+        let opted_out_people: HashSet<&Uuid> = self.opted_out_people.iter()
+            .chain(self.opted_out_registrations.iter().filter_map(|registration| person_ids.get(registration)))
+            .collect();
+        // Pseudonyms of the registrations known to the registry replace the ones of the registrations alone
+        self.pseudonyms = self.opted_out_registrations.iter()
+            .map(|registration| (registration.clone(), ANONYMOUS_NAME.to_string()))
+            .chain(person_ids.iter()
+                .filter(|(_, person_id)| opted_out_people.contains(person_id))
+                .map(|(registration, person_id)| (registration.clone(), pseudonym(person_id))))
+            .collect();
+        self
+    }
+
+    pub fn anonymize_people<'a>(&self, people: Vec<Person<'a>>) -> Vec<Person<'a>> {
+        people.into_iter()
+            .map(|person| {
+                let is_opted_out = self.opted_out_people.contains(person.id())
+                    || RegistrationKey::from_person(&person).iter().any(|registration| self.pseudonyms.contains_key(registration));
+                let performances = person.performances().iter()
+                    .map(|(convention, performances)| {
+                        let performances = performances.iter()
+                            .map(|performance| self.anonymize_performance(convention.tag(), performance.clone()))
+                            .collect();
+                        (*convention, performances)
+                    })
+                    .collect();
+                let person = person.with_performances(performances);
+                if !is_opted_out {
+                    return person;
+                }
+                let identity = Identity::new(PersonName::new(&pseudonym(person.id())), *person.identity().birthday());
+                person.with_identity(identity)
+            })
+            .collect()
+    }
+
+    pub fn anonymize_results<'a>(&self, conventions_results: HashMap<&'a Convention, ResultStore>) -> HashMap<&'a Convention, ResultStore> {
+        conventions_results.into_iter()
+            .map(|(convention, results)| {
+                let results = results.results().iter()
+                    .map(|result| self.anonymize_result(convention.tag(), result.clone()))
+                    .collect();
+                (convention, ResultStore::new(results))
+            })
+            .collect()
+    }
+
+    pub fn anonymize_registrants<'a>(&self, registrants: HashMap<&'a Convention, Vec<Registrant>>) -> HashMap<&'a Convention, Vec<Registrant>> {
+        registrants.into_iter()
+            .map(|(convention, registrants)| {
+                let registrants = registrants.into_iter()
+                    .map(|registrant| match self.pseudonyms.get(&RegistrationKey::new(convention.tag(), *registrant.id())) {
+                        Some(pseudonym) => { registrant.with_names(pseudonym.clone(), String::new()) }
+                        None => { registrant }
+                    })
+                    .collect();
+                (convention, registrants)
+            })
+            .collect()
+    }
+
+    /// Raw results lines of a single registrant are given their pseudonym,
+    /// the ones of a team one of whose members opted out are named [ANONYMOUS_TEAM_NAME].
+    pub fn anonymize_raw_results(&self, convention_tag: &str, raw_results: Vec<RawResult>) -> Vec<RawResult> {
+        raw_results.into_iter()
+            .map(|raw_result| {
+                let pseudonyms: Vec<&String> = raw_result.ids().split(',')
+                    .filter_map(|id| id.trim().parse::<u16>().ok())
+                    .filter_map(|id| self.pseudonyms.get(&RegistrationKey::new(convention_tag, id)))
+                    .collect();
+                let is_team = raw_result.ids().contains(',');
+                let raw_result = match pseudonyms.first() {
+                    None => { raw_result }
+                    Some(_) if is_team => { raw_result.with_name(ANONYMOUS_TEAM_NAME.to_string()) }
+                    Some(pseudonym) => { raw_result.with_name((*pseudonym).clone()) }
+                };
+                if self.strip_details { raw_result.with_details(String::new()) } else { raw_result }
+            })
+            .collect()
+    }
+
+    fn anonymize_result(&self, convention_tag: &str, result: CompetitionResult) -> CompetitionResult {
+        let competitor = self.anonymize_competitor(convention_tag, result.competitor());
+        let details = result.details().clone().filter(|_| !self.strip_details);
+        result.with_competitor(competitor).with_details(details)
+    }
+
+    fn anonymize_performance(&self, convention_tag: &str, performance: Performance) -> Performance {
+        let competitor = self.anonymize_competitor(convention_tag, performance.competitor());
+        let details = performance.details().clone().filter(|_| !self.strip_details);
+        performance.with_competitor(competitor).with_details(details)
+    }
+
+    fn anonymize_competitor(&self, convention_tag: &str, competitor: &Competitor) -> Competitor {
+        match competitor {
+            Competitor::IndividualCompetitor(individual) => {
+                match self.pseudonyms.get(&RegistrationKey::new(convention_tag, *individual.id())) {
+                    Some(pseudonym) => { Competitor::IndividualCompetitor(individual.clone().with_name(pseudonym.clone())) }
+                    None => { competitor.clone() }
+                }
+            }
+            Competitor::Team(team) => {
+                let is_opted_out = competitor.ids().into_iter().any(|id| self.pseudonyms.contains_key(&RegistrationKey::new(convention_tag, id)));
+                let name = if is_opted_out { ANONYMOUS_TEAM_NAME.to_string() } else { team.name().clone() };
+                let members = team.members().iter().map(|member| self.anonymize_competitor(convention_tag, member)).collect();
+                Competitor::Team(Team::new(name, team.gender().clone(), members))
+            }
+            Competitor::UnknownIndividualCompetitor(_) => { competitor.clone() }
+        }
+    }
+}
+
+/// A name that tells people who opted out apart without telling who they are, the same from a run to another.
+fn pseudonym(person_id: &Uuid) -> String {
+    format!("{ANONYMOUS_NAME} {}", &person_id.simple().to_string()[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::result_store::ResultStore;
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::individual_competitor::IndividualCompetitor;
+    use crate::competitor::team::Team;
+    use crate::convention::convention::Convention;
+    use crate::export::privacy::{BirthdayPrecision, PrivacyPolicy, RawPrivacyPolicy};
+    use crate::person::identity_overrides::RegistrationKey;
+    use crate::person::person::Person;
+    use crate::person::person_id_registry::PersonIdRegistry;
+    use crate::raw_result::raw_result::RawResult;
+    use crate::registration::registrant::Registrant;
+
+    fn create_person<'a>(id: u16, name: &str, convention: &'a Convention) -> Person<'a> {
+        let result = CompetitionResult::create_test_instance(id, "100m", "1", "00:14.990")
+            .with_individual_competitor(name, None, None)
+            .with_details(Some("False start".to_string()));
+        Person::create_test_instance(name).with_registrations_id(convention, vec![id]).with_results(convention, &[result])
+    }
+
+    fn create_policy(opted_out_registrations: Vec<RegistrationKey>) -> PrivacyPolicy {
+        PrivacyPolicy::from_raw(RawPrivacyPolicy { opted_out_registrations, ..RawPrivacyPolicy::default() })
+    }
+
+    #[test]
+    fn should_format_birthdays() {
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 20).unwrap();
+
+        assert_eq!(BirthdayPrecision::Day.format(&birthday), "2000-01-20");
+        assert_eq!(BirthdayPrecision::Year.format(&birthday), "2000");
+        assert_eq!(BirthdayPrecision::Hidden.format(&birthday), "");
+    }
+
+    #[test]
+    fn should_give_pseudonyms_to_people_who_opted_out_and_strip_details() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let mut person_id_registry = PersonIdRegistry::default();
+        let people = person_id_registry.assign_ids(vec![create_person(1, "John Doe", &convention), create_person(2, "Jane Doe", &convention)]);
+        let policy = create_policy(vec![RegistrationKey::new("cfm2022", 1)]).with_person_ids(&person_id_registry);
+
+        let people = policy.anonymize_people(people);
+
+        let pseudonym = people[0].identity().person_name().name();
+        assert!(pseudonym.starts_with("Anonymous "), "name: {pseudonym}");
+        assert_eq!(people[1].identity().person_name().name(), "Jane Doe");
+        let performance = &people[0].performances()[&convention][0];
+        assert_eq!(performance.competitor().name(), Some(pseudonym.as_str()));
+        assert_eq!(performance.details(), &None);
+        assert_eq!(people[1].performances()[&convention][0].details(), &None);
+    }
+
+    #[test]
+    fn should_give_pseudonym_to_every_registration_of_people_who_opted_out() {
+        let first_convention = Convention::create_test_instance("cfm2022");
+        let second_convention = Convention::create_test_instance("cfm2023");
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 20).unwrap();
+        let person = create_person(1, "John Doe", &first_convention).merge(create_person(7, "John Doe", &second_convention));
+        let mut person_id_registry = PersonIdRegistry::default();
+        let person_id = *person_id_registry.assign_ids(vec![person])[0].id();
+        let policy = create_policy(vec![RegistrationKey::new("cfm2022", 1)]).with_person_ids(&person_id_registry);
+
+        let registrants = policy.anonymize_registrants(HashMap::from([
+            (&first_convention, vec![Registrant::create_test_instance(1, "John", "Doe", birthday)]),
+            (&second_convention, vec![Registrant::create_test_instance(7, "John", "Doe", birthday), Registrant::create_test_instance(8, "Jane", "Doe", birthday)]),
+        ]));
+
+        let expected_name = format!("Anonymous {}", &person_id.simple().to_string()[..8]);
+        assert_eq!(registrants[&first_convention][0].first_name(), &expected_name);
+        assert_eq!(registrants[&second_convention][0].first_name(), &expected_name);
+        assert_eq!(registrants[&second_convention][0].last_name(), "");
+        assert_eq!(registrants[&second_convention][1].first_name(), "Jane");
+    }
+
+    #[test]
+    fn should_hide_name_of_teams_one_of_whose_members_opted_out() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let policy = create_policy(vec![RegistrationKey::new("cfm2022", 1)]);
+        let create_team = |ids: [u16; 2]| {
+            let members = ids.map(|id| Competitor::IndividualCompetitor(IndividualCompetitor::new(id, format!("Member {id}"), None, None))).to_vec();
+            CompetitionResult::create_test_instance(ids[0], "Pairs", "1", "1").with_competitor(Competitor::Team(Team::new("The Does".to_string(), None, members)))
+        };
+        let create_raw_result = |ids: &str| RawResult::new(ids.to_string(), "The Does".to_string(), "Male".to_string(), 22, "Pairs".to_string(),
+                                                           "1".to_string(), "Overall".to_string(), "1".to_string(), String::new(), String::new());
+
+        let results = policy.anonymize_results(HashMap::from([(&convention, ResultStore::new(vec![create_team([2, 1]), create_team([2, 3])]))]));
+        let raw_results = policy.anonymize_raw_results("cfm2022", vec![create_raw_result("2, 1"), create_raw_result("2, 3"), create_raw_result("1")]);
+
+        let names: Vec<Option<&str>> = results[&convention].results().iter().map(|result| result.competitor().name()).collect();
+        assert_eq!(names, vec![Some("Anonymous team"), Some("The Does")]);
+        let members: Vec<Option<&str>> = match results[&convention].results()[0].competitor() {
+            Competitor::Team(team) => { team.members().iter().map(Competitor::name).collect() }
+            _ => { vec![] }
+        };
+        assert_eq!(members, vec![Some("Member 2"), Some("Anonymous")]);
+        let raw_names: Vec<&str> = raw_results.iter().map(|raw_result| raw_result.name().as_str()).collect();
+        assert_eq!(raw_names, vec!["Anonymous team", "The Does", "Anonymous"]);
+    }
+}
\ No newline at end of file
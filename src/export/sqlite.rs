@@ -0,0 +1,637 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rusqlite::{Connection, OptionalExtension, params, Transaction};
+use sha2::{Digest, Sha256};
+
+use crate::competition::competition_result::CompetitionResult;
+use crate::competition::discipline::Discipline;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::competitor::Competitor;
+use crate::convention::convention::Convention;
+use crate::convention::convention_snapshot::ConventionSnapshot;
+use crate::country::country::Country;
+use crate::export::privacy::BirthdayPrecision;
+use crate::person::person::Person;
+use crate::raw_result::raw_result::RawResult;
+use crate::registration::registrant::Registrant;
+use crate::result::place::Place;
+use crate::utils::create_folder;
+
+const DATABASE_FILENAME: &str = "results.sqlite";
+
+/// Version of the way results and registrants are turned into rows. Bump it whenever parsing changes,
+/// so that conventions ingested by a previous version are ingested again even if their files haven't changed.
+const PARSER_VERSION: u32 = 1;
+
+/// Registrants, competitors and results are scoped to their convention, since registration ids are only unique within it.
+/// A competitor has one member if they are an individual, and one member for each registration id of a team.
+/// Members whose registration id has no registrant have no `registrant_id`.
+/// The snapshot of the files each convention was ingested from is kept in `ingestions`, along with the raw results lines
+/// so that they don't have to be parsed again as long as the files don't change.
+const SCHEMA: &str = "
+    PRAGMA foreign_keys = ON;
+
+    CREATE TABLE IF NOT EXISTS conventions (
+        tag TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        start_date TEXT,
+        end_date TEXT,
+        location TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS persons (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        birthday TEXT NOT NULL,
+        given_names TEXT NOT NULL DEFAULT '',
+        particles TEXT NOT NULL DEFAULT '',
+        family_names TEXT NOT NULL DEFAULT '',
+        name_order TEXT NOT NULL DEFAULT 'given_first',
+        romanized_name TEXT NOT NULL DEFAULT ''
+    );
+
+    CREATE TABLE IF NOT EXISTS registrants (
+        id INTEGER PRIMARY KEY,
+        convention_tag TEXT NOT NULL REFERENCES conventions(tag),
+        registration_id INTEGER NOT NULL,
+        person_id TEXT REFERENCES persons(id),
+        first_name TEXT NOT NULL,
+        last_name TEXT NOT NULL,
+        birthday TEXT NOT NULL,
+        country TEXT,
+        club TEXT,
+        UNIQUE (convention_tag, registration_id)
+    );
+    CREATE INDEX IF NOT EXISTS registrants_person_id ON registrants(person_id);
+
+    CREATE TABLE IF NOT EXISTS competitions (
+        id INTEGER PRIMARY KEY,
+        convention_tag TEXT NOT NULL REFERENCES conventions(tag),
+        name TEXT NOT NULL,
+        discipline TEXT NOT NULL,
+        UNIQUE (convention_tag, name)
+    );
+    CREATE INDEX IF NOT EXISTS competitions_discipline ON competitions(discipline);
+
+    CREATE TABLE IF NOT EXISTS competitors (
+        id INTEGER PRIMARY KEY,
+        convention_tag TEXT NOT NULL REFERENCES conventions(tag),
+        kind TEXT NOT NULL CHECK (kind IN ('Individual', 'Team')),
+        name TEXT,
+        gender TEXT,
+        age INTEGER
+    );
+
+    CREATE TABLE IF NOT EXISTS competitor_members (
+        competitor_id INTEGER NOT NULL REFERENCES competitors(id),
+        registration_id INTEGER NOT NULL,
+        registrant_id INTEGER REFERENCES registrants(id),
+        PRIMARY KEY (competitor_id, registration_id)
+    );
+    CREATE INDEX IF NOT EXISTS competitor_members_registrant_id ON competitor_members(registrant_id);
+
+    CREATE TABLE IF NOT EXISTS results (
+        id INTEGER PRIMARY KEY,
+        competition_id INTEGER NOT NULL REFERENCES competitions(id),
+        competitor_id INTEGER NOT NULL REFERENCES competitors(id),
+        result_type TEXT NOT NULL CHECK (result_type IN ('Overall', 'AgeGroup')),
+        place TEXT NOT NULL,
+        rank INTEGER,
+        result TEXT,
+        details TEXT,
+        age_group TEXT
+    );
+    CREATE INDEX IF NOT EXISTS results_competition_id ON results(competition_id);
+    CREATE INDEX IF NOT EXISTS results_competitor_id ON results(competitor_id);
+
+    CREATE VIEW IF NOT EXISTS person_results AS
+    SELECT persons.id AS person_id, persons.name AS person_name, competitions.convention_tag, competitions.name AS competition,
+           competitions.discipline, results.result_type, results.place, results.rank, results.result, results.age_group,
+           competitors.kind AS competitor_kind
+    FROM persons
+    JOIN registrants ON registrants.person_id = persons.id
+    JOIN competitor_members ON competitor_members.registrant_id = registrants.id
+    JOIN competitors ON competitors.id = competitor_members.competitor_id
+    JOIN results ON results.competitor_id = competitors.id
+    JOIN competitions ON competitions.id = results.competition_id;
+
+    CREATE TABLE IF NOT EXISTS ingestions (
+        convention_tag TEXT PRIMARY KEY REFERENCES conventions(tag),
+        results_hash TEXT NOT NULL,
+        registrants_hash TEXT NOT NULL,
+        downloaded_at TEXT,
+        ingested_at TEXT NOT NULL,
+        parser_version INTEGER NOT NULL DEFAULT 0,
+        details_hash TEXT NOT NULL DEFAULT ''
+    );
+
+    CREATE TABLE IF NOT EXISTS raw_results (
+        convention_tag TEXT NOT NULL REFERENCES conventions(tag),
+        line INTEGER NOT NULL,
+        ids TEXT NOT NULL,
+        name TEXT NOT NULL,
+        gender TEXT NOT NULL,
+        age INTEGER NOT NULL,
+        competition TEXT NOT NULL,
+        place TEXT NOT NULL,
+        result_type TEXT NOT NULL,
+        result TEXT NOT NULL,
+        details TEXT NOT NULL,
+        age_group TEXT NOT NULL,
+        PRIMARY KEY (convention_tag, line)
+    );
+";
+
+/// Columns added to `persons` after it was first created, with their definition.
+const PERSON_NAME_COLUMNS: [(&str, &str); 5] = [
+    ("given_names", "TEXT NOT NULL DEFAULT ''"),
+    ("particles", "TEXT NOT NULL DEFAULT ''"),
+    ("family_names", "TEXT NOT NULL DEFAULT ''"),
+    ("name_order", "TEXT NOT NULL DEFAULT 'given_first'"),
+    ("romanized_name", "TEXT NOT NULL DEFAULT ''"),
+];
+
+/// Columns added to `ingestions` after it was first created, with their definition.
+const INGESTION_COLUMNS: [(&str, &str); 2] = [
+    ("parser_version", "INTEGER NOT NULL DEFAULT 0"),
+    ("details_hash", "TEXT NOT NULL DEFAULT ''"),
+];
+
+/// Everything that has been ingested from a convention, children first so that foreign keys hold.
+const DELETE_CONVENTION_STATEMENTS: [&str; 8] = [
+    "DELETE FROM results WHERE competition_id IN (SELECT id FROM competitions WHERE convention_tag = ?1)",
+    "DELETE FROM competitor_members WHERE competitor_id IN (SELECT id FROM competitors WHERE convention_tag = ?1)",
+    "DELETE FROM competitors WHERE convention_tag = ?1",
+    "DELETE FROM competitions WHERE convention_tag = ?1",
+    "DELETE FROM registrants WHERE convention_tag = ?1",
+    "DELETE FROM raw_results WHERE convention_tag = ?1",
+    "DELETE FROM ingestions WHERE convention_tag = ?1",
+    "DELETE FROM conventions WHERE tag = ?1",
+];
+
+/// The dataset as a normalized SQLite database in `{folder}/results.sqlite`, so that it can be queried with SQL.
+/// The database is kept between runs: only the conventions whose files changed since they were ingested are written again.
+pub struct ResultsDatabase {
+    connection: Connection,
+    birthday_precision: BirthdayPrecision,
+}
+
+impl ResultsDatabase {
+    /// Open the database in `{folder}/results.sqlite`, creating it if it doesn't exist yet.
+    pub fn open(folder: &str) -> Result<Self, ()> {
+        create_folder(folder, &format!("Can't create export folder [folder: {folder}]"))?;
+        let filepath = format!("{folder}/{DATABASE_FILENAME}");
+        match Connection::open(&filepath).and_then(Self::from_connection) {
+            Ok(database) => { Ok(database) }
+            Err(error) => {
+                warn!("Can't open SQLite database, every convention will be ingested again [filepath: {filepath}]");
+                warn!("{}", error);
+                Err(())
+            }
+        }
+    }
+
+    fn from_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        add_missing_columns(&connection, "persons", &PERSON_NAME_COLUMNS)?;
+        add_missing_columns(&connection, "ingestions", &INGESTION_COLUMNS)?;
+        Ok(Self { connection, birthday_precision: BirthdayPrecision::default() })
+    }
+
+    /// Birthdays of registrants and persons are written with this precision, see [crate::export::privacy::PrivacyPolicy].
+    pub fn with_birthday_precision(self, birthday_precision: BirthdayPrecision) -> Self {
+        Self { birthday_precision, ..self }
+    }
+
+    /// The snapshot of the files the convention was last ingested from, if it has ever been ingested by the current parser version
+    /// with the current details: ages of competitors depend on the convention dates, which may be backfilled or overridden later.
+    pub fn ingested_snapshot(&self, convention: &Convention) -> Option<ConventionSnapshot> {
+        let snapshot = self.connection.query_row(
+            "SELECT results_hash, registrants_hash, downloaded_at FROM ingestions WHERE convention_tag = ?1 AND parser_version = ?2 AND details_hash = ?3",
+            params![convention.tag(), PARSER_VERSION, hash_details(convention)],
+            |row| {
+                let downloaded_at: Option<String> = row.get(2)?;
+                let downloaded_at = downloaded_at
+                    .and_then(|downloaded_at| DateTime::parse_from_rfc3339(&downloaded_at).ok())
+                    .map(|downloaded_at| downloaded_at.with_timezone(&Utc));
+                Ok(ConventionSnapshot::new(row.get(0)?, row.get(1)?, downloaded_at))
+            },
+        ).optional();
+        match snapshot {
+            Ok(snapshot) => { snapshot }
+            Err(error) => {
+                warn!("Can't read ingestion of convention [convention: {}]", convention.tag());
+                warn!("{}", error);
+                None
+            }
+        }
+    }
+
+    /// The raw results lines the convention was last ingested from, in the order of the results file.
+    pub fn load_raw_results(&self, convention: &Convention) -> Result<Vec<RawResult>, ()> {
+        let raw_results = self.connection
+            .prepare("SELECT ids, name, gender, age, competition, place, result_type, result, details, age_group
+                      FROM raw_results WHERE convention_tag = ?1 ORDER BY line")
+            .and_then(|mut statement| {
+                statement.query_map(params![convention.tag()], |row| {
+                    Ok(RawResult::new(row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?,
+                                      row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?))
+                })?.collect::<rusqlite::Result<Vec<RawResult>>>()
+            });
+        raw_results.map_err(|error| {
+            warn!("Can't read ingested raw results [convention: {}]", convention.tag());
+            warn!("{}", error);
+        })
+    }
+
+    /// The raw results lines the convention was last ingested from, if its files haven't changed since then.
+    pub fn load_unchanged_raw_results(&self, convention: &Convention, snapshot: &ConventionSnapshot) -> Option<Vec<RawResult>> {
+        let ingested_snapshot = self.ingested_snapshot(convention)?;
+        if !ingested_snapshot.has_same_content(snapshot) {
+            return None;
+        }
+        self.load_raw_results(convention).ok()
+    }
+
+    /// Ingest again every convention that isn't in `unchanged_conventions`, along with the snapshot of its files,
+    /// and remove the conventions that aren't extracted anymore. People are written again as a whole,
+    /// since they may be gathered differently from a run to another, their ids being kept by the person id registry.
+    pub fn update(&mut self,
+                  snapshots: &HashMap<&Convention, ConventionSnapshot>,
+                  unchanged_conventions: &HashSet<&Convention>,
+                  registrants: &HashMap<&Convention, Vec<Registrant>>,
+                  raw_results: &HashMap<&Convention, Vec<RawResult>>,
+                  conventions_results: &HashMap<&Convention, ResultStore>,
+                  people: &[Person]) -> Result<(), ()> {
+        let result = self.write(snapshots, unchanged_conventions, registrants, raw_results, conventions_results, people);
+        match result {
+            Ok(ingested_count) => {
+                info!("SQLite database updated [ingested_conventions: {ingested_count}, unchanged_conventions: {}]", unchanged_conventions.len());
+                Ok(())
+            }
+            Err(error) => {
+                warn!("Can't update SQLite database");
+                warn!("{}", error);
+                Err(())
+            }
+        }
+    }
+
+    fn write(&mut self,
+             snapshots: &HashMap<&Convention, ConventionSnapshot>,
+             unchanged_conventions: &HashSet<&Convention>,
+             registrants: &HashMap<&Convention, Vec<Registrant>>,
+             raw_results: &HashMap<&Convention, Vec<RawResult>>,
+             conventions_results: &HashMap<&Convention, ResultStore>,
+             people: &[Person]) -> rusqlite::Result<usize> {
+        let transaction = self.connection.transaction()?;
+
+        let mut conventions: Vec<&&Convention> = registrants.keys().chain(conventions_results.keys()).collect();
+        conventions.sort_by(|a, b| a.compare_chronologically(b));
+        conventions.dedup();
+        let tags: HashSet<&str> = conventions.iter().map(|convention| convention.tag().as_str()).collect();
+
+        let ingested_tags: Vec<String> = transaction.prepare("SELECT tag FROM conventions")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for tag in ingested_tags.iter().filter(|tag| !tags.contains(tag.as_str())) {
+            delete_convention(&transaction, tag)?;
+        }
+
+        let ingested_at = Utc::now().to_rfc3339();
+        let mut ingested_count = 0;
+        for convention in &conventions {
+            let snapshot = snapshots.get(**convention);
+            if unchanged_conventions.contains(**convention) {
+                transaction.execute(
+                    "UPDATE ingestions SET downloaded_at = ?2 WHERE convention_tag = ?1",
+                    params![convention.tag(), snapshot.and_then(|snapshot| *snapshot.downloaded_at()).map(|date| date.to_rfc3339())],
+                )?;
+                continue;
+            }
+
+            delete_convention(&transaction, convention.tag())?;
+            insert_convention(&transaction, convention, registrants, raw_results, conventions_results, self.birthday_precision)?;
+            if let Some(snapshot) = snapshot {
+                transaction.execute(
+                    "INSERT INTO ingestions (convention_tag, results_hash, registrants_hash, downloaded_at, ingested_at, parser_version, details_hash) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![convention.tag(), snapshot.results_hash(), snapshot.registrants_hash(),
+                        snapshot.downloaded_at().map(|date| date.to_rfc3339()), ingested_at, PARSER_VERSION, hash_details(convention)],
+                )?;
+            }
+            ingested_count += 1;
+        }
+
+        transaction.execute("UPDATE registrants SET person_id = NULL", [])?;
+        transaction.execute("DELETE FROM persons", [])?;
+        for person in people {
+            let person_name = person.identity().person_name();
+            transaction.execute(
+                "INSERT INTO persons (id, name, birthday, given_names, particles, family_names, name_order, romanized_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    person.id().to_string(),
+                    person_name.name(),
+                    self.birthday_precision.format(person.identity().birthday()),
+                    person_name.given_names().join(" "),
+                    person_name.particles().join(" "),
+                    person_name.family_names().join(" "),
+                    person_name.name_order().to_string(),
+                    person_name.romanized_name(),
+                ],
+            )?;
+            for (convention, ids) in person.registrations_id() {
+                for id in ids {
+                    transaction.execute(
+                        "UPDATE registrants SET person_id = ?1 WHERE convention_tag = ?2 AND registration_id = ?3",
+                        params![person.id().to_string(), convention.tag(), id],
+                    )?;
+                }
+            }
+        }
+
+        transaction.commit()?;
+        Ok(ingested_count)
+    }
+}
+
+/// Hash of the name, dates and location of the convention, as they are written in `conventions` and used to compute ages.
+fn hash_details(convention: &Convention) -> String {
+    let details = format!("{}\n{:?}\n{:?}\n{:?}", convention.name(), convention.start_date(), convention.end_date(), convention.location());
+    format!("{:x}", Sha256::digest(details))
+}
+
+fn delete_convention(transaction: &Transaction, tag: &str) -> rusqlite::Result<()> {
+    for statement in DELETE_CONVENTION_STATEMENTS {
+        transaction.execute(statement, params![tag])?;
+    }
+    Ok(())
+}
+
+fn insert_convention(transaction: &Transaction,
+                     convention: &Convention,
+                     registrants: &HashMap<&Convention, Vec<Registrant>>,
+                     raw_results: &HashMap<&Convention, Vec<RawResult>>,
+                     conventions_results: &HashMap<&Convention, ResultStore>,
+                     birthday_precision: BirthdayPrecision) -> rusqlite::Result<()> {
+    transaction.execute(
+        "INSERT INTO conventions (tag, name, start_date, end_date, location) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![convention.tag(), convention.name(), convention.start_date().map(|date| date.to_string()),
+            convention.end_date().map(|date| date.to_string()), convention.location()],
+    )?;
+
+    let mut registrants_ids: HashMap<u16, i64> = HashMap::new();
+    for registrant in registrants.get(convention).map(Vec::as_slice).unwrap_or_default() {
+        let country = Country::from_string(registrant.country()).ok().map(|country| country.code().to_string());
+        transaction.execute(
+            "INSERT INTO registrants (convention_tag, registration_id, first_name, last_name, birthday, country, club)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![convention.tag(), registrant.id(), registrant.first_name(), registrant.last_name(),
+                birthday_precision.format(registrant.birthday()), country, Some(registrant.club()).filter(|club| !club.is_empty())],
+        )?;
+        registrants_ids.insert(*registrant.id(), transaction.last_insert_rowid());
+    }
+
+    for (line, raw_result) in raw_results.get(convention).map(Vec::as_slice).unwrap_or_default().iter().enumerate() {
+        transaction.execute(
+            "INSERT INTO raw_results (convention_tag, line, ids, name, gender, age, competition, place, result_type, result, details, age_group)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![convention.tag(), line as i64, raw_result.ids(), raw_result.name(), raw_result.gender(), raw_result.age(),
+                raw_result.competition(), raw_result.place(), raw_result.result_type(), raw_result.result(),
+                raw_result.details(), raw_result.age_group()],
+        )?;
+    }
+
+    let Some(results) = conventions_results.get(convention) else { return Ok(()); };
+    let mut competitions_ids: HashMap<&str, i64> = HashMap::new();
+    let mut competitors_ids: HashMap<&Competitor, i64> = HashMap::new();
+    for result in results.results() {
+        let competition_id = match competitions_ids.get(result.competition().name().as_str()) {
+            Some(competition_id) => { *competition_id }
+            None => {
+                let competition_id = insert_competition(transaction, convention, result)?;
+                competitions_ids.insert(result.competition().name(), competition_id);
+                competition_id
+            }
+        };
+        let competitor_id = match competitors_ids.get(result.competitor()) {
+            Some(competitor_id) => { *competitor_id }
+            None => {
+                let competitor_id = insert_competitor(transaction, convention, result.competitor(), &registrants_ids)?;
+                competitors_ids.insert(result.competitor(), competitor_id);
+                competitor_id
+            }
+        };
+        let rank = match result.place() {
+            Place::Rank(rank) => { Some(*rank) }
+            Place::Disqualified(_) => { None }
+        };
+        transaction.execute(
+            "INSERT INTO results (competition_id, competitor_id, result_type, place, rank, result, details, age_group)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![competition_id, competitor_id, result.result_type().to_string(), result.place().to_string(), rank,
+                result.result().as_ref().map(|value| value.to_string()), result.details(),
+                result.age_group().as_ref().map(|age_group| age_group.to_string())],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_competition(transaction: &Transaction, convention: &Convention, result: &CompetitionResult) -> rusqlite::Result<i64> {
+    transaction.execute(
+        "INSERT INTO competitions (convention_tag, name, discipline) VALUES (?1, ?2, ?3)",
+        params![convention.tag(), result.competition().name(), Discipline::from_competition(result.competition()).name()],
+    )?;
+    Ok(transaction.last_insert_rowid())
+}
+
+fn insert_competitor(transaction: &Transaction,
+                     convention: &Convention,
+                     competitor: &Competitor,
+                     registrants_ids: &HashMap<u16, i64>) -> rusqlite::Result<i64> {
+    let (kind, age) = match competitor {
+        Competitor::IndividualCompetitor(individual) => { ("Individual", *individual.age()) }
+        Competitor::UnknownIndividualCompetitor(unknown) => { ("Individual", *unknown.age()) }
+        Competitor::Team(_) => { ("Team", None) }
+    };
+    transaction.execute(
+        "INSERT INTO competitors (convention_tag, kind, name, gender, age) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![convention.tag(), kind, competitor.name(), competitor.gender().map(|gender| gender.to_string()), age],
+    )?;
+    let competitor_id = transaction.last_insert_rowid();
+
+    let mut ids = competitor.ids();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        transaction.execute(
+            "INSERT INTO competitor_members (competitor_id, registration_id, registrant_id) VALUES (?1, ?2, ?3)",
+            params![competitor_id, id, registrants_ids.get(&id)],
+        )?;
+    }
+    Ok(competitor_id)
+}
+
+/// Databases created before a column was added to the table don't have it, since the schema only creates missing tables.
+fn add_missing_columns(connection: &Connection, table: &str, columns: &[(&str, &str)]) -> rusqlite::Result<()> {
+    let existing_columns: HashSet<String> = connection
+        .prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (column, definition) in columns {
+        if !existing_columns.contains(*column) {
+            connection.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), [])?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use chrono::NaiveDate;
+    use rusqlite::Connection;
+
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::result_store::ResultStore;
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::individual_competitor::IndividualCompetitor;
+    use crate::competitor::team::Team;
+    use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
+    use crate::convention::convention::Convention;
+    use crate::convention::convention_snapshot::ConventionSnapshot;
+    use crate::export::sqlite::ResultsDatabase;
+    use crate::person::identity::Identity;
+    use crate::person::person::Person;
+    use crate::person::person_name::{NameOrder, PersonName};
+    use crate::raw_result::raw_result::RawResult;
+    use crate::registration::registrant::Registrant;
+
+    fn create_raw_result(ids: &str, competition: &str, place: &str) -> RawResult {
+        RawResult::new(ids.to_string(), String::new(), "Male".to_string(), 22, competition.to_string(), place.to_string(),
+                       "Overall".to_string(), "00:14.990".to_string(), String::new(), String::new())
+    }
+
+    fn count(database: &ResultsDatabase, table: &str) -> i64 {
+        database.connection.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn should_write_normalized_database() {
+        let convention = Convention::create_test_instance("unicon2022");
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let registrants = HashMap::from([(&convention, vec![
+            Registrant::create_test_instance(1, "John", "Doe", birthday),
+            Registrant::create_test_instance(2, "Jane", "Doe", birthday),
+        ])]);
+        let john = Competitor::IndividualCompetitor(IndividualCompetitor::new(1, "John Doe".to_string(), None, Some(22)));
+        let team = Competitor::Team(Team::new("Team Doe".to_string(), None, vec![
+            john.clone(),
+            Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(3)),
+        ]));
+        let results = HashMap::from([(&convention, ResultStore::new(vec![
+            CompetitionResult::create_test_instance(1, "10km Male", "1", "00:14.990").with_competitor(john.clone()),
+            CompetitionResult::create_test_instance(1, "100m", "DNF", "00:14.990").with_competitor(john),
+            CompetitionResult::create_test_instance(1, "Relay", "2", "00:14.990").with_competitor(team),
+        ]))]);
+        let people = vec![Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1])];
+        let mut database = ResultsDatabase::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+
+        database.update(&HashMap::new(), &HashSet::new(), &registrants, &HashMap::new(), &results, &people).unwrap();
+
+        let counts = ["registrants", "competitions", "competitors", "competitor_members", "results"].map(|table| count(&database, table));
+        assert_eq!(counts, [2, 3, 2, 3, 3]);
+        let john_results: Vec<(String, Option<u16>)> = database.connection
+            .prepare("SELECT discipline, rank FROM person_results WHERE person_name = 'John Doe' ORDER BY discipline").unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(john_results, vec![("100m".to_string(), None), ("10km".to_string(), Some(1)), ("relay".to_string(), Some(2))]);
+    }
+
+    #[test]
+    fn should_only_ingest_changed_conventions() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let removed_convention = Convention::create_test_instance("cfm2021");
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let registrants = HashMap::from([(&convention, vec![Registrant::create_test_instance(1, "John", "Doe", birthday)])]);
+        let raw_results = HashMap::from([(&convention, vec![create_raw_result("1", "100m", "1"), create_raw_result("1", "200m", "2")])]);
+        let snapshot = ConventionSnapshot::new("abc".to_string(), "def".to_string(), None);
+        let snapshots = HashMap::from([(&convention, snapshot.clone())]);
+        let mut database = ResultsDatabase::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let old_registrants = HashMap::from([(&removed_convention, vec![Registrant::create_test_instance(1, "Jack", "Doe", birthday)])]);
+        database.update(&HashMap::new(), &HashSet::new(), &old_registrants, &HashMap::new(), &HashMap::new(), &[]).unwrap();
+
+        database.update(&snapshots, &HashSet::new(), &registrants, &raw_results, &HashMap::new(), &[Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1])]).unwrap();
+        database.update(&snapshots, &HashSet::from([&convention]), &registrants, &HashMap::new(), &HashMap::new(), &[Person::create_test_instance("Johnny Doe").with_registrations_id(&convention, vec![1])]).unwrap();
+
+        assert_eq!(database.ingested_snapshot(&convention), Some(snapshot));
+        assert_eq!(database.load_raw_results(&convention).unwrap().iter().map(|raw_result| raw_result.competition().clone()).collect::<Vec<String>>(), vec!["100m", "200m"]);
+        assert_eq!(count(&database, "conventions"), 1);
+        let person_name: String = database.connection.query_row(
+            "SELECT persons.name FROM registrants JOIN persons ON persons.id = registrants.person_id", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(person_name, "Johnny Doe");
+    }
+
+    #[test]
+    fn should_ingest_again_conventions_ingested_by_another_parser_version() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![Registrant::create_test_instance(1, "John", "Doe", NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())])]);
+        let raw_results = HashMap::from([(&convention, vec![create_raw_result("1", "100m", "1")])]);
+        let snapshot = ConventionSnapshot::new("abc".to_string(), "def".to_string(), None);
+        let snapshots = HashMap::from([(&convention, snapshot.clone())]);
+        let mut database = ResultsDatabase::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        database.update(&snapshots, &HashSet::new(), &registrants, &raw_results, &HashMap::new(), &[]).unwrap();
+        assert_eq!(database.load_unchanged_raw_results(&convention, &snapshot).map(|raw_results| raw_results.len()), Some(1));
+
+        database.connection.execute("UPDATE ingestions SET parser_version = 0", []).unwrap();
+
+        assert_eq!(database.ingested_snapshot(&convention), None);
+        assert!(database.load_unchanged_raw_results(&convention, &snapshot).is_none());
+    }
+
+    #[test]
+    fn should_ingest_again_conventions_whose_details_changed() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![Registrant::create_test_instance(1, "John", "Doe", NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())])]);
+        let raw_results = HashMap::from([(&convention, vec![create_raw_result("1", "100m", "1")])]);
+        let snapshot = ConventionSnapshot::new("abc".to_string(), "def".to_string(), None);
+        let mut database = ResultsDatabase::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        database.update(&HashMap::from([(&convention, snapshot.clone())]), &HashSet::new(), &registrants, &raw_results, &HashMap::new(), &[]).unwrap();
+
+        let dated_convention = convention.clone().with_missing_details(NaiveDate::from_ymd_opt(2022, 5, 26), None, None);
+
+        assert!(database.load_unchanged_raw_results(&convention, &snapshot).is_some());
+        assert!(database.load_unchanged_raw_results(&dated_convention, &snapshot).is_none());
+    }
+
+    #[test]
+    fn should_keep_the_parts_of_names() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let person_name = PersonName::from_names("Anna Maria", "van der Berg", NameOrder::FamilyFirst);
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch("CREATE TABLE persons (id TEXT PRIMARY KEY, name TEXT NOT NULL, birthday TEXT NOT NULL);").unwrap();
+        let mut database = ResultsDatabase::from_connection(connection).unwrap();
+        let person = Person::create_test_instance("John Doe")
+            .with_identity(Identity::new(person_name.clone(), NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()))
+            .with_registrations_id(&convention, vec![1]);
+
+        database.update(&HashMap::new(), &HashSet::new(), &HashMap::new(), &HashMap::new(), &HashMap::new(), &[person]).unwrap();
+
+        let (name, read_name): (String, PersonName) = database.connection.query_row(
+            "SELECT name, given_names, particles, family_names, name_order FROM persons", [],
+            |row| {
+                let words = |index: usize| -> rusqlite::Result<Vec<String>> {
+                    Ok(row.get::<_, String>(index)?.split_whitespace().map(str::to_string).collect())
+                };
+                let name_order = if row.get::<_, String>(4)? == NameOrder::FamilyFirst.to_string() { NameOrder::FamilyFirst } else { NameOrder::GivenFirst };
+                Ok((row.get(0)?, PersonName::from_parts(words(1)?, words(2)?, words(3)?, name_order)))
+            },
+        ).unwrap();
+        assert_eq!(name, "van der Berg Anna Maria");
+        assert_eq!(read_name.name(), person_name.name());
+        assert_eq!(read_name.particles(), person_name.particles());
+    }
+}
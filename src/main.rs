@@ -1,13 +1,41 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use calamine::{Error, open_workbook, RangeDeserializerBuilder, Reader, Xls};
-use log::{error, warn};
+use log::{error, info, warn};
 
-use crate::convention::convention::{compute_conventions_to_download, Convention, dump_conventions, load_conventions_from_folder};
-use crate::download::download_data;
+#[cfg(feature = "serve")]
+use crate::api::dataset::ApiDataset;
+#[cfg(feature = "serve")]
+use crate::api::server::{DEFAULT_SERVE_ADDRESS, serve};
+use crate::competition::discipline_family::DisciplineFamilies;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::team::export_team_results;
+use crate::convention::convention::{apply_conventions_overrides, compute_conventions_to_backfill, compute_conventions_to_download, Convention, dump_conventions, load_conventions_from_folder};
+use crate::convention::convention_snapshot::ConventionSnapshot;
+use crate::download::{backfill_conventions_details, download_data};
+use crate::export::{EXPORT_FOLDER, PUBLIC_EXPORT_FOLDER};
+use crate::export::privacy::PrivacyPolicy;
+use crate::export::sqlite::ResultsDatabase;
+use crate::person::identity_matcher::{export_merge_candidates, find_merge_candidates, merge_people, MergeCandidate};
+use crate::person::identity_overrides::IdentityOverrides;
+use crate::person::name_aliases::NameAliases;
+use crate::person::name_order::NameOrders;
 use crate::person::person::create_people;
+use crate::person::person_id_registry::PersonIdRegistry;
+use crate::person::person_search::run_search_command;
+use crate::person::teammate::export_teammates;
+use crate::record::age_grading::AgeGradingTable;
+use crate::record::leaderboard::{compute_age_graded_leaderboards, compute_leaderboards, export_leaderboards};
+use crate::record::medal_table::{compute_medal_table, export_medal_table};
+use crate::record::personal_record::export_personal_records;
+use crate::record::rating::{compute_ratings, export_ratings};
+use crate::record::record_table::{compute_record_table, export_record_table, RecordScope};
+use crate::record::season_ranking::{compute_season_ranking, export_season_ranking, SeasonRankingConfig};
+use crate::result::result_direction::ResultDirections;
 use crate::raw_result::raw_result::{get_results_from_raw_results_lines, load_raw_results_for_conventions, RawResult};
+use crate::raw_result::results_diff::run_diff_command;
 use crate::registration::registrant::load_registrants_for_conventions;
+use crate::site::site::{generate_site, SITE_FOLDER};
 use crate::utils::DATA_FOLDER;
 use crate::utils::env_manager::retrieve_env_value;
 
@@ -16,16 +44,60 @@ mod convention;
 mod result;
 mod person;
 mod competitor;
+mod country;
 mod registration;
 mod raw_result;
 mod download;
 mod utils;
+mod export;
+mod record;
+mod site;
+#[cfg(feature = "serve")]
+mod api;
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
 
-    let conventions = match load_conventions().await {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let privacy_policy = match retrieve_env_value("PRIVACY_MODE").as_deref() {
+        Some("true") => {
+            match PrivacyPolicy::load_from_folder(DATA_FOLDER) {
+                Ok(privacy_policy) => { Some(privacy_policy) }
+                Err(_) => {
+                    error!("Aborting process");
+                    return;
+                }
+            }
+        }
+        _ => { None }
+    };
+    let export_folder = if privacy_policy.is_some() { PUBLIC_EXPORT_FOLDER } else { EXPORT_FOLDER };
+    let birthday_precision = privacy_policy.as_ref().map(PrivacyPolicy::birthday_precision).unwrap_or_default();
+    match args.first().map(String::as_str) {
+        Some("diff") => {
+            let privacy_policy = privacy_policy.map(|privacy_policy| privacy_policy.with_person_ids(&PersonIdRegistry::load_from_folder(DATA_FOLDER)));
+            if run_diff_command(DATA_FOLDER, export_folder, &args[1..], privacy_policy.as_ref()).is_err() {
+                error!("Can't diff results");
+            }
+            return;
+        }
+        #[cfg(not(feature = "serve"))]
+        Some("serve") => {
+            error!("Serve mode isn't available, build with `--features serve`");
+            return;
+        }
+        #[cfg(feature = "serve")]
+        Some("serve") => {}
+        None | Some("refresh") | Some("site") | Some("search") => {}
+        Some(command) => {
+            error!("Unknown command [command: {command}]");
+            return;
+        }
+    }
+
+    let refresh = args.first().is_some_and(|command| command == "refresh");
+    let conventions = match load_conventions(refresh).await {
         Ok(conventions) => { conventions }
         Err(_) => {
             error!("Aborting process");
@@ -41,16 +113,127 @@ async fn main() {
             return;
         }
     };
-    let raw_results = load_raw_results_for_conventions(&conventions);
+    let snapshots: HashMap<&Convention, ConventionSnapshot> = conventions.iter()
+        .filter_map(|convention| match ConventionSnapshot::from_folder(DATA_FOLDER, convention) {
+            Ok(snapshot) => { Some((convention, snapshot)) }
+            Err(error) => {
+                warn!("Can't take snapshot of convention, it will be ingested again [convention: {}]: {error}", convention.tag());
+                None
+            }
+        })
+        .collect();
+    let mut database = ResultsDatabase::open(export_folder).ok().map(|database| database.with_birthday_precision(birthday_precision));
+    // In privacy mode, the database holds anonymized raw results: every convention is ingested again so that the current policy applies to all of them
+    let ingested_database = database.as_ref().filter(|_| privacy_policy.is_none());
+    let (raw_results, unchanged_conventions) = load_raw_results_for_conventions(&conventions, |convention| {
+        ingested_database?.load_unchanged_raw_results(convention, snapshots.get(convention)?)
+    });
     let results = raw_results.iter()
-        .map(|(convention, raw_results)| (*convention, get_results_from_raw_results_lines(raw_results)))
+        .map(|(convention, raw_results)| {
+            let convention_registrants = registrants.get(convention).map(Vec::as_slice).unwrap_or_default();
+            (*convention, ResultStore::new(get_results_from_raw_results_lines(raw_results, convention, convention_registrants)))
+        })
         .collect();
-    let people = create_people(&registrants, &results);
+    let identity_overrides = IdentityOverrides::load_from_folder(DATA_FOLDER);
+    let name_orders = NameOrders::load_from_folder(DATA_FOLDER);
+    let name_aliases = NameAliases::load_from_folder(DATA_FOLDER);
+    let people = create_people(&registrants, &results, &identity_overrides, &name_orders, &name_aliases);
+    let merge_candidates = find_merge_candidates(&people, &identity_overrides);
+    let (auto_merges, candidates_to_review): (Vec<&MergeCandidate>, Vec<&MergeCandidate>) = merge_candidates.iter()
+        .partition(|candidate| candidate.is_auto_mergeable(&people));
+    if privacy_policy.is_some() {
+        info!("Merge candidates aren't exported in privacy mode, as they are meant to be reviewed");
+    } else if export_merge_candidates(EXPORT_FOLDER, &people, &candidates_to_review).is_err() {
+        warn!("Can't export merge candidates. However, process will continue.");
+    }
+    let people = merge_people(people, &auto_merges, &identity_overrides);
+    let mut person_id_registry = PersonIdRegistry::load_from_folder(DATA_FOLDER);
+    let people = person_id_registry.assign_ids(people);
+    if person_id_registry.dump(DATA_FOLDER).is_err() {
+        warn!("Can't dump people ids, they will change on next run. However, process will continue.");
+    }
+    let privacy_policy = privacy_policy.map(|privacy_policy| privacy_policy.with_person_ids(&person_id_registry));
+    let (registrants, raw_results, results, people) = match &privacy_policy {
+        Some(privacy_policy) => {
+            let raw_results = raw_results.into_iter()
+                .map(|(convention, raw_results)| (convention, privacy_policy.anonymize_raw_results(convention.tag(), raw_results)))
+                .collect();
+            (privacy_policy.anonymize_registrants(registrants), raw_results, privacy_policy.anonymize_results(results), privacy_policy.anonymize_people(people))
+        }
+        None => { (registrants, raw_results, results, people) }
+    };
+    let result_directions = ResultDirections::load_from_folder(DATA_FOLDER);
+    match args.first().map(String::as_str) {
+        Some("search") => {
+            if run_search_command(&people, &args[1..]).is_err() {
+                error!("Can't search people");
+            }
+            return;
+        }
+        Some("site") => {
+            if generate_site(&format!("{export_folder}/{SITE_FOLDER}"), &conventions, &results, &people, &result_directions).is_err() {
+                error!("Can't generate site");
+            }
+            return;
+        }
+        #[cfg(feature = "serve")]
+        Some("serve") => {
+            let address = retrieve_env_value("SERVE_ADDRESS").unwrap_or(DEFAULT_SERVE_ADDRESS.to_string());
+            if serve(&address, ApiDataset::new(&conventions, &results, &people, &result_directions).with_birthday_precision(birthday_precision)).await.is_err() {
+                error!("Can't serve results");
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    if let Some(database) = database.as_mut() {
+        if database.update(&snapshots, &unchanged_conventions, &registrants, &raw_results, &results, &people).is_err() {
+            warn!("Can't update SQLite database. However, process will continue.");
+        }
+    }
+    if export_personal_records(export_folder, &people, birthday_precision, &result_directions).is_err() {
+        warn!("Can't export personal records. However, process will continue.");
+    }
+    if export_leaderboards(export_folder, &compute_leaderboards(&people, &result_directions), false).is_err() {
+        warn!("Can't export leaderboards. However, process will continue.");
+    }
+    let age_grading_table = AgeGradingTable::load_from_folder(DATA_FOLDER);
+    if export_leaderboards(export_folder, &compute_age_graded_leaderboards(&people, &age_grading_table, &result_directions), true).is_err() {
+        warn!("Can't export age-graded leaderboards. However, process will continue.");
+    }
+    let discipline_families = DisciplineFamilies::load_from_folder(DATA_FOLDER);
+    if export_ratings(export_folder, &compute_ratings(&people, &discipline_families)).is_err() {
+        warn!("Can't export ratings. However, process will continue.");
+    }
+    for season_ranking_config in SeasonRankingConfig::load_from_folder(DATA_FOLDER) {
+        if export_season_ranking(export_folder, &compute_season_ranking(&people, &season_ranking_config)).is_err() {
+            warn!("Can't export season ranking [season: {}]. However, process will continue.", season_ranking_config.tag());
+        }
+    }
+    if export_teammates(export_folder, &people).is_err() {
+        warn!("Can't export teammates. However, process will continue.");
+    }
+    if export_team_results(export_folder, &results).is_err() {
+        warn!("Can't export team results. However, process will continue.");
+    }
+    let scopes = std::iter::once(RecordScope::AllTime)
+        .chain(conventions.iter().map(RecordScope::Convention));
+    for scope in scopes {
+        let record_table = compute_record_table(&people, scope.clone(), &result_directions);
+        if export_record_table(export_folder, &record_table).is_err() {
+            warn!("Can't export records [scope: {:?}]. However, process will continue.", record_table.scope());
+        }
+        let medal_table = compute_medal_table(&people, scope);
+        if export_medal_table(export_folder, &medal_table).is_err() {
+            warn!("Can't export medals [scope: {:?}]. However, process will continue.", medal_table.scope());
+        }
+    }
 
     // info!("{:?}", people);
 }
 
-async fn load_conventions() -> Result<HashSet<Convention>, ()> {
+async fn load_conventions(refresh: bool) -> Result<HashSet<Convention>, ()> {
     let conventions_tag = match retrieve_env_value("CONVENTIONS") {
         None => {
             error!("No convention to deal with, can't continue...");
@@ -58,9 +241,13 @@ async fn load_conventions() -> Result<HashSet<Convention>, ()> {
         }
         Some(conventions_tag) => { conventions_tag.split(',').map(str::trim).map(str::to_string).collect() }
     };
-    let loaded_conventions = load_conventions_from_folder(DATA_FOLDER, &conventions_tag);
-    let mut conventions = HashSet::from_iter(loaded_conventions.iter().map(|(_, convention)| convention.clone()));
-    let conventions_to_download = compute_conventions_to_download(&loaded_conventions, &conventions_tag);
+    let mut loaded_conventions = load_conventions_from_folder(DATA_FOLDER, &conventions_tag);
+    apply_conventions_overrides(DATA_FOLDER, &mut loaded_conventions, &conventions_tag);
+    let conventions_to_download = compute_conventions_to_download(&loaded_conventions, &conventions_tag, refresh);
+    let conventions_to_backfill = compute_conventions_to_backfill(&loaded_conventions, &conventions_to_download);
+    if !conventions_to_backfill.is_empty() {
+        backfill_conventions_details(&mut loaded_conventions, &conventions_to_backfill).await;
+    }
     let downloaded_conventions = if !conventions_to_download.is_empty() {
         let data = download_data(&conventions_to_download).await;
         if data.is_err() {
@@ -71,7 +258,11 @@ async fn load_conventions() -> Result<HashSet<Convention>, ()> {
     } else {
         vec![]
     };
-    conventions.extend(downloaded_conventions);
+    if !downloaded_conventions.is_empty() {
+        loaded_conventions.extend(downloaded_conventions.into_iter().map(|convention| (convention.tag().clone(), convention)));
+        apply_conventions_overrides(DATA_FOLDER, &mut loaded_conventions, &conventions_tag);
+    }
+    let conventions = HashSet::from_iter(loaded_conventions.into_values());
 
     let dump_result = dump_conventions(DATA_FOLDER, &conventions);
     if dump_result.is_err() {
@@ -4,11 +4,11 @@ use derive_getters::Getters;
 use crate::person::person_name::PersonName;
 
 /// The identity of a person is defined by their names and their birthday.
+/// Their country isn't part of it, as it may change from a convention to another.
 #[derive(Clone, Debug, Getters, PartialEq, Eq, Hash)]
 pub struct Identity {
     person_name: PersonName,
     birthday: NaiveDate,
-    // FIXME: add support for country
 }
 
 impl Identity {
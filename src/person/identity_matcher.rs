@@ -0,0 +1,442 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, NaiveDate};
+use derive_getters::Getters;
+use log::{debug, info};
+use serde::Serialize;
+
+use crate::convention::convention::Convention;
+use crate::export::write_json_file;
+use crate::person::identity_overrides::{IdentityOverrides, RegistrationKey};
+use crate::person::person::Person;
+use crate::person::person_name::PersonName;
+use crate::utils::string_similarity::similarity;
+
+const MERGE_CANDIDATES_FILE: &str = "merge_candidates.json";
+
+/// Candidates scoring at least this are merged without human review.
+pub const AUTO_MERGE_MIN_SCORE: f32 = 0.9;
+/// Candidates scoring less than this are not considered to be the same person.
+pub const CANDIDATE_MIN_SCORE: f32 = 0.7;
+
+const NAME_WEIGHT: f32 = 0.5;
+const BIRTHDAY_WEIGHT: f32 = 0.35;
+const COUNTRY_WEIGHT: f32 = 0.1;
+const CLUB_WEIGHT: f32 = 0.05;
+
+/// Two people that may be the same, referred to by their index in the people they have been found in.
+/// The score goes from 0 (nothing in common) to 1 (same person).
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct MergeCandidate {
+    left: usize,
+    right: usize,
+    score: f32,
+}
+
+impl MergeCandidate {
+    pub fn new(left: usize, right: usize, score: f32) -> Self {
+        Self { left, right, score }
+    }
+
+    /// Candidates are safe to merge automatically if they score high enough
+    /// and if they haven't registered for the same convention, where they would likely be two different people.
+    pub fn is_auto_mergeable(&self, people: &[Person]) -> bool {
+        if self.score < AUTO_MERGE_MIN_SCORE {
+            return false;
+        }
+        let left_conventions = people[self.left].registrations_id().keys().collect::<HashSet<_>>();
+        !people[self.right].registrations_id().keys().any(|convention| left_conventions.contains(convention))
+    }
+}
+
+/// Find the people that may be the same person despite having different identities, sorted by decreasing score.
+/// Only people born the same year or a year apart, or on the same day of the year (e.g. a typo in the year), are compared,
+/// and never people the identity overrides keep apart.
+pub fn find_merge_candidates(people: &[Person], identity_overrides: &IdentityOverrides) -> Vec<MergeCandidate> {
+    let registrations: Vec<Vec<RegistrationKey>> = people.iter().map(RegistrationKey::from_person).collect();
+    let mut people_by_birth_year: HashMap<i32, Vec<usize>> = HashMap::new();
+    let mut people_by_birth_day: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (index, person) in people.iter().enumerate() {
+        let birthday = person.identity().birthday();
+        people_by_birth_year.entry(birthday.year()).or_default().push(index);
+        people_by_birth_day.entry((birthday.month(), birthday.day())).or_default().push(index);
+    }
+
+    let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+    for (year, indices) in &people_by_birth_year {
+        let next_year_indices = people_by_birth_year.get(&(year + 1)).map(Vec::as_slice).unwrap_or_default();
+        for (position, left) in indices.iter().enumerate() {
+            pairs.extend(indices[position + 1..].iter().chain(next_year_indices).map(|right| (*left.min(right), *left.max(right))));
+        }
+    }
+    for indices in people_by_birth_day.values() {
+        for (position, left) in indices.iter().enumerate() {
+            pairs.extend(indices[position + 1..].iter().map(|right| (*left.min(right), *left.max(right))));
+        }
+    }
+
+    let mut candidates = vec![];
+    for (left, right) in pairs {
+        if identity_overrides.are_forced_apart(&registrations[left], &registrations[right]) {
+            continue;
+        }
+        let score = compute_match_score(&people[left], &people[right]);
+        if score >= CANDIDATE_MIN_SCORE {
+            debug!("Merge candidate found [left: {:?}, right: {:?}, score: {score}]", people[left].identity(), people[right].identity());
+            candidates.push(MergeCandidate::new(left, right, score));
+        }
+    }
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| (a.left, a.right).cmp(&(b.left, b.right))));
+
+    candidates
+}
+
+/// Merge the people of every given candidate.
+/// Merges are transitive: if A is merged with B and B with C, then A, B and C end up as a single person.
+/// A candidate is skipped if it would merge people registered at the same convention or registrations the identity overrides
+/// keep apart, even through other candidates.
+pub fn merge_people<'a>(people: Vec<Person<'a>>, candidates: &[&MergeCandidate], identity_overrides: &IdentityOverrides) -> Vec<Person<'a>> {
+    let mut groups: Vec<usize> = (0..people.len()).collect();
+    let mut groups_conventions: Vec<HashSet<&Convention>> = people.iter()
+        .map(|person| person.registrations_id().keys().copied().collect())
+        .collect();
+    let mut groups_registrations: Vec<Vec<RegistrationKey>> = people.iter().map(RegistrationKey::from_person).collect();
+    let mut merges = 0;
+    for candidate in candidates {
+        let left_group = find_group(&mut groups, candidate.left);
+        let right_group = find_group(&mut groups, candidate.right);
+        if left_group == right_group {
+            continue;
+        }
+        if !groups_conventions[left_group].is_disjoint(&groups_conventions[right_group]) {
+            debug!("Merge skipped, people registered at the same convention [left: {:?}, right: {:?}]", people[candidate.left].identity(), people[candidate.right].identity());
+            continue;
+        }
+        if identity_overrides.are_forced_apart(&groups_registrations[left_group], &groups_registrations[right_group]) {
+            debug!("Merge skipped, registrations forced apart [left: {:?}, right: {:?}]", people[candidate.left].identity(), people[candidate.right].identity());
+            continue;
+        }
+        let right_conventions = std::mem::take(&mut groups_conventions[right_group]);
+        groups_conventions[left_group].extend(right_conventions);
+        let right_registrations = std::mem::take(&mut groups_registrations[right_group]);
+        groups_registrations[left_group].extend(right_registrations);
+        groups[right_group] = left_group;
+        merges += 1;
+    }
+
+    let mut merged_people: Vec<Option<Person>> = vec![None; people.len()];
+    for (index, person) in people.into_iter().enumerate() {
+        let group = find_group(&mut groups, index);
+        merged_people[group] = match merged_people[group].take() {
+            None => { Some(person) }
+            Some(merged_person) => { Some(merged_person.merge(person)) }
+        };
+    }
+
+    let merged_people: Vec<Person> = merged_people.into_iter().flatten().collect();
+    info!("People merged [merges: {merges}, people: {}]", merged_people.len());
+    merged_people
+}
+
+fn find_group(groups: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while groups[root] != root {
+        root = groups[root];
+    }
+    groups[index] = root;
+    root
+}
+
+/// Compute the probability that two people are the same from their names, birthdays, countries and clubs.
+/// Countries and clubs only count when both people have some.
+pub fn compute_match_score(left: &Person, right: &Person) -> f32 {
+    let mut score = NAME_WEIGHT * compute_name_similarity(left.identity().person_name(), right.identity().person_name())
+        + BIRTHDAY_WEIGHT * compute_birthday_similarity(left.identity().birthday(), right.identity().birthday());
+    let mut weights = NAME_WEIGHT + BIRTHDAY_WEIGHT;
+
+    let left_countries: HashSet<_> = left.countries().values().collect();
+    let right_countries: HashSet<_> = right.countries().values().collect();
+    if !left_countries.is_empty() && !right_countries.is_empty() {
+        if !left_countries.is_disjoint(&right_countries) {
+            score += COUNTRY_WEIGHT;
+        }
+        weights += COUNTRY_WEIGHT;
+    }
+
+    let left_clubs: HashSet<String> = left.clubs().values().map(|club| club.to_lowercase()).collect();
+    let right_clubs: HashSet<String> = right.clubs().values().map(|club| club.to_lowercase()).collect();
+    if !left_clubs.is_disjoint(&right_clubs) {
+        // Not sharing a club is no evidence of being different people, so it only counts when shared
+        score += CLUB_WEIGHT;
+        weights += CLUB_WEIGHT;
+    }
+
+    score / weights
+}
+
+/// Names are compared as written. When one of them isn't in Latin letters, they are also compared once romanized,
+/// with each of their aliases, and the best similarity is kept: "Юрий Иванов" is far from "Yuriy Ivanov" as written
+/// but equal once romanized. Romanizing names already in Latin letters would make "Michael" equal to "Michel".
+fn compute_name_similarity(left: &PersonName, right: &PersonName) -> f32 {
+    let similarity = compute_parts_similarity(left.name_parts(), right.name_parts());
+    if left.is_in_latin_script() && right.is_in_latin_script() {
+        return similarity;
+    }
+
+    let left_variants = left.romanized_variants();
+    let right_variants = right.romanized_variants();
+    left_variants.iter()
+        .flat_map(|left_parts| right_variants.iter().map(move |right_parts| compute_parts_similarity(left_parts, right_parts)))
+        .fold(similarity, f32::max)
+}
+
+/// Names with the same parts are equal. A name whose parts are all included in the other (e.g. a missing middle name)
+/// is nearly equal. Otherwise, each part is compared to the closest part of the other name.
+fn compute_parts_similarity(left_parts: &[String], right_parts: &[String]) -> f32 {
+    if left_parts == right_parts {
+        return 1.;
+    }
+
+    let (shorter, longer) = if left_parts.len() <= right_parts.len() { (left_parts, right_parts) } else { (right_parts, left_parts) };
+    if shorter.is_empty() {
+        return 0.;
+    }
+    if shorter.iter().all(|part| longer.contains(part)) {
+        return 0.9;
+    }
+
+    let parts_similarity: f32 = shorter.iter()
+        .map(|part| longer.iter().map(|other_part| similarity(part, other_part)).fold(0., f32::max))
+        .sum::<f32>() / shorter.len() as f32;
+    let full_name_similarity = similarity(&left_parts.join(" "), &right_parts.join(" "));
+
+    parts_similarity.max(full_name_similarity)
+}
+
+/// Birthdays are often mistyped: day and month may be swapped, or a single field may be wrong.
+fn compute_birthday_similarity(left: &NaiveDate, right: &NaiveDate) -> f32 {
+    if left == right {
+        return 1.;
+    }
+    if left.year() == right.year() && left.day() == right.month() && left.month() == right.day() {
+        return 0.9;
+    }
+
+    let equal_fields = [left.year() == right.year(), left.month() == right.month(), left.day() == right.day()]
+        .iter()
+        .filter(|is_equal| **is_equal)
+        .count();
+    if equal_fields == 2 {
+        0.6
+    } else {
+        0.
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedCandidate<'a> {
+    score: f32,
+    left_name: &'a str,
+    left_birthday: String,
+    left_conventions: Vec<&'a str>,
+    left_registrations: Vec<RegistrationKey>,
+    right_name: &'a str,
+    right_birthday: String,
+    right_conventions: Vec<&'a str>,
+    right_registrations: Vec<RegistrationKey>,
+}
+
+/// Export the candidates that need a human review as JSON in `{folder}/merge_candidates.json`.
+pub fn export_merge_candidates(folder: &str, people: &[Person], candidates: &[&MergeCandidate]) -> Result<(), ()> {
+    let exported_candidates: Vec<ExportedCandidate> = candidates.iter()
+        .map(|candidate| {
+            let left = &people[candidate.left];
+            let right = &people[candidate.right];
+            ExportedCandidate {
+                score: candidate.score,
+                left_name: left.identity().person_name().name(),
+                left_birthday: left.identity().birthday().to_string(),
+                left_conventions: get_conventions_tag(left),
+                left_registrations: get_sorted_registrations(left),
+                right_name: right.identity().person_name().name(),
+                right_birthday: right.identity().birthday().to_string(),
+                right_conventions: get_conventions_tag(right),
+                right_registrations: get_sorted_registrations(right),
+            }
+        })
+        .collect();
+
+    write_json_file(folder, MERGE_CANDIDATES_FILE, &exported_candidates)
+}
+
+fn get_conventions_tag<'p>(person: &'p Person) -> Vec<&'p str> {
+    let mut conventions_tag: Vec<&str> = person.registrations_id().keys().map(|convention| convention.tag().as_str()).collect();
+    conventions_tag.sort();
+    conventions_tag
+}
+
+fn get_sorted_registrations(person: &Person) -> Vec<RegistrationKey> {
+    let mut registrations = RegistrationKey::from_person(person);
+    registrations.sort();
+    registrations
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::convention::convention::Convention;
+    use crate::person::identity_overrides::IdentityOverrides;
+    use crate::person::identity_matcher::{AUTO_MERGE_MIN_SCORE, CANDIDATE_MIN_SCORE, compute_match_score, find_merge_candidates, merge_people, MergeCandidate};
+    use crate::person::person::Person;
+
+    #[test]
+    fn should_match_name_typo() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let score = compute_match_score(
+            &Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            &Person::create_test_instance("Jonh Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+        );
+        assert!(score >= AUTO_MERGE_MIN_SCORE, "score: {score}");
+    }
+
+    #[test]
+    fn should_match_missing_middle_name() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let score = compute_match_score(
+            &Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            &Person::create_test_instance("John Paul Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+        );
+        assert!(score >= AUTO_MERGE_MIN_SCORE, "score: {score}");
+    }
+
+    #[test]
+    fn should_match_swapped_day_and_month() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let score = compute_match_score(
+            &Person::create_test_instance("John Doe").with_birthday(2000, 1, 5).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            &Person::create_test_instance("John Doe").with_birthday(2000, 5, 1).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+        );
+        assert!(score >= AUTO_MERGE_MIN_SCORE, "score: {score}");
+    }
+
+    #[test]
+    fn should_match_name_in_another_script() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let score = compute_match_score(
+            &Person::create_test_instance("Алексей Щербаков").with_birthday(2000, 1, 5).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            &Person::create_test_instance("Alexey Scherbakov").with_birthday(2000, 5, 1).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+        );
+        assert!(score >= AUTO_MERGE_MIN_SCORE, "score: {score}");
+    }
+
+    #[test]
+    fn should_only_suggest_married_name() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let score = compute_match_score(
+            &Person::create_test_instance("Jane Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            &Person::create_test_instance("Jane Smith").with_birthday(2000, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+        );
+        assert!((CANDIDATE_MIN_SCORE..AUTO_MERGE_MIN_SCORE).contains(&score), "score: {score}");
+    }
+
+    #[test]
+    fn should_not_match_different_people() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let score = compute_match_score(
+            &Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention, vec![1]).with_country(&convention, "France"),
+            &Person::create_test_instance("Jack Smith").with_birthday(2001, 3, 12).with_registrations_id(&convention, vec![1]).with_country(&convention, "Germany"),
+        );
+        assert!(score < CANDIDATE_MIN_SCORE, "score: {score}");
+    }
+
+    #[test]
+    fn should_not_auto_merge_people_registered_at_same_convention() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let people = vec![
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention, vec![1]).with_country(&convention, "France"),
+            Person::create_test_instance("Jonh Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention, vec![1]).with_country(&convention, "France"),
+        ];
+
+        let candidates = find_merge_candidates(&people, &IdentityOverrides::default());
+
+        assert_eq!(candidates.len(), 1);
+        assert!(!candidates[0].is_auto_mergeable(&people));
+    }
+
+    #[test]
+    fn should_compare_people_born_on_same_day_years_apart() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let people = vec![
+            Person::create_test_instance("John Doe").with_birthday(1990, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            Person::create_test_instance("John Doe").with_birthday(1999, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+            Person::create_test_instance("John Doe").with_birthday(1995, 3, 12).with_registrations_id(&convention2, vec![2]).with_country(&convention2, "France"),
+        ];
+
+        let candidates = find_merge_candidates(&people, &IdentityOverrides::default());
+
+        let pairs: Vec<(usize, usize)> = candidates.iter().map(|candidate| (*candidate.left(), *candidate.right())).collect();
+        assert_eq!(pairs, vec![(0, 1)]);
+    }
+
+    #[test]
+    fn should_merge_candidates_transitively() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let convention3 = Convention::create_test_instance("cfm2024");
+        let people = vec![
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 5).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            Person::create_test_instance("Jack Smith").with_birthday(1990, 1, 5).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            Person::create_test_instance("Jonh Doe").with_birthday(2000, 1, 5).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+            Person::create_test_instance("John Doe").with_birthday(2000, 5, 1).with_registrations_id(&convention3, vec![1]).with_country(&convention3, "France"),
+        ];
+
+        let candidates = find_merge_candidates(&people, &IdentityOverrides::default());
+        let candidates: Vec<_> = candidates.iter().filter(|candidate| candidate.is_auto_mergeable(&people)).collect();
+        let people = merge_people(people, &candidates, &IdentityOverrides::default());
+
+        assert_eq!(people.len(), 2);
+        let john_doe = people.iter().find(|person| person.registrations_id().len() == 3);
+        assert!(john_doe.is_some());
+    }
+
+    #[test]
+    fn should_not_merge_transitively_people_registered_at_same_convention() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let people = vec![
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+            Person::create_test_instance("Jonh Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+        ];
+        let candidates = [MergeCandidate::new(0, 1, 1.), MergeCandidate::new(1, 2, 0.95)];
+
+        let people = merge_people(people, &candidates.iter().collect::<Vec<_>>(), &IdentityOverrides::default());
+
+        assert_eq!(people.len(), 2);
+        assert!(people.iter().all(|person| person.registrations_id().values().all(|ids| ids.len() == 1)));
+    }
+
+    #[test]
+    fn should_not_merge_transitively_registrations_forced_apart() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let convention3 = Convention::create_test_instance("cfm2024");
+        let people = vec![
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention1, vec![1]).with_country(&convention1, "France"),
+            Person::create_test_instance("John Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention2, vec![1]).with_country(&convention2, "France"),
+            Person::create_test_instance("Jonh Doe").with_birthday(2000, 1, 20).with_registrations_id(&convention3, vec![1]).with_country(&convention3, "France"),
+        ];
+        let candidates = [MergeCandidate::new(0, 1, 1.), MergeCandidate::new(1, 2, 0.95)];
+        let identity_overrides = IdentityOverrides::create_test_instance(vec![], vec![vec![("cfm2022", 1), ("cfm2024", 1)]]);
+
+        let people = merge_people(people, &candidates.iter().collect::<Vec<_>>(), &identity_overrides);
+
+        assert_eq!(people.len(), 2);
+        assert!(people.iter().all(|person| !(person.registrations_id().contains_key(&convention1) && person.registrations_id().contains_key(&convention3))));
+    }
+}
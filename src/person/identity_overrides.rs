@@ -0,0 +1,224 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::person::person::Person;
+
+const IDENTITY_OVERRIDES_FILE: &str = "identity_overrides.json";
+const SUPPORTED_VERSION: u8 = 1;
+
+/// A registration is identified by the tag of its convention and the id of its registrant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RegistrationKey {
+    convention: String,
+    id: u16,
+}
+
+impl RegistrationKey {
+    pub fn new(convention: &str, id: u16) -> Self {
+        Self { convention: convention.to_string(), id }
+    }
+
+    pub fn from_person(person: &Person) -> Vec<Self> {
+        person.registrations_id().iter()
+            .flat_map(|(convention, ids)| ids.iter().map(|id| RegistrationKey::new(convention.tag(), *id)))
+            .collect()
+    }
+}
+
+/// Human decisions on whether registrations belong to the same person, which take precedence over any matching.
+#[derive(Debug, Default, Deserialize)]
+pub struct IdentityOverrides {
+    version: u8,
+    #[serde(default)]
+    same_person: Vec<Vec<RegistrationKey>>,
+    #[serde(default)]
+    different_people: Vec<Vec<RegistrationKey>>,
+    /// The registrations each registration is forced apart from, built once `different_people` is known.
+    #[serde(skip)]
+    forced_apart: HashMap<RegistrationKey, HashSet<RegistrationKey>>,
+}
+
+impl IdentityOverrides {
+    /// Load overrides from `{folder}/identity_overrides.json`. If there is none, no override is applied.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{IDENTITY_OVERRIDES_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No identity override [filepath: {filepath}]");
+                return IdentityOverrides::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        let overrides: IdentityOverrides = match serde_json::from_reader(reader) {
+            Ok(overrides) => { overrides }
+            Err(error) => {
+                warn!("Can't read identity overrides, they will be ignored [filepath: {filepath}]");
+                warn!("{}", error);
+                return IdentityOverrides::default();
+            }
+        };
+        if overrides.version != SUPPORTED_VERSION {
+            warn!("Unsupported identity overrides version, they will be ignored [filepath: {filepath}, version: {}, supported_version: {SUPPORTED_VERSION}]", overrides.version);
+            return IdentityOverrides::default();
+        }
+
+        info!("Identity overrides loaded [same_person: {}, different_people: {}]", overrides.same_person.len(), overrides.different_people.len());
+        overrides.with_forced_apart()
+    }
+
+    fn with_forced_apart(mut self) -> Self {
+        let mut forced_apart: HashMap<RegistrationKey, HashSet<RegistrationKey>> = HashMap::new();
+        for registrations in &self.different_people {
+            for left in registrations {
+                for right in registrations {
+                    if left != right {
+                        forced_apart.entry(left.clone()).or_default().insert(right.clone());
+                    }
+                }
+            }
+        }
+        self.forced_apart = forced_apart;
+        self
+    }
+
+    pub fn are_forced_apart(&self, left: &[RegistrationKey], right: &[RegistrationKey]) -> bool {
+        left.iter().any(|left| self.forced_apart.get(left).is_some_and(|apart| right.iter().any(|right| apart.contains(right))))
+    }
+
+    /// Split the groups of registrations that contain registrations forced apart,
+    /// then merge the groups that contain registrations forced together.
+    /// Forcing together registrations that are also forced apart is ignored.
+    pub fn apply<T>(&self, groups: Vec<Vec<T>>, get_key: impl Fn(&T) -> RegistrationKey) -> Vec<Vec<T>> {
+        let mut split_groups: Vec<Vec<T>> = vec![];
+        for group in groups {
+            let mut subgroups: Vec<Vec<T>> = vec![];
+            for element in group {
+                let key = [get_key(&element)];
+                let subgroup = subgroups.iter_mut()
+                    .find(|subgroup| !self.are_forced_apart(&key, &subgroup.iter().map(&get_key).collect::<Vec<_>>()));
+                match subgroup {
+                    None => { subgroups.push(vec![element]); }
+                    Some(subgroup) => { subgroup.push(element); }
+                }
+            }
+            if subgroups.len() > 1 {
+                debug!("Registrations forced apart [groups: {}]", subgroups.len());
+            }
+            split_groups.extend(subgroups);
+        }
+
+        let mut group_by_key: HashMap<RegistrationKey, usize> = HashMap::new();
+        for (index, group) in split_groups.iter().enumerate() {
+            for element in group {
+                group_by_key.insert(get_key(element), index);
+            }
+        }
+        let mut merged_groups: Vec<Option<Vec<T>>> = split_groups.into_iter().map(Some).collect();
+        let mut merged_into: Vec<usize> = (0..merged_groups.len()).collect();
+        for registrations in &self.same_person {
+            let mut target: Option<usize> = None;
+            for key in registrations {
+                let Some(index) = group_by_key.get(key) else {
+                    warn!("Registration forced together doesn't exist [registration: {:?}]", key);
+                    continue;
+                };
+                let index = find_root(&mut merged_into, *index);
+                let Some(target_index) = target else {
+                    target = Some(index);
+                    continue;
+                };
+                if index == target_index {
+                    continue;
+                }
+
+                let target_keys: Vec<RegistrationKey> = merged_groups[target_index].iter().flatten().map(&get_key).collect();
+                let keys: Vec<RegistrationKey> = merged_groups[index].iter().flatten().map(&get_key).collect();
+                if self.are_forced_apart(&target_keys, &keys) {
+                    warn!("Registrations forced both together and apart, they are kept apart [registration: {:?}]", key);
+                    continue;
+                }
+                let group = merged_groups[index].take().unwrap_or_default();
+                merged_groups[target_index].get_or_insert_with(Vec::new).extend(group);
+                merged_into[index] = target_index;
+            }
+        }
+
+        merged_groups.into_iter().flatten().collect()
+    }
+}
+
+fn find_root(merged_into: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while merged_into[root] != root {
+        root = merged_into[root];
+    }
+    merged_into[index] = root;
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::person::identity_overrides::{IdentityOverrides, RegistrationKey};
+
+    impl IdentityOverrides {
+        pub fn create_test_instance(same_person: Vec<Vec<(&str, u16)>>, different_people: Vec<Vec<(&str, u16)>>) -> Self {
+            let to_keys = |groups: Vec<Vec<(&str, u16)>>| groups.into_iter()
+                .map(|group| group.into_iter().map(|(convention, id)| RegistrationKey::new(convention, id)).collect())
+                .collect();
+            IdentityOverrides { version: 1, same_person: to_keys(same_person), different_people: to_keys(different_people), forced_apart: HashMap::new() }
+                .with_forced_apart()
+        }
+    }
+
+    fn sorted(mut groups: Vec<Vec<(&str, u16)>>) -> Vec<Vec<(&str, u16)>> {
+        groups.iter_mut().for_each(|group| group.sort());
+        groups.sort();
+        groups
+    }
+
+    #[test]
+    fn should_split_registrations_forced_apart() {
+        let overrides = IdentityOverrides::create_test_instance(vec![], vec![vec![("cfm2022", 1), ("cfm2023", 2)]]);
+        let groups = vec![vec![("cfm2022", 1), ("cfm2023", 2), ("cfm2024", 3)]];
+
+        let groups = overrides.apply(groups, |(convention, id)| RegistrationKey::new(convention, *id));
+
+        assert_eq!(sorted(groups), vec![vec![("cfm2022", 1), ("cfm2024", 3)], vec![("cfm2023", 2)]]);
+    }
+
+    #[test]
+    fn should_merge_registrations_forced_together() {
+        let overrides = IdentityOverrides::create_test_instance(vec![vec![("cfm2022", 1), ("cfm2023", 2)], vec![("cfm2023", 2), ("cfm2024", 3)]], vec![]);
+        let groups = vec![vec![("cfm2022", 1)], vec![("cfm2023", 2)], vec![("cfm2024", 3)], vec![("cfm2024", 4)]];
+
+        let groups = overrides.apply(groups, |(convention, id)| RegistrationKey::new(convention, *id));
+
+        assert_eq!(sorted(groups), vec![vec![("cfm2022", 1), ("cfm2023", 2), ("cfm2024", 3)], vec![("cfm2024", 4)]]);
+    }
+
+    #[test]
+    fn should_keep_apart_registrations_forced_both_together_and_apart() {
+        let overrides = IdentityOverrides::create_test_instance(vec![vec![("cfm2022", 1), ("cfm2023", 2)]], vec![vec![("cfm2022", 1), ("cfm2023", 2)]]);
+        let groups = vec![vec![("cfm2022", 1)], vec![("cfm2023", 2)]];
+
+        let groups = overrides.apply(groups, |(convention, id)| RegistrationKey::new(convention, *id));
+
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn should_tell_forced_apart_registrations() {
+        let overrides = IdentityOverrides::create_test_instance(vec![], vec![vec![("cfm2022", 1), ("cfm2023", 2)]]);
+
+        assert!(overrides.are_forced_apart(&[RegistrationKey::new("cfm2023", 2)], &[RegistrationKey::new("cfm2022", 1)]));
+        assert!(!overrides.are_forced_apart(&[RegistrationKey::new("cfm2023", 2)], &[RegistrationKey::new("cfm2022", 3)]));
+    }
+}
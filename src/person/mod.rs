@@ -1,3 +1,11 @@
 pub mod person;
 pub mod person_name;
 pub mod identity;
+pub mod identity_matcher;
+pub mod identity_overrides;
+pub mod person_id_registry;
+pub mod teammate;
+pub mod person_search;
+pub mod name_order;
+pub mod romanization;
+pub mod name_aliases;
\ No newline at end of file
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+use serde::Deserialize;
+
+use crate::person::person_name::PersonName;
+
+const NAME_ALIASES_FILE: &str = "name_aliases.json";
+
+#[derive(Debug, Default, Deserialize)]
+struct RawNameAliases {
+    #[serde(default)]
+    aliases: Vec<Vec<String>>,
+}
+
+/// The names a same person registered with, e.g. a nickname or a name written in another script, so that they are equal.
+#[derive(Debug, Default)]
+pub struct NameAliases {
+    aliases_by_name_parts: HashMap<Vec<String>, Vec<String>>,
+}
+
+impl NameAliases {
+    /// Load name aliases from `{folder}/name_aliases.json`, e.g. `{"aliases": [["Юрий Иванов", "Yura Ivanov"]]}`,
+    /// each list being the names of a same person. If there is none, names are only equal when written the same.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{NAME_ALIASES_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No name aliases [filepath: {filepath}]");
+                return NameAliases::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        let raw_name_aliases: RawNameAliases = match serde_json::from_reader(reader) {
+            Ok(raw_name_aliases) => { raw_name_aliases }
+            Err(error) => {
+                warn!("Can't read name aliases, names will only be equal when written the same [filepath: {filepath}]");
+                warn!("{}", error);
+                return NameAliases::default();
+            }
+        };
+        let name_aliases = Self::from_groups(&raw_name_aliases.aliases);
+        info!("Name aliases loaded [groups: {}]", raw_name_aliases.aliases.len());
+        name_aliases
+    }
+
+    pub fn from_groups(groups: &[Vec<String>]) -> Self {
+        let mut aliases_by_name_parts = HashMap::new();
+        for group in groups {
+            for name in group {
+                let aliases = group.iter().filter(|alias| *alias != name).cloned().collect();
+                aliases_by_name_parts.insert(PersonName::new(name).name_parts().clone(), aliases);
+            }
+        }
+        Self { aliases_by_name_parts }
+    }
+
+    /// The name with the aliases it has been given, if any.
+    pub fn apply(&self, name: PersonName) -> PersonName {
+        match self.aliases_by_name_parts.get(name.name_parts()) {
+            Some(aliases) => { name.with_aliases(aliases.clone()) }
+            None => { name }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::person::name_aliases::NameAliases;
+    use crate::person::person_name::{NameOrder, PersonName};
+
+    #[test]
+    fn should_make_aliases_equal() {
+        let name_aliases = NameAliases::from_groups(&[vec!["Юрий Иванов".to_string(), "Yura Ivanov".to_string()]]);
+
+        let native_name = name_aliases.apply(PersonName::from_names("Юрий", "Иванов", NameOrder::GivenFirst));
+        let nickname = name_aliases.apply(PersonName::new("Ivanov Yura"));
+
+        assert_eq!(native_name, nickname);
+        assert_eq!(nickname.aliases(), &vec!["Юрий Иванов".to_string()]);
+        assert_ne!(name_aliases.apply(PersonName::new("Yuriy Ivanov")), nickname);
+        assert!(name_aliases.apply(PersonName::new("Yura Petrov")).aliases().is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+use serde::Deserialize;
+
+use crate::country::country::Country;
+use crate::person::person_name::NameOrder;
+
+const NAME_ORDERS_FILE: &str = "name_orders.json";
+
+#[derive(Debug, Default, Deserialize)]
+struct RawNameOrders {
+    #[serde(default)]
+    family_first: Vec<String>,
+}
+
+/// The countries whose people are named family name first, e.g. Hungary or Japan. Everyone else is named given names first.
+#[derive(Debug, Default)]
+pub struct NameOrders {
+    family_first_countries: HashSet<Country>,
+}
+
+impl NameOrders {
+    /// Load name orders from `{folder}/name_orders.json`, e.g. `{"family_first": ["Hungary", "JP"]}`.
+    /// If there is none, everyone is named given names first.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{NAME_ORDERS_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No name order [filepath: {filepath}]");
+                return NameOrders::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        let raw_name_orders: RawNameOrders = match serde_json::from_reader(reader) {
+            Ok(raw_name_orders) => { raw_name_orders }
+            Err(error) => {
+                warn!("Can't read name orders, everyone will be named given names first [filepath: {filepath}]");
+                warn!("{}", error);
+                return NameOrders::default();
+            }
+        };
+        let name_orders = Self::from_countries(&raw_name_orders.family_first);
+        info!("Name orders loaded [family_first_countries: {}]", name_orders.family_first_countries.len());
+        name_orders
+    }
+
+    pub fn from_countries(family_first_countries: &[String]) -> Self {
+        let family_first_countries = family_first_countries.iter()
+            .filter_map(|country| Country::from_string(country)
+                .map_err(|error| warn!("Can't read family-first country, it will be ignored: {error}"))
+                .ok())
+            .collect();
+        Self { family_first_countries }
+    }
+
+    pub fn name_order_of(&self, country: Option<&Country>) -> NameOrder {
+        match country {
+            Some(country) if self.family_first_countries.contains(country) => { NameOrder::FamilyFirst }
+            _ => { NameOrder::GivenFirst }
+        }
+    }
+}
@@ -2,30 +2,104 @@ use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 use derive_getters::Getters;
+use log::warn;
+use uuid::Uuid;
 
 use crate::competition::competition_result::CompetitionResult;
+use crate::competition::performance::Performance;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::competitor::Competitor;
 use crate::convention::convention::Convention;
+use crate::country::country::Country;
 use crate::person::identity::Identity;
+use crate::person::identity_overrides::{IdentityOverrides, RegistrationKey};
+use crate::person::name_aliases::NameAliases;
+use crate::person::name_order::NameOrders;
 use crate::person::person_name::PersonName;
+use crate::person::teammate::Teammate;
 use crate::registration::registrant::Registrant;
 
 #[derive(Clone, Debug, Getters)]
 pub struct Person<'a> {
+    // Stable across runs, see [crate::person::person_id_registry::PersonIdRegistry]
+    id: Uuid,
     identity: Identity,
     // Someone can have multiple complete or incomplete registration ids if they have registered multiple times for a convention
     registrations_id: HashMap<&'a Convention, Vec<u16>>,
-    results: HashMap<&'a Convention, Vec<CompetitionResult>>,
+    performances: HashMap<&'a Convention, Vec<Performance>>,
+    // Someone may represent different countries or clubs over time
+    countries: HashMap<&'a Convention, Country>,
+    clubs: HashMap<&'a Convention, String>,
 }
 
 impl<'a> Person<'a> {
-    pub fn new(identity: Identity, registrations_id: HashMap<&'a Convention, Vec<u16>>, results: HashMap<&'a Convention, Vec<CompetitionResult>>) -> Self {
-        Self { identity, registrations_id, results }
+    pub fn new(identity: Identity,
+               registrations_id: HashMap<&'a Convention, Vec<u16>>,
+               performances: HashMap<&'a Convention, Vec<Performance>>,
+               countries: HashMap<&'a Convention, Country>,
+               clubs: HashMap<&'a Convention, String>) -> Self {
+        Self { id: Uuid::new_v4(), identity, registrations_id, performances, countries, clubs }
+    }
+
+    pub fn with_id(self, id: Uuid) -> Self {
+        Self { id, ..self }
+    }
+
+    pub fn with_identity(self, identity: Identity) -> Self {
+        Self { identity, ..self }
+    }
+
+    pub fn with_performances(self, performances: HashMap<&'a Convention, Vec<Performance>>) -> Self {
+        Self { performances, ..self }
+    }
+
+    pub fn country_at(&self, convention: &Convention) -> Option<&Country> {
+        self.countries.get(convention)
+    }
+
+    /// Everyone this person competed with in a team, such as a relay or a pair, sorted chronologically.
+    pub fn teammates(&self) -> Vec<Teammate<'a>> {
+        let mut teammates = vec![];
+        for (convention, performances) in &self.performances {
+            let own_ids = self.registrations_id.get(convention).map(Vec::as_slice).unwrap_or_default();
+            for performance in performances {
+                let Competitor::Team(team) = performance.competitor() else { continue; };
+                for member in team.members() {
+                    if member.ids().iter().any(|id| own_ids.contains(id)) {
+                        continue;
+                    }
+                    teammates.push(Teammate::new(convention, performance.competition().clone(), member.clone()));
+                }
+            }
+        }
+        teammates.sort_by(|a, b| a.convention().compare_chronologically(b.convention())
+            .then_with(|| a.competition().name().cmp(b.competition().name())));
+
+        teammates
+    }
+
+    /// Merge the registrations of another person that has been found to be the same as this one.
+    /// The id and the identity of this person are kept.
+    pub fn merge(mut self, other: Person<'a>) -> Self {
+        for (convention, ids) in other.registrations_id {
+            self.registrations_id.entry(convention).or_default().extend(ids);
+        }
+        for (convention, performances) in other.performances {
+            self.performances.entry(convention).or_default().extend(performances);
+        }
+        for (convention, country) in other.countries {
+            self.countries.entry(convention).or_insert(country);
+        }
+        for (convention, club) in other.clubs {
+            self.clubs.entry(convention).or_insert(club);
+        }
+        self
     }
 }
 
 impl<'a> PartialEq for Person<'a> {
     fn eq(&self, other: &Self) -> bool {
-        self.identity == other.identity
+        self.id == other.id
     }
 }
 
@@ -33,41 +107,71 @@ impl<'a> Eq for Person<'a> {}
 
 impl<'a> Hash for Person<'a> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.identity.hash(state);
+        self.id.hash(state);
     }
 }
 
+/// Group registrants by identity into people, their names matching whatever their romanization or aliases,
+/// then apply the identity overrides.
 pub fn create_people<'a>(registrants: &HashMap<&'a Convention, Vec<Registrant>>,
-                         conventions_results: &HashMap<&'a Convention, Vec<CompetitionResult>>) -> Vec<Person<'a>> {
-    let mut people_information: HashMap<Identity, HashMap<&Convention, Vec<u16>>> = HashMap::new();
+                         conventions_results: &HashMap<&'a Convention, ResultStore>,
+                         identity_overrides: &IdentityOverrides,
+                         name_orders: &NameOrders,
+                         name_aliases: &NameAliases) -> Vec<Person<'a>> {
+    let mut groups_index: HashMap<Identity, usize> = HashMap::new();
+    let mut groups: Vec<Vec<(&'a Convention, &Registrant)>> = vec![];
 
     for (convention, registrants) in registrants {
         for registrant in registrants {
-            let name = PersonName::from_names(&[registrant.first_name(), registrant.last_name()]);
-            let birthday = registrant.birthday();
-            let identity = Identity::new(name, *birthday);
-
-            let mut information = people_information.entry(identity).or_insert(HashMap::new());
-            let mut ids = information.entry(*convention).or_insert(vec![]);
-            ids.push(*registrant.id());
+            let identity = create_identity(registrant, name_orders, name_aliases);
+            let index = *groups_index.entry(identity).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[index].push((*convention, registrant));
         }
     }
+    let groups = identity_overrides.apply(groups, |(convention, registrant)| RegistrationKey::new(convention.tag(), *registrant.id()));
 
     let mut people = vec![];
-    for (identity, registrations_id) in people_information {
-        let results = get_results_from_raw_results(&registrations_id, conventions_results);
-        let new_person = Person::new(identity.clone(), registrations_id, results);
+    for group in groups {
+        let Some((_, first_registrant)) = group.first() else { continue; };
+        let identity = create_identity(first_registrant, name_orders, name_aliases);
+        let mut registrations_id: HashMap<&Convention, Vec<u16>> = HashMap::new();
+        let mut countries = HashMap::new();
+        let mut clubs = HashMap::new();
+        for (convention, registrant) in group {
+            registrations_id.entry(convention).or_default().push(*registrant.id());
+            match Country::from_string(registrant.country()) {
+                Ok(country) => { countries.insert(convention, country); }
+                Err(error) => { warn!("Can't read country of registrant [convention: {}, id: {}]: {}", convention.tag(), registrant.id(), error); }
+            }
+            if !registrant.club().trim().is_empty() {
+                clubs.insert(convention, registrant.club().trim().to_string());
+            }
+        }
+
+        let performances = get_performances(&registrations_id, conventions_results);
+        let new_person = Person::new(identity, registrations_id, performances, countries, clubs);
         people.push(new_person);
     }
 
     people
 }
 
-fn get_results_from_raw_results<'a>(
+fn create_identity(registrant: &Registrant, name_orders: &NameOrders, name_aliases: &NameAliases) -> Identity {
+    let name_order = name_orders.name_order_of(Country::from_string(registrant.country()).ok().as_ref());
+    let name = name_aliases.apply(PersonName::from_names(registrant.first_name(), registrant.last_name(), name_order));
+    Identity::new(name, *registrant.birthday())
+}
+
+/// Gather the results of every registration id, skipping the ones already recorded for another id of the same convention,
+/// and link them into performances. Identical results of a same id, e.g. two DNF lines, are kept as they are.
+fn get_performances<'a>(
     registrations_id: &HashMap<&'a Convention, Vec<u16>>,
-    conventions_results: &HashMap<&'a Convention, Vec<CompetitionResult>>,
-) -> HashMap<&'a Convention, Vec<CompetitionResult>> {
-    let mut person_results = HashMap::new();
+    conventions_results: &HashMap<&'a Convention, ResultStore>,
+) -> HashMap<&'a Convention, Vec<Performance>> {
+    let mut person_performances = HashMap::new();
 
     for (convention, ids) in registrations_id {
         let convention_results = match conventions_results.get(convention) {
@@ -75,15 +179,205 @@ fn get_results_from_raw_results<'a>(
             Some(results) => { results }
         };
 
+        let mut results: Vec<&CompetitionResult> = vec![];
         for id in ids {
-            let results = convention_results.iter()
-                .filter(|result| result.id() == id)
-                .map(|result| result.clone())
-                .collect();
+            let other_ids_results_count = results.len();
+            for result in convention_results.by_id(*id) {
+                if !results[..other_ids_results_count].iter().any(|known_result| known_result.is_same_performance(result)) {
+                    results.push(result);
+                }
+            }
+        }
+        if !results.is_empty() {
+            person_performances.insert(*convention, Performance::link_results(results));
+        }
+    }
+
+    person_performances
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
 
-            person_results.insert(*convention, results);
+    use crate::competition::competition::Competition;
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::performance::Performance;
+    use crate::competition::result_store::ResultStore;
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::individual_competitor::IndividualCompetitor;
+    use crate::competitor::team::Team;
+    use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
+    use crate::convention::convention::Convention;
+    use crate::country::country::Country;
+    use crate::person::identity::Identity;
+    use crate::person::identity_overrides::IdentityOverrides;
+    use crate::person::name_aliases::NameAliases;
+    use crate::person::name_order::NameOrders;
+    use crate::person::person::{create_people, Person};
+    use crate::person::person_name::PersonName;
+    use crate::registration::registrant::Registrant;
+
+    impl<'a> Person<'a> {
+        /// Someone born on 2000-01-01 who hasn't registered nor competed anywhere yet.
+        pub fn create_test_instance(name: &str) -> Self {
+            let identity = Identity::new(PersonName::new(name), NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+            Person::new(identity, HashMap::new(), HashMap::new(), HashMap::new(), HashMap::new())
         }
+
+        pub fn with_birthday(self, year: i32, month: u32, day: u32) -> Self {
+            let identity = Identity::new(self.identity.person_name().clone(), NaiveDate::from_ymd_opt(year, month, day).unwrap());
+            Self { identity, ..self }
+        }
+
+        pub fn with_registrations_id(mut self, convention: &'a Convention, ids: Vec<u16>) -> Self {
+            self.registrations_id.insert(convention, ids);
+            self
+        }
+
+        pub fn with_results(mut self, convention: &'a Convention, results: &[CompetitionResult]) -> Self {
+            self.performances.insert(convention, Performance::link_results(results));
+            self
+        }
+
+        pub fn with_country(mut self, convention: &'a Convention, country: &str) -> Self {
+            self.countries.insert(convention, Country::from_string(country).unwrap());
+            self
+        }
+    }
+
+    fn create_registrant(id: u16) -> Registrant {
+        Registrant::create_test_instance(id, "John", "Doe", NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+    }
+
+    fn create_team_result(competition: &str, members: Vec<Competitor>) -> CompetitionResult {
+        CompetitionResult::create_test_instance(1, competition, "1", "01:14.990")
+            .with_competitor(Competitor::Team(Team::new("Team Doe".to_string(), None, members)))
+    }
+
+    #[test]
+    fn should_merge_results_of_every_registration() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![create_registrant(1), create_registrant(2)])]);
+        let results = HashMap::from([(&convention, ResultStore::new(vec![
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990"),
+            CompetitionResult::create_test_instance(2, "Marathon", "1", "02:14:00.000"),
+            CompetitionResult::create_test_instance(3, "100m", "1", "00:15.990"),
+        ]))]);
+
+        let people = create_people(&registrants, &results, &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        assert_eq!(people.len(), 1);
+        let person_results = people[0].performances().get(&convention).unwrap();
+        assert_eq!(person_results.len(), 2);
+        assert!(person_results.iter().any(|result| result.competition() == &Competition::new("100m")));
+        assert!(person_results.iter().any(|result| result.competition() == &Competition::new("Marathon")));
     }
 
-    person_results
-}
\ No newline at end of file
+    #[test]
+    fn should_deduplicate_results_recorded_for_several_registrations() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![create_registrant(1), create_registrant(2)])]);
+        let results = HashMap::from([(&convention, ResultStore::new(vec![
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990"),
+            CompetitionResult::create_test_instance(2, "100m", "1", "00:14.990"),
+            CompetitionResult::create_test_instance(2, "100m", "1", "00:14.990"),
+        ]))]);
+
+        let people = create_people(&registrants, &results, &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        assert_eq!(people[0].performances().get(&convention).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_keep_identical_results_of_a_single_registration() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![create_registrant(1)])]);
+        let results = HashMap::from([(&convention, ResultStore::new(vec![
+            CompetitionResult::create_test_instance(1, "Slow Forward", "1", "DNF"),
+            CompetitionResult::create_test_instance(1, "Slow Forward", "1", "DNF"),
+        ]))]);
+
+        let people = create_people(&registrants, &results, &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        assert_eq!(people[0].performances().get(&convention).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn should_not_have_results_for_convention_without_any() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![create_registrant(1)])]);
+        let results = HashMap::from([(&convention, ResultStore::new(vec![CompetitionResult::create_test_instance(2, "100m", "1", "00:14.990")]))]);
+
+        let people = create_people(&registrants, &results, &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        assert!(people[0].performances().is_empty());
+    }
+
+    #[test]
+    fn should_find_teammates() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let registrants = HashMap::from([(&convention, vec![
+            create_registrant(1),
+            Registrant::create_test_instance(2, "Jane", "Doe", NaiveDate::from_ymd_opt(2001, 1, 1).unwrap()),
+        ])]);
+        let jane = Competitor::IndividualCompetitor(IndividualCompetitor::new(2, "Jane Doe".to_string(), None, None));
+        let unknown = Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(3));
+        let john = Competitor::IndividualCompetitor(IndividualCompetitor::new(1, "John Doe".to_string(), None, None));
+        let results = HashMap::from([(&convention, ResultStore::new(vec![
+            create_team_result("Relay", vec![john, jane.clone(), unknown.clone()]),
+            CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990"),
+        ]))]);
+
+        let people = create_people(&registrants, &results, &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        let john = people.iter().find(|person| person.identity().person_name().name().contains("John")).unwrap();
+        let teammates: Vec<Competitor> = john.teammates().iter().map(|teammate| teammate.member().clone()).collect();
+        assert_eq!(teammates, vec![jane, unknown]);
+        assert_eq!(john.performances().get(&convention).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn should_group_registrants_by_the_aliases_of_their_names() {
+        let first_convention = Convention::create_test_instance("cfm2022");
+        let second_convention = Convention::create_test_instance("unicon20");
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let registrants = HashMap::from([
+            (&first_convention, vec![Registrant::create_test_instance(1, "Юрий", "Иванов", birthday)]),
+            (&second_convention, vec![
+                Registrant::create_test_instance(1, "Yuriy", "Ivanov", birthday),
+                Registrant::create_test_instance(2, "Yura", "Ivanov", birthday),
+            ]),
+        ]);
+        let name_aliases = NameAliases::from_groups(&[vec!["Юрий Иванов".to_string(), "Yuriy Ivanov".to_string(), "Yura Ivanov".to_string()]]);
+
+        let people = create_people(&registrants, &HashMap::new(), &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+        let people_with_aliases = create_people(&registrants, &HashMap::new(), &IdentityOverrides::default(), &NameOrders::default(), &name_aliases);
+
+        assert_eq!(people.len(), 3);
+        assert_eq!(people_with_aliases.len(), 1);
+        assert_eq!(people_with_aliases[0].registrations_id().values().map(Vec::len).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn should_not_group_registrants_whose_names_only_sound_the_same() {
+        let first_convention = Convention::create_test_instance("cfm2022");
+        let second_convention = Convention::create_test_instance("unicon20");
+        let birthday = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let names = [("Michael", "Michel"), ("Henry", "Henri"), ("Louis", "Lois"), ("Leo", "Lu")];
+        let registrants = HashMap::from([
+            (&first_convention, names.iter().enumerate()
+                .map(|(id, (first_name, _))| Registrant::create_test_instance(id as u16, first_name, "Martin", birthday))
+                .collect()),
+            (&second_convention, names.iter().enumerate()
+                .map(|(id, (_, first_name))| Registrant::create_test_instance(id as u16, first_name, "Martin", birthday))
+                .collect()),
+        ]);
+
+        let people = create_people(&registrants, &HashMap::new(), &IdentityOverrides::default(), &NameOrders::default(), &NameAliases::default());
+
+        assert_eq!(people.len(), 8);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Write};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::person::identity_overrides::RegistrationKey;
+use crate::person::person::Person;
+use crate::utils::create_folder;
+
+const PEOPLE_IDS_FILE: &str = "people_ids.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersonIdEntry {
+    #[serde(flatten)]
+    registration: RegistrationKey,
+    person_id: Uuid,
+}
+
+/// Keeps track of the id given to the person of every registration,
+/// so that a person keeps the same id from a run to another.
+#[derive(Debug, Default)]
+pub struct PersonIdRegistry {
+    ids: HashMap<RegistrationKey, Uuid>,
+}
+
+impl PersonIdRegistry {
+    /// Load the ids from `{folder}/people_ids.json`. If there is none, every person will get a new id.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{PEOPLE_IDS_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No people ids [filepath: {filepath}]");
+                return PersonIdRegistry::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        match serde_json::from_reader::<_, Vec<PersonIdEntry>>(reader) {
+            Ok(entries) => {
+                let ids = entries.into_iter().map(|entry| (entry.registration, entry.person_id)).collect();
+                PersonIdRegistry { ids }
+            }
+            Err(error) => {
+                warn!("Can't read people ids, every person will get a new id [filepath: {filepath}]");
+                warn!("{}", error);
+                PersonIdRegistry::default()
+            }
+        }
+    }
+
+    /// The id of the person of every known registration.
+    pub fn ids(&self) -> &HashMap<RegistrationKey, Uuid> {
+        &self.ids
+    }
+
+    /// Give every person the id most of their registrations already had.
+    /// When several people claim the same id, e.g. because a person has been split, claims are resolved over all people at once
+    /// so that the outcome doesn't depend on their order: the person sharing the most registrations with the id gets it,
+    /// then the one with the smallest registration. A person whose registrations never had any id,
+    /// or whose ids have all been given to other people, gets a new one.
+    pub fn assign_ids<'a>(&mut self, people: Vec<Person<'a>>) -> Vec<Person<'a>> {
+        let registrations: Vec<Vec<RegistrationKey>> = people.iter().map(RegistrationKey::from_person).collect();
+
+        // (person index, id, registrations with this id, smallest of these registrations)
+        let mut claims: Vec<(usize, Uuid, usize, &RegistrationKey)> = vec![];
+        for (index, person_registrations) in registrations.iter().enumerate() {
+            let mut known_ids: HashMap<Uuid, (usize, &RegistrationKey)> = HashMap::new();
+            for registration in person_registrations {
+                if let Some(id) = self.ids.get(registration) {
+                    let (count, smallest_registration) = known_ids.entry(*id).or_insert((0, registration));
+                    *count += 1;
+                    *smallest_registration = (*smallest_registration).min(registration);
+                }
+            }
+            claims.extend(known_ids.into_iter().map(|(id, (count, smallest_registration))| (index, id, count, smallest_registration)));
+        }
+        claims.sort_by(|(_, a_id, a_count, a_registration), (_, b_id, b_count, b_registration)| b_count.cmp(a_count)
+            .then_with(|| a_registration.cmp(b_registration))
+            .then_with(|| a_id.cmp(b_id)));
+
+        let mut assigned_ids: HashMap<usize, Uuid> = HashMap::new();
+        let mut used_ids = HashSet::new();
+        for (index, id, _, _) in claims {
+            if !assigned_ids.contains_key(&index) && !used_ids.contains(&id) {
+                assigned_ids.insert(index, id);
+                used_ids.insert(id);
+            }
+        }
+
+        let mut new_ids = vec![];
+        for (index, person_registrations) in registrations.iter().enumerate() {
+            let id = *assigned_ids.entry(index).or_insert_with(Uuid::new_v4);
+            new_ids.extend(person_registrations.iter().map(|registration| (registration.clone(), id)));
+        }
+        self.ids.extend(new_ids);
+
+        people.into_iter()
+            .enumerate()
+            .map(|(index, person)| person.with_id(assigned_ids[&index]))
+            .collect()
+    }
+
+    /// Write the ids to `{folder}/people_ids.json`, including the ones of registrations that haven't been loaded this time.
+    pub fn dump(&self, folder: &str) -> Result<(), ()> {
+        create_folder(
+            folder,
+            &format!("Can't dump people ids because folder couldn't be created [folder: {folder}]"),
+        )?;
+        let mut entries: Vec<PersonIdEntry> = self.ids.iter()
+            .map(|(registration, person_id)| PersonIdEntry { registration: registration.clone(), person_id: *person_id })
+            .collect();
+        entries.sort_by(|a, b| a.registration.cmp(&b.registration));
+        let json = json!(entries);
+
+        let filepath = format!("{folder}/{PEOPLE_IDS_FILE}");
+        let mut file = match File::create(&filepath) {
+            Ok(file) => { Ok(file) }
+            Err(error) => {
+                warn!("Can't dump people ids because file couldn't be opened [filepath: {filepath}]");
+                warn!("{}", error);
+                Err(())
+            }
+        }?;
+        match file.write_all(json.to_string().as_bytes()) {
+            Ok(_) => { Ok(()) }
+            Err(error) => {
+                warn!("Can't dump people ids [filepath: {filepath}]");
+                warn!("{}", error);
+                Err(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use crate::convention::convention::Convention;
+    use crate::person::identity_overrides::RegistrationKey;
+    use crate::person::person::Person;
+    use crate::person::person_id_registry::PersonIdRegistry;
+
+    #[test]
+    fn should_keep_known_id() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let known_id = Uuid::new_v4();
+        let mut registry = PersonIdRegistry { ids: HashMap::from([(RegistrationKey::new("cfm2022", 1), known_id)]) };
+
+        let people = registry.assign_ids(vec![Person::create_test_instance("John Doe").with_registrations_id(&convention1, vec![1]).with_registrations_id(&convention2, vec![2])]);
+
+        assert_eq!(people[0].id(), &known_id);
+        assert_eq!(registry.ids.get(&RegistrationKey::new("cfm2023", 2)), Some(&known_id));
+    }
+
+    #[test]
+    fn should_not_give_same_id_to_split_people() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let known_id = Uuid::new_v4();
+        let mut registry = PersonIdRegistry {
+            ids: HashMap::from([(RegistrationKey::new("cfm2022", 1), known_id), (RegistrationKey::new("cfm2022", 2), known_id)]),
+        };
+
+        let people = registry.assign_ids(vec![
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1]),
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![2]),
+        ]);
+
+        assert_eq!(people[0].id(), &known_id);
+        assert_ne!(people[1].id(), &known_id);
+    }
+
+    #[test]
+    fn should_give_known_id_to_same_part_of_split_person_whatever_the_order() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let known_id = Uuid::new_v4();
+        let known_ids = HashMap::from([1, 2, 3, 4].map(|id| (RegistrationKey::new("cfm2022", id), known_id)));
+        let create_people = || vec![
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![3, 4]),
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![2]),
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1]),
+        ];
+
+        let people = PersonIdRegistry { ids: known_ids.clone() }.assign_ids(create_people());
+        let reversed_people = PersonIdRegistry { ids: known_ids }.assign_ids(create_people().into_iter().rev().collect());
+
+        assert_eq!(people[0].id(), &known_id);
+        assert_eq!(reversed_people[2].id(), &known_id);
+        assert_eq!(people.iter().filter(|person| person.id() == &known_id).count(), 1);
+    }
+
+    #[test]
+    fn should_break_ties_by_smallest_registration() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let known_id = Uuid::new_v4();
+        let known_ids = HashMap::from([(RegistrationKey::new("cfm2022", 1), known_id), (RegistrationKey::new("cfm2022", 2), known_id)]);
+
+        let people = PersonIdRegistry { ids: known_ids }.assign_ids(vec![
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![2]),
+            Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1]),
+        ]);
+
+        assert_ne!(people[0].id(), &known_id);
+        assert_eq!(people[1].id(), &known_id);
+    }
+}
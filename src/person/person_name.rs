@@ -1,56 +1,207 @@
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
 use derive_getters::Getters;
 use deunicode::deunicode;
-use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::person::romanization::{is_latin_script, romanization_key, romanize};
+
+/// Words that may precede a family name without being part of what it is sorted by, e.g. "van der" in "van der Berg".
+const PARTICLES: [&str; 21] = [
+    "al", "da", "das", "de", "del", "della", "den", "der", "des", "di", "do", "dos", "du", "el", "la", "le", "ten", "ter",
+    "van", "von", "zu",
+];
+
+/// Whether a name is written given names first ("John Doe") or family name first ("Doe John"), as in Hungary or Japan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameOrder {
+    #[default]
+    GivenFirst,
+    FamilyFirst,
+}
 
-#[derive(Debug, Getters, Eq, Hash, Clone)]
+impl Display for NameOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameOrder::GivenFirst => { write!(f, "given_first") }
+            NameOrder::FamilyFirst => { write!(f, "family_first") }
+        }
+    }
+}
+
+/// The structured parts of a name, which is how a [PersonName] is serialized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersonNameParts {
+    given_names: Vec<String>,
+    #[serde(default)]
+    particles: Vec<String>,
+    family_names: Vec<String>,
+    #[serde(default)]
+    name_order: NameOrder,
+    /// Only written when the name isn't in Latin letters, it is computed again when read.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    romanized_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+}
+
+/// The name of a person made of given names, particles and family names, each of them possibly empty
+/// (e.g. someone registered with a single name). `name` is how it is displayed, according to its [NameOrder],
+/// and `name_parts` are its words sanitized and sorted to compare names whatever their order and accents.
+/// `romanized_name` is the name in Latin letters, and `romanized_parts` are the [romanization_key]s of its words,
+/// so that a name written in Cyrillic, Japanese or Korean matches its usual romanizations.
+/// `aliases` are other ways the same person wrote their name, e.g. in another script.
+/// Names are compared by their `name_parts`, not by their romanized parts, which are too lossy to tell
+/// "Michael" from "Michel": romanizations are only scored as candidates when merging identities.
+#[derive(Debug, Getters, Eq, Clone, Serialize, Deserialize)]
+#[serde(from = "PersonNameParts", into = "PersonNameParts")]
 pub struct PersonName {
+    given_names: Vec<String>,
+    particles: Vec<String>,
+    family_names: Vec<String>,
+    name_order: NameOrder,
     name: String,
     name_parts: Vec<String>,
+    romanized_name: String,
+    romanized_parts: Vec<String>,
+    aliases: Vec<String>,
+    #[getter(skip)]
+    matching_parts: Vec<String>,
 }
 
 impl PersonName {
+    /// Read a full name written given names first: the family name starts at the first particle or is the last word.
     pub fn new(name: &str) -> Self {
-        let mut name_parts: Vec<String> = PersonName::sanitize_name(name);
-        name_parts.sort();
-        Self { name: String::from(name.trim()), name_parts }
+        let words = split_words(name);
+        let family_start = words.iter()
+            .skip(1)
+            .position(|word| is_particle(word))
+            .map(|position| position + 1)
+            .unwrap_or(words.len().saturating_sub(1));
+        let (given_names, family_names) = words.split_at(family_start);
+        let (particles, family_names) = split_particles(family_names);
+        Self::from_parts(given_names.to_vec(), particles, family_names, NameOrder::GivenFirst)
+    }
+
+    /// Read a name as registered, with its first names and its last names apart.
+    pub fn from_names(first_name: &str, last_name: &str, name_order: NameOrder) -> Self {
+        let (particles, family_names) = split_particles(&split_words(last_name));
+        Self::from_parts(split_words(first_name), particles, family_names, name_order)
     }
 
-    pub fn from_names(names: &[&String; 2]) -> Self {
-        let name_parts = names.iter()
-            .map(|name| PersonName::sanitize_name(name))
-            .sorted()
-            .flatten()
-            .collect();
+    pub fn from_parts(given_names: Vec<String>, particles: Vec<String>, family_names: Vec<String>, name_order: NameOrder) -> Self {
+        let family_part = particles.iter().chain(family_names.iter());
+        let name = match name_order {
+            NameOrder::GivenFirst => { given_names.iter().chain(family_part).cloned().collect::<Vec<String>>() }
+            NameOrder::FamilyFirst => { family_part.chain(given_names.iter()).cloned().collect::<Vec<String>>() }
+        }.join(" ");
+        let name_parts = to_name_parts(given_names.iter().chain(particles.iter()).chain(family_names.iter()));
+        let romanized_name = romanize(&name);
+        let romanized_parts = to_romanized_parts(&name);
+        let matching_parts = name_parts.clone();
+
+        Self { given_names, particles, family_names, name_order, name, name_parts, romanized_name, romanized_parts, aliases: vec![], matching_parts }
+    }
 
-        let displayed_name = names.iter()
-            .map(|name_part| name_part.trim().to_string())
-            .fold(String::new(), |a, b| format!("{a} {b}"));
+    /// The same name, also known as each of the `aliases`.
+    /// Names sharing an alias are compared by the `name_parts` of the first of their names and aliases in alphabetical order,
+    /// so that they are equal.
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.matching_parts = aliases.iter()
+            .map(|alias| to_name_parts(split_words(alias).iter()))
+            .chain([self.name_parts.clone()])
+            .min()
+            .unwrap_or_default();
+        self.aliases = aliases;
+        self
+    }
 
-        Self { name: String::from(displayed_name), name_parts }
+    /// Whether every letter of the name is a Latin one, once accents are set aside.
+    pub fn is_in_latin_script(&self) -> bool {
+        is_latin_script(&self.name)
     }
 
-    fn sanitize_name(name: &str) -> Vec<String> {
-        name
-            .split(' ')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_lowercase())
-            .map(|s| deunicode(&s))
+    /// The sorted [romanization_key]s of the name and of each of its aliases, the name's first.
+    pub fn romanized_variants(&self) -> Vec<Vec<String>> {
+        [self.romanized_parts.clone()].into_iter()
+            .chain(self.aliases.iter().map(|alias| to_romanized_parts(alias)))
             .collect()
     }
 }
 
 impl PartialEq for PersonName {
-    /// [PersonName]s are considered as equal if their name is strictly equal
-    /// or if each part of both name is equal after accents have been replaced by their non-accentuated letters.
+    /// [PersonName]s are considered as equal if each part of both names is the same, whatever their order and their accents,
+    /// or if they are aliases of each other.
     fn eq(&self, other: &Self) -> bool {
-        return self.name == other.name
-            || self.name_parts == other.name_parts;
+        self.matching_parts == other.matching_parts
     }
 }
 
+impl Hash for PersonName {
+    /// Equal names have the same matching parts, whatever the way they are displayed.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.matching_parts.hash(state);
+    }
+}
+
+impl From<PersonNameParts> for PersonName {
+    fn from(parts: PersonNameParts) -> Self {
+        Self::from_parts(parts.given_names, parts.particles, parts.family_names, parts.name_order).with_aliases(parts.aliases)
+    }
+}
+
+impl From<PersonName> for PersonNameParts {
+    fn from(name: PersonName) -> Self {
+        let romanized_name = Some(name.romanized_name).filter(|romanized_name| *romanized_name != name.name);
+        Self {
+            given_names: name.given_names,
+            particles: name.particles,
+            family_names: name.family_names,
+            name_order: name.name_order,
+            romanized_name,
+            aliases: name.aliases,
+        }
+    }
+}
+
+fn to_name_parts<'a>(words: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut name_parts: Vec<String> = words.map(|word| deunicode(&word.to_lowercase())).collect();
+    name_parts.sort();
+    name_parts
+}
+
+fn to_romanized_parts(name: &str) -> Vec<String> {
+    let mut romanized_parts: Vec<String> = split_words(name).iter()
+        .map(|word| romanization_key(word))
+        .filter(|key| !key.is_empty())
+        .collect();
+    romanized_parts.sort();
+    romanized_parts
+}
+
+fn split_words(name: &str) -> Vec<String> {
+    name.split_whitespace().map(str::to_string).collect()
+}
+
+fn is_particle(word: &str) -> bool {
+    PARTICLES.contains(&word.to_lowercase().as_str())
+}
+
+/// Leading particles of the family names, provided a family name remains after them.
+fn split_particles(family_names: &[String]) -> (Vec<String>, Vec<String>) {
+    let particles_count = family_names.iter()
+        .take_while(|word| is_particle(word))
+        .count()
+        .min(family_names.len().saturating_sub(1));
+    let (particles, family_names) = family_names.split_at(particles_count);
+    (particles.to_vec(), family_names.to_vec())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::person::person_name::PersonName;
+    use crate::person::person_name::{NameOrder, PersonName};
 
     #[test]
     fn should_be_equal_when_same_name() {
@@ -72,4 +223,82 @@ mod tests {
     fn should_be_equal_when_same_name_with_accent() {
         assert_eq!(PersonName::new("John Doe"), PersonName::new("Jôhn Doé"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_display_registered_names() {
+        assert_eq!(PersonName::from_names(" John ", "Doe", NameOrder::GivenFirst).name(), "John Doe");
+        assert_eq!(PersonName::from_names("Anna Maria", "van der Berg", NameOrder::GivenFirst).name(), "Anna Maria van der Berg");
+        assert_eq!(PersonName::from_names("Madonna", "", NameOrder::GivenFirst).name(), "Madonna");
+        assert_eq!(PersonName::from_names("", "Van", NameOrder::GivenFirst).family_names(), &vec!["Van".to_string()]);
+        assert_eq!(PersonName::from_names("János", "Kovács", NameOrder::FamilyFirst).name(), "Kovács János");
+    }
+
+    #[test]
+    fn should_split_full_names() {
+        let name = PersonName::new("Anna Maria van der Berg");
+
+        assert_eq!(name.given_names(), &vec!["Anna".to_string(), "Maria".to_string()]);
+        assert_eq!(name.particles(), &vec!["van".to_string(), "der".to_string()]);
+        assert_eq!(name.family_names(), &vec!["Berg".to_string()]);
+        assert_eq!(name, PersonName::from_names("Anna Maria", "van der Berg", NameOrder::FamilyFirst));
+    }
+
+    #[test]
+    fn should_round_trip_through_json() {
+        let name = PersonName::from_names("Anna Maria", "van der Berg", NameOrder::FamilyFirst);
+
+        let json = serde_json::to_string(&name).unwrap();
+        let read_name: PersonName = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, r#"{"given_names":["Anna","Maria"],"particles":["van","der"],"family_names":["Berg"],"name_order":"family_first"}"#);
+        assert_eq!(read_name.name(), "van der Berg Anna Maria");
+        assert_eq!(read_name.given_names(), name.given_names());
+    }
+
+    #[test]
+    fn should_have_same_romanized_parts_when_same_name_in_another_script_or_romanization() {
+        assert_eq!(PersonName::new("Юрий Иванов").romanized_parts(), PersonName::new("Yuriy Ivanov").romanized_parts());
+        assert_eq!(PersonName::new("Iurii Ivanov").romanized_parts(), PersonName::new("Yuri Ivanov").romanized_parts());
+        assert_eq!(PersonName::from_names("Shouta", "Satou", NameOrder::FamilyFirst).romanized_parts(), PersonName::new("Shota Sato").romanized_parts());
+        assert_eq!(PersonName::from_names("연아", "김", NameOrder::FamilyFirst).romanized_parts(), PersonName::new("Yuna Kim").romanized_parts());
+        assert_ne!(PersonName::new("Yuri Ivanov").romanized_parts(), PersonName::new("Ilya Ivanov").romanized_parts());
+    }
+
+    #[test]
+    fn should_not_be_equal_when_only_romanized_parts_are_the_same() {
+        assert_ne!(PersonName::new("Michael Martin"), PersonName::new("Michel Martin"));
+        assert_ne!(PersonName::new("Henry Martin"), PersonName::new("Henri Martin"));
+        assert_ne!(PersonName::new("Louis Martin"), PersonName::new("Lois Martin"));
+        assert_ne!(PersonName::new("Leo Martin"), PersonName::new("Lu Martin"));
+        assert_ne!(PersonName::new("Юрий Иванов"), PersonName::new("Yuriy Ivanov"));
+    }
+
+    #[test]
+    fn should_tell_names_in_latin_letters() {
+        assert!(PersonName::new("Jôhn Doé").is_in_latin_script());
+        assert!(!PersonName::new("Юрий Иванов").is_in_latin_script());
+        assert!(!PersonName::from_names("연아", "김", NameOrder::FamilyFirst).is_in_latin_script());
+    }
+
+    #[test]
+    fn should_be_equal_to_aliases() {
+        let aliases = vec!["Ivan Petrov".to_string()];
+        let name = PersonName::new("Vanya Petrov").with_aliases(aliases.clone());
+
+        assert_eq!(name, PersonName::new("Ivan Petrov").with_aliases(aliases));
+        assert_eq!(name, PersonName::new("Ivan Petrov"));
+        assert_ne!(name, PersonName::new("Vanya Petrov"));
+        assert_eq!(name.romanized_variants().len(), 2);
+    }
+
+    #[test]
+    fn should_write_romanized_name_when_not_in_latin_letters() {
+        let name = PersonName::from_names("Юрий", "Иванов", NameOrder::GivenFirst);
+
+        let json = serde_json::to_string(&name).unwrap();
+
+        assert_eq!(name.romanized_name(), "Iurii Ivanov");
+        assert_eq!(json, r#"{"given_names":["Юрий"],"particles":[],"family_names":["Иванов"],"name_order":"given_first","romanized_name":"Iurii Ivanov"}"#);
+        assert_eq!(serde_json::from_str::<PersonName>(&json).unwrap(), name);
+    }
+}
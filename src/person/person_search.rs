@@ -0,0 +1,251 @@
+use std::cmp::Ordering;
+
+use chrono::{Datelike, NaiveDate};
+use derive_getters::Getters;
+use log::{error, info};
+use serde::Serialize;
+
+use crate::export::to_markdown_table;
+use crate::person::person::Person;
+use crate::person::person_name::PersonName;
+use crate::utils::string_similarity::similarity;
+
+/// Score of a searched word that is the beginning of a name part, e.g. "do" for "doe".
+const PREFIX_SCORE: f32 = 0.9;
+/// Under this similarity, a searched word doesn't match a name part.
+const MIN_WORD_SCORE: f32 = 0.65;
+
+const BIRTH_YEAR_OPTION: &str = "--birth-year";
+const MAX_DISPLAYED_HITS: usize = 20;
+
+/// A match of a search, scored between 0 and 1 (every searched word is a part of the name).
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct SearchHit<T> {
+    item: T,
+    score: f32,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedName<T> {
+    item: T,
+    name: String,
+    name_parts: Vec<String>,
+    is_in_latin_script: bool,
+    romanized_variants: Vec<Vec<String>>,
+    birth_year: i32,
+}
+
+/// An index of names to search people by, whatever the case, the accents, the order, the script and the aliases of the names,
+/// each searched word matching a distinct part of the name either exactly, as a prefix or approximately.
+/// Items are what is returned for a match, e.g. a [Person] or a position in a list of people.
+#[derive(Debug, Clone, Default)]
+pub struct PersonSearchIndex<T> {
+    names: Vec<IndexedName<T>>,
+}
+
+impl<'p, 'a> PersonSearchIndex<&'p Person<'a>> {
+    pub fn from_people(people: &'p [Person<'a>]) -> Self {
+        Self::new(people.iter().map(|person| (person, person.identity().person_name(), *person.identity().birthday())))
+    }
+}
+
+impl<T: Clone> PersonSearchIndex<T> {
+    pub fn new<'n>(names: impl IntoIterator<Item=(T, &'n PersonName, NaiveDate)>) -> Self {
+        let names = names.into_iter()
+            .map(|(item, person_name, birthday)| IndexedName {
+                item,
+                name: person_name.name().trim().to_string(),
+                name_parts: person_name.name_parts().clone(),
+                is_in_latin_script: person_name.is_in_latin_script(),
+                romanized_variants: person_name.romanized_variants(),
+                birth_year: birthday.year(),
+            })
+            .collect();
+        Self { names }
+    }
+
+    /// People whose name matches every word of the query, born in `birth_year` if given,
+    /// from the best match to the worst and then by name.
+    /// Romanized names are only scored when the query or the name isn't in Latin script,
+    /// so that a Latin name close to the query doesn't score as well as the exact one.
+    pub fn search(&self, query: &str, birth_year: Option<i32>) -> Vec<SearchHit<T>> {
+        let query_name = PersonName::new(query);
+        let mut hits: Vec<(&str, SearchHit<T>)> = self.names.iter()
+            .filter(|indexed_name| birth_year.is_none_or(|birth_year| indexed_name.birth_year == birth_year))
+            .filter_map(|indexed_name| {
+                let is_romanized = !query_name.is_in_latin_script() || !indexed_name.is_in_latin_script;
+                let score = indexed_name.romanized_variants.iter()
+                    .filter(|_| is_romanized)
+                    .filter_map(|romanized_parts| score_name(query_name.romanized_parts(), romanized_parts))
+                    .chain(score_name(query_name.name_parts(), &indexed_name.name_parts))
+                    .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))?;
+                Some((indexed_name.name.as_str(), SearchHit { item: indexed_name.item.clone(), score }))
+            })
+            .collect();
+        hits.sort_by(|(a_name, a), (b_name, b)| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal).then_with(|| a_name.cmp(b_name)));
+        hits.into_iter().map(|(_, hit)| hit).collect()
+    }
+}
+
+/// Average score of the searched words, each one being matched with the best name part not matched yet,
+/// or `None` if a word doesn't match any name part.
+fn score_name(query_parts: &[String], name_parts: &[String]) -> Option<f32> {
+    if query_parts.is_empty() {
+        return Some(1.);
+    }
+    let mut available_parts: Vec<&String> = name_parts.iter().collect();
+    let mut total_score = 0.;
+    for query_part in query_parts {
+        let (index, score) = available_parts.iter()
+            .map(|name_part| score_word(query_part, name_part))
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))?;
+        if score < MIN_WORD_SCORE {
+            return None;
+        }
+        available_parts.remove(index);
+        total_score += score;
+    }
+    Some(total_score / query_parts.len() as f32)
+}
+
+fn score_word(query_part: &str, name_part: &str) -> f32 {
+    if query_part == name_part {
+        return 1.;
+    }
+    if name_part.starts_with(query_part) {
+        return PREFIX_SCORE;
+    }
+    let name_prefix: String = name_part.chars().take(query_part.chars().count()).collect();
+    similarity(query_part, name_part).max(similarity(query_part, &name_prefix) * PREFIX_SCORE)
+}
+
+#[derive(Serialize)]
+struct DisplayedHit<'a> {
+    score: String,
+    id: String,
+    name: &'a str,
+    birth_year: i32,
+    conventions: String,
+}
+
+/// Run the `search <name> [--birth-year <year>]` command: print the people matching the query, the best matches first.
+pub fn run_search_command(people: &[Person], args: &[String]) -> Result<(), ()> {
+    let usage = || error!("Usage: search <name> [{BIRTH_YEAR_OPTION} <year>]");
+    let mut query_words: Vec<&str> = vec![];
+    let mut birth_year = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg != BIRTH_YEAR_OPTION {
+            query_words.push(arg);
+            continue;
+        }
+        match args.next().and_then(|year| year.parse::<i32>().ok()) {
+            Some(year) => { birth_year = Some(year) }
+            None => {
+                usage();
+                return Err(());
+            }
+        }
+    }
+    if query_words.is_empty() && birth_year.is_none() {
+        usage();
+        return Err(());
+    }
+
+    let query = query_words.join(" ");
+    let hits = PersonSearchIndex::from_people(people).search(&query, birth_year);
+    info!("People searched [query: {query}, birth_year: {birth_year:?}, hits: {}]", hits.len());
+    let displayed_hits: Vec<DisplayedHit> = hits.iter()
+        .take(MAX_DISPLAYED_HITS)
+        .map(|hit| {
+            let mut conventions: Vec<&str> = hit.item.registrations_id().keys().map(|convention| convention.tag().as_str()).collect();
+            conventions.sort();
+            DisplayedHit {
+                score: format!("{:.2}", hit.score),
+                id: hit.item.id().to_string(),
+                name: hit.item.identity().person_name().name(),
+                birth_year: hit.item.identity().birthday().year(),
+                conventions: conventions.join(", "),
+            }
+        })
+        .collect();
+    match to_markdown_table(&displayed_hits) {
+        Ok(table) => {
+            println!("{table}");
+            Ok(())
+        }
+        Err(error) => {
+            error!("Can't display search results: {error}");
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::person::person_name::PersonName;
+    use crate::person::person_search::PersonSearchIndex;
+
+    fn create_index(names: &[(&str, i32)]) -> PersonSearchIndex<usize> {
+        let person_names: Vec<(PersonName, NaiveDate)> = names.iter()
+            .map(|(name, birth_year)| (PersonName::new(name), NaiveDate::from_ymd_opt(*birth_year, 6, 1).unwrap()))
+            .collect();
+        PersonSearchIndex::new(person_names.iter().enumerate().map(|(index, (name, birthday))| (index, name, *birthday)))
+    }
+
+    fn search(index: &PersonSearchIndex<usize>, query: &str, birth_year: Option<i32>) -> Vec<usize> {
+        index.search(query, birth_year).iter().map(|hit| *hit.item()).collect()
+    }
+
+    #[test]
+    fn should_find_names_by_prefix_and_approximately_whatever_the_order_and_accents() {
+        let index = create_index(&[("Jôhn Doé", 1990), ("Jane Doe", 1992), ("Johan Dupont", 1990), ("Marc Dolan", 1985)]);
+
+        assert_eq!(search(&index, "jon do", None), vec![0]);
+        assert_eq!(search(&index, "doe jo", None), vec![0]);
+        assert_eq!(search(&index, "JOHN DOE", None), vec![0]);
+        assert_eq!(search(&index, "do", None), vec![1, 0, 3]);
+        assert!(search(&index, "smith", None).is_empty());
+    }
+
+    #[test]
+    fn should_find_names_whatever_their_script_and_romanization() {
+        let index = create_index(&[("Юрий Иванов", 1990), ("Shouta Satou", 1992), ("Ilya Ivanov", 1990)]);
+
+        assert_eq!(search(&index, "yuriy ivanov", None), vec![0]);
+        assert_eq!(search(&index, "iurii", None), vec![0]);
+        assert_eq!(search(&index, "syota", None), vec![1]);
+        assert_eq!(search(&index, "Иванов", None), vec![2, 0]);
+    }
+
+    #[test]
+    fn should_filter_by_birth_year() {
+        let index = create_index(&[("John Doe", 1990), ("John Doe", 1992)]);
+
+        assert_eq!(search(&index, "john doe", Some(1992)), vec![1]);
+        assert_eq!(search(&index, "", Some(1990)), vec![0]);
+    }
+
+    #[test]
+    fn should_rank_exact_matches_first() {
+        let index = create_index(&[("Johnny Doe", 1990), ("John Doe", 1990), ("Jon Doe", 1990)]);
+
+        let hits = index.search("john doe", None);
+
+        assert_eq!(hits.iter().map(|hit| *hit.item()).collect::<Vec<usize>>(), vec![1, 0, 2]);
+        assert_eq!(*hits[0].score(), 1.);
+    }
+
+    #[test]
+    fn should_rank_exact_latin_match_before_names_romanized_the_same() {
+        let index = create_index(&[("Michael Doe", 1990), ("Michel Doe", 1990)]);
+
+        let hits = index.search("Michel", None);
+
+        assert_eq!(hits.iter().map(|hit| *hit.item()).collect::<Vec<usize>>(), vec![1, 0]);
+        assert!(hits[0].score() > hits[1].score());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+use deunicode::deunicode;
+
+/// Romanizations of a same name part that no rule can tell apart, mostly Korean family names.
+/// Every variant is replaced by the first one of its list.
+const NAME_PART_VARIANTS: [&[&str]; 7] = [
+    &["lee", "yi", "i", "rhee", "ri", "rhie"],
+    &["park", "bak", "pak", "bag"],
+    &["kim", "gim"],
+    &["choi", "choe", "coe", "chwe"],
+    &["jung", "jeong", "chung", "joung"],
+    &["kang", "gang"],
+    &["yoon", "yun"],
+];
+
+/// Spellings of a same sound in different romanization systems, replaced in this order.
+const ROMANIZATION_RULES: [(&str, &str); 21] = [
+    ("x", "ks"),
+    // Cyrillic, e.g. "Shcherbakov" or "Scherbakov", "Khokhlov" or "Hohlov", "Kseniia" or "Ksenia", "Iurii" or "Yuriy"
+    ("shch", "sh"),
+    ("sch", "sh"),
+    ("kh", "h"),
+    ("iya", "ya"),
+    ("iia", "ya"),
+    ("ia", "ya"),
+    ("iu", "yu"),
+    // Japanese (Kunrei and Hepburn), e.g. "Siyouta" or "Shota", "Tihiro" or "Chihiro", "Tubasa" or "Tsubasa", "Ziyun" or "Jun"
+    ("iy", "y"),
+    ("sy", "sh"),
+    ("zy", "j"),
+    ("ty", "ch"),
+    ("ti", "chi"),
+    ("tu", "tsu"),
+    ("zi", "ji"),
+    ("hu", "fu"),
+    ("ou", "o"),
+    ("oo", "o"),
+    ("uu", "u"),
+    // Korean (Revised and McCune-Reischauer), e.g. "Jiseong" or "Jisung", "Yeona" or "Yuna"
+    ("eo", "u"),
+    ("ae", "e"),
+];
+
+/// The name in Latin letters, e.g. "Iurii Ivanov" for "Юрий Иванов".
+pub fn romanize(name: &str) -> String {
+    deunicode(name)
+}
+
+/// Whether every letter of the name is a Latin one, with or without accents, e.g. "Jôhn Doé" but not "Юрий Иванов".
+pub fn is_latin_script(name: &str) -> bool {
+    name.chars()
+        .filter(|character| character.is_alphabetic())
+        .all(|character| character.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&character) || ('\u{1E00}'..='\u{1EFF}').contains(&character))
+}
+
+/// A key that is the same for the usual romanizations of a name part, whatever its script,
+/// e.g. "yuri" for "Юрий", "Yuriy" and "Yuri", or "shota" for "しょうた", "Shouta" and "Syota".
+pub fn romanization_key(name_part: &str) -> String {
+    let letters: String = romanize(name_part)
+        .to_lowercase()
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect();
+    if let Some(variants) = NAME_PART_VARIANTS.iter().find(|variants| variants.contains(&letters.as_str())) {
+        return variants[0].to_string();
+    }
+
+    let mut key = letters;
+    for (spelling, replacement) in ROMANIZATION_RULES {
+        key = key.replace(spelling, replacement);
+    }
+    // Cyrillic "Е" at the beginning of a name, e.g. "Yevgeny" or "Evgenii"
+    if key.starts_with("ye") {
+        key.replace_range(..2, "e");
+    }
+    for (ending, replacement) in [("ii", "i"), ("ij", "i"), ("oh", "o"), ("y", "i")] {
+        if key.len() > ending.len() && key.ends_with(ending) {
+            key.replace_range(key.len() - ending.len().., replacement);
+            break;
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::person::romanization::romanization_key;
+
+    fn assert_same_key(name_parts: &[&str]) {
+        let keys: Vec<String> = name_parts.iter().map(|name_part| romanization_key(name_part)).collect();
+        assert!(keys.iter().all(|key| *key == keys[0]), "{name_parts:?} have different keys: {keys:?}");
+    }
+
+    #[test]
+    fn should_give_same_key_to_cyrillic_romanizations() {
+        assert_same_key(&["Юрий", "Yuriy", "Yuri", "Yury"]);
+        assert_same_key(&["Евгений", "Yevgeny", "Evgeniy"]);
+        assert_same_key(&["Алексей", "Alexey", "Aleksei"]);
+        assert_same_key(&["Наталья", "Natalya", "Natalia"]);
+        assert_same_key(&["Ксения", "Ksenia", "Kseniya"]);
+        assert_same_key(&["Щербаков", "Scherbakov", "Shcherbakov"]);
+        assert_same_key(&["Хохлов", "Khokhlov", "Hohlov"]);
+    }
+
+    #[test]
+    fn should_give_same_key_to_japanese_romanizations() {
+        assert_same_key(&["しょうた", "Shota", "Shouta", "Syota"]);
+        assert_same_key(&["ちひろ", "Chihiro"]);
+        assert_same_key(&["つばさ", "Tsubasa"]);
+        assert_same_key(&["じゅん", "Jun"]);
+        assert_same_key(&["さとう", "Sato", "Satoh", "Satou"]);
+        assert_same_key(&["ゆうき", "Yuki", "Yuuki"]);
+    }
+
+    #[test]
+    fn should_give_same_key_to_korean_romanizations() {
+        assert_same_key(&["박", "Park", "Pak"]);
+        assert_same_key(&["김", "Kim"]);
+        assert_same_key(&["이", "Lee", "Yi"]);
+        assert_same_key(&["최", "Choi"]);
+        assert_same_key(&["지성", "Ji-sung", "Jiseong"]);
+        assert_same_key(&["연아", "Yuna", "Yeon-a"]);
+    }
+
+    #[test]
+    fn should_keep_different_names_apart() {
+        assert_ne!(romanization_key("Ivan"), romanization_key("Ilya"));
+        assert_ne!(romanization_key("Kim"), romanization_key("Park"));
+        assert_ne!(romanization_key("Sato"), romanization_key("Saito"));
+    }
+}
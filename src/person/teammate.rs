@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use crate::competition::competition::Competition;
+use crate::competitor::competitor::Competitor;
+use crate::convention::convention::Convention;
+use crate::export::write_json_file;
+use crate::person::identity_overrides::RegistrationKey;
+use crate::person::person::Person;
+
+const TEAMMATES_FILE: &str = "teammates.json";
+
+/// A [Teammate] is a member of a team a person competed in, such as a relay or a pair.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Teammate<'a> {
+    convention: &'a Convention,
+    competition: Competition,
+    member: Competitor,
+}
+
+impl<'a> Teammate<'a> {
+    pub fn new(convention: &'a Convention, competition: Competition, member: Competitor) -> Self {
+        Self { convention, competition, member }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedTeamCompetition<'a> {
+    convention: &'a str,
+    competition: String,
+}
+
+#[derive(Serialize)]
+struct ExportedTeammate<'a> {
+    // Empty when the teammate isn't registered, and so can't be linked to a person
+    id: String,
+    name: String,
+    competitions: Vec<ExportedTeamCompetition<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedTeammates<'a> {
+    id: String,
+    name: &'a str,
+    teammates: Vec<ExportedTeammate<'a>>,
+}
+
+/// Export the teammates of every person as JSON in `{folder}/teammates.json`.
+/// A teammate who is a known person is listed once, along with every team competition both of them took part in.
+pub fn export_teammates(folder: &str, people: &[Person]) -> Result<(), ()> {
+    let people_by_registration: HashMap<RegistrationKey, &Person> = people.iter()
+        .flat_map(|person| RegistrationKey::from_person(person).into_iter().map(move |registration| (registration, person)))
+        .collect();
+
+    let mut exported_people = vec![];
+    for person in people {
+        let teammates = person.teammates();
+        if teammates.is_empty() {
+            continue;
+        }
+
+        let mut exported_teammates: Vec<ExportedTeammate> = vec![];
+        for teammate in &teammates {
+            let teammate_person = teammate.member.ids().first()
+                .and_then(|id| people_by_registration.get(&RegistrationKey::new(teammate.convention.tag(), *id)));
+            let (id, name) = match teammate_person {
+                None => { (String::new(), teammate.member.name().unwrap_or_default().to_string()) }
+                Some(teammate_person) => {
+                    (teammate_person.id().to_string(), teammate_person.identity().person_name().name().to_string())
+                }
+            };
+            let competition = ExportedTeamCompetition {
+                convention: teammate.convention.tag(),
+                competition: teammate.competition.name().clone(),
+            };
+            let known_teammate = exported_teammates.iter_mut()
+                .find(|exported_teammate| !id.is_empty() && exported_teammate.id == id);
+            match known_teammate {
+                None => { exported_teammates.push(ExportedTeammate { id, name, competitions: vec![competition] }); }
+                Some(known_teammate) => { known_teammate.competitions.push(competition); }
+            }
+        }
+
+        exported_people.push(ExportedTeammates {
+            id: person.id().to_string(),
+            name: person.identity().person_name().name(),
+            teammates: exported_teammates,
+        });
+    }
+    exported_people.sort_by(|a, b| a.name.cmp(b.name));
+
+    write_json_file(folder, TEAMMATES_FILE, &exported_people)
+}
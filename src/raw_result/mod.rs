@@ -1 +1,3 @@
-pub mod raw_result;
\ No newline at end of file
+pub mod raw_result;
+pub mod results_snapshot;
+pub mod results_diff;
\ No newline at end of file
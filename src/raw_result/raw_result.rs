@@ -1,12 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
 use derive_getters::Getters;
-use log::{error, warn};
+use log::{debug, error, warn};
 
 use crate::competition::competition::Competition;
 use crate::competition::competition_result::CompetitionResult;
+use crate::competitor::competitor::Competitor;
+use crate::competitor::gender::Gender;
+use crate::competitor::individual_competitor::IndividualCompetitor;
+use crate::competitor::team::Team;
+use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
 use crate::convention::convention::Convention;
 use crate::load_raw_results;
+use crate::registration::registrant::Registrant;
 use crate::result::age_group::AgeGroup;
 use crate::result::place::Place;
 use crate::result::result_type::ResultType;
@@ -42,12 +48,32 @@ impl RawResult {
     ) -> Self {
         Self { ids, name, gender, age, competition, place, result_type, result, details, age_group }
     }
+
+    pub fn with_name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
+
+    pub fn with_details(self, details: String) -> Self {
+        Self { details, ..self }
+    }
 }
 
-pub fn load_raw_results_for_conventions(conventions: &HashSet<Convention>) -> HashMap<&Convention, Vec<RawResult>> {
+/// Load the raw results of every convention. The ones of a convention that `load_ingested_raw_results` returns,
+/// e.g. because its files haven't changed since it was ingested, are used rather than parsed again,
+/// and the convention is returned as unchanged.
+pub fn load_raw_results_for_conventions(conventions: &HashSet<Convention>,
+                                        load_ingested_raw_results: impl Fn(&Convention) -> Option<Vec<RawResult>>) -> (HashMap<&Convention, Vec<RawResult>>, HashSet<&Convention>) {
     let mut results = HashMap::new();
+    let mut unchanged_conventions = HashSet::new();
 
     for convention in conventions {
+        if let Some(raw_results) = load_ingested_raw_results(convention) {
+            debug!("Convention unchanged since last ingestion [convention: {}]", convention.tag());
+            results.insert(convention, raw_results);
+            unchanged_conventions.insert(convention);
+            continue;
+        }
+
         let file_name = format!("{}/{}/results.xls", DATA_FOLDER, convention.tag());
         let raw_results = match load_raw_results(&file_name) {
             Ok(raw_results) => { raw_results }
@@ -60,15 +86,19 @@ pub fn load_raw_results_for_conventions(conventions: &HashSet<Convention>) -> Ha
         results.insert(convention, raw_results);
     }
 
-
-    results
+    (results, unchanged_conventions)
 }
 
-pub fn get_results_from_raw_results_lines(raw_results: &Vec<RawResult>) -> Vec<CompetitionResult> {
+/// Read the results of a convention. Competitors are resolved from the registrants of the convention:
+/// an id with no registrant row is kept as an [UnknownIndividualCompetitor].
+pub fn get_results_from_raw_results_lines(raw_results: &Vec<RawResult>, convention: &Convention, registrants: &[Registrant]) -> Vec<CompetitionResult> {
+    let registrants: HashMap<u16, &Registrant> = registrants.iter()
+        .map(|registrant| (*registrant.id(), registrant))
+        .collect();
     let mut results = vec![];
 
     for raw_result in raw_results {
-        let result = match read_competition_result_from_raw_result(raw_result) {
+        let result = match read_competition_result_from_raw_result(raw_result, convention, &registrants) {
             Ok(result) => { result }
             Err(error) => {
                 warn!("Can't read raw result line: {}", error);
@@ -110,7 +140,7 @@ fn get_ids_from_raw_result(ids: &str) -> Vec<u16> {
     ids_vec
 }
 
-fn read_competition_result_from_raw_result(raw_result: &RawResult) -> Result<Vec<CompetitionResult>, String> {
+fn read_competition_result_from_raw_result(raw_result: &RawResult, convention: &Convention, registrants: &HashMap<u16, &Registrant>) -> Result<Option<CompetitionResult>, String> {
     let competition = Competition::new(raw_result.competition());
     let place = Place::from_string(raw_result.place())?;
     let result_type = ResultType::from_string(raw_result.result_type())?;
@@ -130,8 +160,156 @@ fn read_competition_result_from_raw_result(raw_result: &RawResult) -> Result<Vec
         Some(AgeGroup::from_string(raw_result.age_group()))
     };
 
+    let gender = match Gender::from_string(raw_result.gender()) {
+        Ok(gender) => { Some(gender) }
+        Err(error) => {
+            warn!("Can't read gender, result will be kept without gender: {}", error);
+            None
+        }
+    };
+
     let ids = get_ids_from_raw_result(raw_result.ids());
-    Ok(ids.iter()
-        .map(|id| CompetitionResult::new(*id, competition.clone(), place.clone(), result_type.clone(), result.clone(), details.clone(), age_group.clone()))
-        .collect())
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    let competitor = create_competitor(raw_result, &ids, gender, convention, registrants);
+    if let (Competitor::IndividualCompetitor(individual), Some(age_group)) = (&competitor, &age_group) {
+        if let Some(false) = individual.age().and_then(|age| age_group.contains(age)) {
+            warn!("Age doesn't fit age group [convention: {}, id: {}, age: {:?}, age_group: {}]", convention.tag(), individual.id(), individual.age(), raw_result.age_group());
+        }
+    }
+    Ok(Some(CompetitionResult::new(competition, place, result_type, result, details, age_group, competitor)))
+}
+
+/// A single id is an individual, several ids are a team whose members are the registrants of these ids.
+fn create_competitor(raw_result: &RawResult, ids: &[u16], gender: Option<Gender>, convention: &Convention, registrants: &HashMap<u16, &Registrant>) -> Competitor {
+    if let [id] = ids {
+        let Some(registrant) = registrants.get(id) else {
+            return Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(*id).with_sheet_details(raw_result.name(), gender, *raw_result.age()));
+        };
+        let age = get_age_at_convention(registrant, convention, Some(*raw_result.age()).filter(|age| *age > 0));
+        return Competitor::IndividualCompetitor(IndividualCompetitor::new(*id, raw_result.name().trim().to_string(), gender, age));
+    }
+
+    let members = ids.iter()
+        .map(|id| match registrants.get(id) {
+            None => { Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(*id)) }
+            Some(registrant) => {
+                let name = format!("{} {}", registrant.first_name().trim(), registrant.last_name().trim());
+                let age = get_age_at_convention(registrant, convention, None);
+                Competitor::IndividualCompetitor(IndividualCompetitor::new(*id, name, None, age))
+            }
+        })
+        .collect();
+    Competitor::Team(Team::new(raw_result.name().trim().to_string(), gender, members))
+}
+
+/// Compute the age of the registrant at the start of the convention, and check it against the age written in the results sheet.
+/// The age of the sheet is kept when the dates of the convention are unknown.
+fn get_age_at_convention(registrant: &Registrant, convention: &Convention, sheet_age: Option<u8>) -> Option<u8> {
+    let Some(start_date) = convention.start_date() else { return sheet_age; };
+    let end_date = convention.end_date().unwrap_or(*start_date);
+    let age = registrant.age_at(*start_date)?;
+    if let Some(sheet_age) = sheet_age {
+        // The registrant may have had their birthday during the convention
+        let age_at_end = registrant.age_at(end_date).unwrap_or(age);
+        if sheet_age < age || sheet_age > age_at_end {
+            warn!("Age in results sheet differs from the one computed from birthday [convention: {}, id: {}, sheet_age: {sheet_age}, computed_age: {age}]", convention.tag(), registrant.id());
+        }
+    }
+
+    Some(age)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::gender::Gender;
+    use crate::competitor::individual_competitor::IndividualCompetitor;
+    use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
+    use crate::convention::convention::Convention;
+    use crate::raw_result::raw_result::{RawResult, read_competition_result_from_raw_result};
+    use crate::registration::registrant::Registrant;
+
+    fn create_raw_result(ids: &str, name: &str, gender: &str, age: u8) -> RawResult {
+        RawResult::new(
+            ids.to_string(),
+            name.to_string(),
+            gender.to_string(),
+            age,
+            "100m".to_string(),
+            "1".to_string(),
+            "Overall".to_string(),
+            "00:14.990".to_string(),
+            String::new(),
+            String::new(),
+        )
+    }
+
+    fn create_registrant(id: u16, first_name: &str) -> Registrant {
+        Registrant::create_test_instance(id, first_name, "Doe", NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+    }
+
+    #[test]
+    fn should_read_individual_competitor() {
+        let registrant = create_registrant(12, "John");
+        let registrants = HashMap::from([(12, &registrant)]);
+
+        let result = read_competition_result_from_raw_result(&create_raw_result("12", "John Doe", "Male", 22), &Convention::create_test_instance("cfm2022"), &registrants).unwrap().unwrap();
+
+        let expected_competitor = IndividualCompetitor::new(12, "John Doe".to_string(), Some(Gender::from_string("Male").unwrap()), Some(22));
+        assert_eq!(result.competitor(), &Competitor::IndividualCompetitor(expected_competitor));
+    }
+
+    #[test]
+    fn should_read_unknown_competitor() {
+        let result = read_competition_result_from_raw_result(&create_raw_result("12", "John Doe", "Male", 22), &Convention::create_test_instance("cfm2022"), &HashMap::new()).unwrap().unwrap();
+
+        let Competitor::UnknownIndividualCompetitor(competitor) = result.competitor() else { panic!("Expected an unknown competitor") };
+        assert_eq!(competitor.id(), &12);
+        assert_eq!(competitor.name(), &Some("John Doe".to_string()));
+        assert_eq!(competitor.gender(), &Some(Gender::from_string("Male").unwrap()));
+        assert_eq!(competitor.age(), &Some(22));
+        assert_eq!(result.competitor().name(), Some("John Doe"));
+    }
+
+    #[test]
+    fn should_read_team_members() {
+        let john = create_registrant(12, "John");
+        let jane = create_registrant(45, "Jane");
+        let registrants = HashMap::from([(12, &john), (45, &jane)]);
+
+        let result = read_competition_result_from_raw_result(&create_raw_result("12, 45, 78", "Team Doe", "(mixed)", 0), &Convention::create_test_instance("cfm2022"), &registrants).unwrap().unwrap();
+
+        let Competitor::Team(team) = result.competitor() else { panic!("Expected a team") };
+        assert_eq!(team.name(), "Team Doe");
+        assert_eq!(team.gender(), &Some(Gender::from_string("(mixed)").unwrap()));
+        assert_eq!(team.members(), &vec![
+            Competitor::IndividualCompetitor(IndividualCompetitor::new(12, "John Doe".to_string(), None, None)),
+            Competitor::IndividualCompetitor(IndividualCompetitor::new(45, "Jane Doe".to_string(), None, None)),
+            Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(78)),
+        ]);
+    }
+
+    #[test]
+    fn should_compute_age_at_convention() {
+        let registrant = create_registrant(12, "John");
+        let registrants = HashMap::from([(12, &registrant)]);
+        let convention = Convention::new(
+            "cfm2022".to_string(),
+            "CFM 2022".to_string(),
+            NaiveDate::from_ymd_opt(2022, 7, 16),
+            NaiveDate::from_ymd_opt(2022, 7, 28),
+            None,
+        );
+
+        let result = read_competition_result_from_raw_result(&create_raw_result("12", "John Doe", "Male", 30), &convention, &registrants).unwrap().unwrap();
+
+        let Competitor::IndividualCompetitor(competitor) = result.competitor() else { panic!("Expected an individual") };
+        assert_eq!(competitor.age(), &Some(22));
+    }
 }
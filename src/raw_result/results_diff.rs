@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use derive_getters::Getters;
+use log::{error, info};
+use serde::Serialize;
+
+use crate::export::privacy::PrivacyPolicy;
+use crate::export::{to_markdown_table, write_csv_file, write_json_file, write_markdown_file};
+use crate::load_raw_results;
+use crate::raw_result::raw_result::RawResult;
+use crate::raw_result::results_snapshot::{list_results_snapshots, ResultsSnapshot};
+
+const RESULTS_DIFF_FILENAME: &str = "results_diff";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResultChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Display for ResultChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultChangeKind::Added => { write!(f, "Added") }
+            ResultChangeKind::Removed => { write!(f, "Removed") }
+            ResultChangeKind::Changed => { write!(f, "Changed") }
+        }
+    }
+}
+
+/// A result line is identified across snapshots by its competition, its result type, its age group and its competitor ids:
+/// the rest of the line is what may be corrected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Getters)]
+pub struct ResultKey {
+    competition: String,
+    result_type: String,
+    age_group: String,
+    ids: String,
+}
+
+impl ResultKey {
+    fn from_raw_result(raw_result: &RawResult) -> Self {
+        let mut ids: Vec<&str> = raw_result.ids().split(',').map(str::trim).collect();
+        ids.sort();
+        Self {
+            competition: raw_result.competition().clone(),
+            result_type: raw_result.result_type().clone(),
+            age_group: raw_result.age_group().clone(),
+            ids: ids.join(", "),
+        }
+    }
+}
+
+/// What may change in a result line after the event: its place (e.g. after a DQ), its value (e.g. a time correction) and its details.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct ResultLine {
+    name: String,
+    place: String,
+    result: String,
+    details: String,
+}
+
+impl ResultLine {
+    fn from_raw_result(raw_result: &RawResult) -> Self {
+        Self {
+            name: raw_result.name().clone(),
+            place: raw_result.place().clone(),
+            result: raw_result.result().clone(),
+            details: raw_result.details().clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct ResultChange {
+    kind: ResultChangeKind,
+    key: ResultKey,
+    old: Option<ResultLine>,
+    new: Option<ResultLine>,
+}
+
+/// List the results that have been added, removed or changed between two snapshots of the results of a convention,
+/// sorted by competition.
+/// Lines sharing a key (e.g. a competitor listed twice) are compared as multisets: identical lines cancel each other out,
+/// the remaining ones are paired in order as changes, and the extra ones are added or removed.
+pub fn diff_raw_results(old_raw_results: &[RawResult], new_raw_results: &[RawResult]) -> Vec<ResultChange> {
+    let index = |raw_results: &[RawResult]| -> BTreeMap<ResultKey, Vec<ResultLine>> {
+        let mut lines: BTreeMap<ResultKey, Vec<ResultLine>> = BTreeMap::new();
+        for raw_result in raw_results {
+            lines.entry(ResultKey::from_raw_result(raw_result)).or_default().push(ResultLine::from_raw_result(raw_result));
+        }
+        lines
+    };
+    let mut old_lines = index(old_raw_results);
+    let new_lines = index(new_raw_results);
+
+    let mut changes = vec![];
+    for (key, new_key_lines) in new_lines {
+        let mut old_key_lines = old_lines.remove(&key).unwrap_or_default();
+        let mut remaining_new_lines = vec![];
+        for new_line in new_key_lines {
+            match old_key_lines.iter().position(|old_line| old_line == &new_line) {
+                Some(position) => { old_key_lines.remove(position); }
+                None => { remaining_new_lines.push(new_line) }
+            }
+        }
+
+        let mut old_key_lines = old_key_lines.into_iter();
+        for new_line in remaining_new_lines {
+            match old_key_lines.next() {
+                None => { changes.push(ResultChange { kind: ResultChangeKind::Added, key: key.clone(), old: None, new: Some(new_line) }) }
+                Some(old_line) => {
+                    let is_changed = old_line.place != new_line.place || old_line.result != new_line.result || old_line.details != new_line.details;
+                    if is_changed {
+                        changes.push(ResultChange { kind: ResultChangeKind::Changed, key: key.clone(), old: Some(old_line), new: Some(new_line) });
+                    }
+                }
+            }
+        }
+        changes.extend(old_key_lines
+            .map(|old_line| ResultChange { kind: ResultChangeKind::Removed, key: key.clone(), old: Some(old_line), new: None }));
+    }
+    changes.extend(old_lines.into_iter()
+        .flat_map(|(key, old_key_lines)| old_key_lines.into_iter()
+            .map(move |old_line| ResultChange { kind: ResultChangeKind::Removed, key: key.clone(), old: Some(old_line), new: None })));
+    changes.sort_by(|a, b| a.key.cmp(&b.key));
+    changes
+}
+
+#[derive(Serialize)]
+struct ExportedResultChange<'a> {
+    change: String,
+    competition: &'a str,
+    result_type: &'a str,
+    age_group: &'a str,
+    ids: &'a str,
+    name: &'a str,
+    old_place: &'a str,
+    new_place: &'a str,
+    old_result: &'a str,
+    new_result: &'a str,
+    old_details: &'a str,
+    new_details: &'a str,
+}
+
+impl<'a> ExportedResultChange<'a> {
+    fn from_change(change: &'a ResultChange) -> Self {
+        let field = |line: &'a Option<ResultLine>, get: fn(&ResultLine) -> &String| -> &'a str {
+            line.as_ref().map(|line| get(line).as_str()).unwrap_or_default()
+        };
+        Self {
+            change: change.kind.to_string(),
+            competition: &change.key.competition,
+            result_type: &change.key.result_type,
+            age_group: &change.key.age_group,
+            ids: &change.key.ids,
+            name: change.new.as_ref().or(change.old.as_ref()).map(|line| line.name.as_str()).unwrap_or_default(),
+            old_place: field(&change.old, ResultLine::place),
+            new_place: field(&change.new, ResultLine::place),
+            old_result: field(&change.old, ResultLine::result),
+            new_result: field(&change.new, ResultLine::result),
+            old_details: field(&change.old, ResultLine::details),
+            new_details: field(&change.new, ResultLine::details),
+        }
+    }
+}
+
+/// Run the `diff <tag> [<from> <to>]` command: compare two snapshots of the results of a convention, the two latest ones by default,
+/// print the changes and export them in `{export_folder}/results_diff_{tag}_{from}_{to}.{json,csv,md}`.
+/// Results are anonymized by the privacy policy, if any, before being compared.
+pub fn run_diff_command(data_folder: &str, export_folder: &str, args: &[String], privacy_policy: Option<&PrivacyPolicy>) -> Result<(), ()> {
+    let Some(convention_tag) = args.first() else {
+        error!("Usage: diff <convention tag> [<from snapshot> <to snapshot>]");
+        return Err(());
+    };
+    let snapshots = list_results_snapshots(data_folder, convention_tag);
+    let (from, to) = match &args[1..] {
+        [] if snapshots.len() >= 2 => { (&snapshots[snapshots.len() - 2], &snapshots[snapshots.len() - 1]) }
+        [] => {
+            error!("At least two snapshots are needed to diff results [convention: {convention_tag}, snapshots: {}]", snapshots.len());
+            return Err(());
+        }
+        [from, to] => { (find_snapshot(&snapshots, convention_tag, from)?, find_snapshot(&snapshots, convention_tag, to)?) }
+        _ => {
+            error!("Usage: diff <convention tag> [<from snapshot> <to snapshot>]");
+            return Err(());
+        }
+    };
+
+    let load = |snapshot: &ResultsSnapshot| -> Result<Vec<RawResult>, ()> {
+        let raw_results = load_raw_results(snapshot.filepath()).map_err(|error| {
+            error!("Can't load results snapshot [filepath: {}]", snapshot.filepath());
+            error!("{error}");
+        })?;
+        match privacy_policy {
+            Some(privacy_policy) => { Ok(privacy_policy.anonymize_raw_results(convention_tag, raw_results)) }
+            None => { Ok(raw_results) }
+        }
+    };
+    let changes = diff_raw_results(&load(from)?, &load(to)?);
+    info!("Results diffed [convention: {convention_tag}, from: {}, to: {}, changes: {}]", from.name(), to.name(), changes.len());
+
+    let exported_changes: Vec<ExportedResultChange> = changes.iter().map(ExportedResultChange::from_change).collect();
+    match to_markdown_table(&exported_changes) {
+        Ok(table) => { println!("{table}") }
+        Err(error) => { error!("Can't display changes: {error}") }
+    }
+    let filename = format!("{RESULTS_DIFF_FILENAME}_{convention_tag}_{}_{}", from.name(), to.name());
+    let title = format!("Results changes of {convention_tag} from {} to {}", from.name(), to.name());
+    let json_result = write_json_file(export_folder, &format!("{filename}.json"), &exported_changes);
+    let csv_result = write_csv_file(export_folder, &format!("{filename}.csv"), &exported_changes);
+    let markdown_result = write_markdown_file(export_folder, &format!("{filename}.md"), &title, &exported_changes);
+
+    json_result.and(csv_result).and(markdown_result)
+}
+
+fn find_snapshot<'s>(snapshots: &'s [ResultsSnapshot], convention_tag: &str, name: &str) -> Result<&'s ResultsSnapshot, ()> {
+    snapshots.iter()
+        .find(|snapshot| snapshot.name() == name)
+        .ok_or_else(|| error!("Unknown results snapshot [convention: {convention_tag}, snapshot: {name}]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::raw_result::raw_result::RawResult;
+    use crate::raw_result::results_diff::{diff_raw_results, ResultChangeKind};
+
+    fn create_raw_result(ids: &str, place: &str, result: &str, details: &str) -> RawResult {
+        RawResult::new(ids.to_string(), "John Doe".to_string(), "Male".to_string(), 22, "100m".to_string(), place.to_string(),
+                       "Overall".to_string(), result.to_string(), details.to_string(), String::new())
+    }
+
+    #[test]
+    fn should_list_added_removed_and_changed_results() {
+        let old = vec![
+            create_raw_result("1", "1", "00:14.990", ""),
+            create_raw_result("2", "2", "00:15.990", ""),
+            create_raw_result("3, 4", "3", "00:16.990", ""),
+            create_raw_result("5", "4", "00:17.990", ""),
+        ];
+        let new = vec![
+            create_raw_result("1", "DQ", "00:14.990", "False start"),
+            create_raw_result("2", "1", "00:15.990", ""),
+            create_raw_result("4,3", "2", "00:16.990", ""),
+            create_raw_result("6", "3", "00:18.990", ""),
+        ];
+
+        let changes = diff_raw_results(&old, &new);
+
+        let kinds: Vec<(&str, ResultChangeKind)> = changes.iter().map(|change| (change.key().ids().as_str(), *change.kind())).collect();
+        assert_eq!(kinds, vec![
+            ("1", ResultChangeKind::Changed),
+            ("2", ResultChangeKind::Changed),
+            ("3, 4", ResultChangeKind::Changed),
+            ("5", ResultChangeKind::Removed),
+            ("6", ResultChangeKind::Added),
+        ]);
+        assert_eq!(changes[0].new().as_ref().unwrap().details(), "False start");
+    }
+
+    #[test]
+    fn should_compare_lines_sharing_a_key() {
+        let old = vec![
+            create_raw_result("1", "1", "00:14.990", ""),
+            create_raw_result("1", "2", "00:15.990", ""),
+            create_raw_result("2", "3", "00:16.990", ""),
+            create_raw_result("2", "4", "00:17.990", ""),
+        ];
+        let new = vec![
+            create_raw_result("1", "2", "00:15.990", ""),
+            create_raw_result("1", "1", "00:14.890", ""),
+            create_raw_result("2", "3", "00:16.990", ""),
+        ];
+
+        let changes = diff_raw_results(&old, &new);
+
+        let kinds: Vec<(&str, ResultChangeKind)> = changes.iter().map(|change| (change.key().ids().as_str(), *change.kind())).collect();
+        assert_eq!(kinds, vec![
+            ("1", ResultChangeKind::Changed),
+            ("2", ResultChangeKind::Removed),
+        ]);
+        assert_eq!(changes[0].old().as_ref().unwrap().result(), "00:14.990");
+        assert_eq!(changes[0].new().as_ref().unwrap().result(), "00:14.890");
+        assert_eq!(changes[1].old().as_ref().unwrap().place(), "4");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::fs;
+
+use chrono::{DateTime, Utc};
+use derive_getters::Getters;
+use log::{debug, info, warn};
+
+use crate::utils::create_folder;
+
+const SNAPSHOTS_FOLDER: &str = "snapshots";
+const SNAPSHOT_PREFIX: &str = "results_";
+const SNAPSHOT_EXTENSION: &str = ".xls";
+/// Colons aren't allowed in filenames on every system.
+const SNAPSHOT_NAME_FORMAT: &str = "%Y-%m-%dT%H-%M-%SZ";
+
+/// The results file of a convention as it was downloaded at some point, kept in `{folder}/{tag}/snapshots/results_{name}.xls`.
+/// Its name is its download time, so that snapshots sort chronologically.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Getters)]
+pub struct ResultsSnapshot {
+    name: String,
+    filepath: String,
+}
+
+/// Keep a copy of `{folder}/{tag}/results.xls` as a snapshot named after its download time,
+/// unless it is the same as the latest snapshot.
+pub fn save_results_snapshot(folder: &str, convention_tag: &str, downloaded_at: DateTime<Utc>) -> Result<Option<ResultsSnapshot>, ()> {
+    let results_filepath = format!("{folder}/{convention_tag}/results.xls");
+    let content = fs::read(&results_filepath).map_err(|error| {
+        warn!("Can't read results to snapshot [filepath: {results_filepath}]: {error}");
+    })?;
+    let latest_snapshot = list_results_snapshots(folder, convention_tag).pop();
+    if let Some(latest_snapshot) = latest_snapshot {
+        if fs::read(&latest_snapshot.filepath).is_ok_and(|latest_content| latest_content == content) {
+            debug!("Results unchanged since latest snapshot [convention: {convention_tag}, snapshot: {}]", latest_snapshot.name);
+            return Ok(None);
+        }
+    }
+
+    let snapshots_folder = format!("{folder}/{convention_tag}/{SNAPSHOTS_FOLDER}");
+    create_folder(&snapshots_folder, &format!("Can't create snapshots folder [folder: {snapshots_folder}]"))?;
+    let name = downloaded_at.format(SNAPSHOT_NAME_FORMAT).to_string();
+    let filepath = format!("{snapshots_folder}/{SNAPSHOT_PREFIX}{name}{SNAPSHOT_EXTENSION}");
+    fs::write(&filepath, content).map_err(|error| {
+        warn!("Can't write results snapshot [filepath: {filepath}]: {error}");
+    })?;
+
+    info!("Results snapshot saved [convention: {convention_tag}, snapshot: {name}]");
+    Ok(Some(ResultsSnapshot { name, filepath }))
+}
+
+/// Keep a copy of `{folder}/{tag}/results.xls`, if it exists, as a snapshot named after its last modification time,
+/// so that it isn't lost when it is downloaded again.
+pub fn save_existing_results_snapshot(folder: &str, convention_tag: &str) -> Result<Option<ResultsSnapshot>, ()> {
+    let results_filepath = format!("{folder}/{convention_tag}/results.xls");
+    let Ok(modified_at) = fs::metadata(&results_filepath).and_then(|metadata| metadata.modified()) else {
+        debug!("No results to snapshot [filepath: {results_filepath}]");
+        return Ok(None);
+    };
+    save_results_snapshot(folder, convention_tag, modified_at.into())
+}
+
+/// The snapshots of the results of a convention, from the oldest to the latest.
+pub fn list_results_snapshots(folder: &str, convention_tag: &str) -> Vec<ResultsSnapshot> {
+    let snapshots_folder = format!("{folder}/{convention_tag}/{SNAPSHOTS_FOLDER}");
+    let Ok(entries) = fs::read_dir(&snapshots_folder) else { return vec![]; };
+
+    let mut snapshots: Vec<ResultsSnapshot> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let filename = entry.file_name().into_string().ok()?;
+            let name = filename.strip_prefix(SNAPSHOT_PREFIX)?.strip_suffix(SNAPSHOT_EXTENSION)?.to_string();
+            Some(ResultsSnapshot { name, filepath: format!("{snapshots_folder}/{filename}") })
+        })
+        .collect();
+    snapshots.sort();
+    snapshots
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::raw_result::results_snapshot::{list_results_snapshots, save_existing_results_snapshot, save_results_snapshot};
+
+    #[test]
+    fn should_only_save_changed_results() {
+        let folder = std::env::temp_dir().join(format!("snapshots_{}", Uuid::new_v4())).to_string_lossy().to_string();
+        fs::create_dir_all(format!("{folder}/cfm2023")).unwrap();
+        let results_filepath = format!("{folder}/cfm2023/results.xls");
+
+        fs::write(&results_filepath, "first download").unwrap();
+        let first = save_results_snapshot(&folder, "cfm2023", Utc.with_ymd_and_hms(2023, 5, 21, 18, 0, 0).unwrap()).unwrap();
+        let unchanged = save_results_snapshot(&folder, "cfm2023", Utc.with_ymd_and_hms(2023, 5, 22, 18, 0, 0).unwrap()).unwrap();
+        fs::write(&results_filepath, "corrected").unwrap();
+        let corrected = save_results_snapshot(&folder, "cfm2023", Utc.with_ymd_and_hms(2023, 5, 30, 9, 30, 0).unwrap()).unwrap();
+
+        assert_eq!(first.unwrap().name(), "2023-05-21T18-00-00Z");
+        assert_eq!(unchanged, None);
+        assert!(corrected.is_some());
+        let names: Vec<String> = list_results_snapshots(&folder, "cfm2023").iter().map(|snapshot| snapshot.name().clone()).collect();
+        assert_eq!(names, vec!["2023-05-21T18-00-00Z", "2023-05-30T09-30-00Z"]);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn should_snapshot_existing_results_before_download() {
+        let folder = std::env::temp_dir().join(format!("snapshots_{}", Uuid::new_v4())).to_string_lossy().to_string();
+        fs::create_dir_all(format!("{folder}/cfm2023")).unwrap();
+
+        let missing = save_existing_results_snapshot(&folder, "cfm2023").unwrap();
+        fs::write(format!("{folder}/cfm2023/results.xls"), "cached download").unwrap();
+        let existing = save_existing_results_snapshot(&folder, "cfm2023").unwrap();
+
+        assert_eq!(missing, None);
+        assert!(existing.is_some());
+        assert_eq!(fs::read_to_string(existing.unwrap().filepath()).unwrap(), "cached download");
+        assert_eq!(list_results_snapshots(&folder, "cfm2023").len(), 1);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
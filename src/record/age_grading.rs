@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+use serde::Deserialize;
+
+use crate::competition::discipline::Discipline;
+use crate::competition::performance::Performance;
+use crate::competitor::competitor::Competitor;
+use crate::competitor::gender::Gender;
+use crate::result::result_direction::ResultDirections;
+use crate::result::result_value::ResultValue;
+
+const AGE_GRADING_FACTORS_FILE: &str = "age_grading_factors.json";
+
+/// A line of the factor table, as written in the file.
+#[derive(Debug, Deserialize)]
+struct RawAgeGradingFactors {
+    discipline: String,
+    #[serde(default)]
+    gender: Option<String>,
+    open_standard: String,
+    factors: BTreeMap<u8, f64>,
+}
+
+/// The best performance of all ages in a discipline, and the factors that make up for age.
+#[derive(Debug, Clone, PartialEq)]
+struct AgeGradingFactors {
+    open_standard: ResultValue,
+    factors: BTreeMap<u8, f64>,
+}
+
+impl AgeGradingFactors {
+    /// Factors of ages in between two ages of the table are interpolated, the ones of ages out of the table are the closest known.
+    fn factor_at(&self, age: u8) -> Option<f64> {
+        let lower = self.factors.range(..=age).next_back();
+        let upper = self.factors.range(age..).next();
+        match (lower, upper) {
+            (None, None) => { None }
+            (Some((_, factor)), None) | (None, Some((_, factor))) => { Some(*factor) }
+            (Some((lower_age, lower_factor)), Some((upper_age, upper_factor))) => {
+                if lower_age == upper_age {
+                    return Some(*lower_factor);
+                }
+                let ratio = (age - lower_age) as f64 / (upper_age - lower_age) as f64;
+                Some(lower_factor + (upper_factor - lower_factor) * ratio)
+            }
+        }
+    }
+}
+
+/// Age-grading converts a performance into a percentage of the best performance possible at the age of the competitor,
+/// so that competitors of all ages can be ranked together.
+/// Factors are given per discipline, per gender and per age. Factors without gender apply to every gender.
+#[derive(Debug, Default)]
+pub struct AgeGradingTable {
+    factors: HashMap<(Discipline, Option<Gender>), AgeGradingFactors>,
+}
+
+impl AgeGradingTable {
+    /// Load the factor table from `{folder}/age_grading_factors.json`. If there is none, no result can be age-graded.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{AGE_GRADING_FACTORS_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No age-grading factors [filepath: {filepath}]");
+                return AgeGradingTable::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        match serde_json::from_reader::<_, Vec<RawAgeGradingFactors>>(reader) {
+            Ok(raw_factors) => {
+                let table = Self::from_raw_factors(raw_factors);
+                info!("Age-grading factors loaded [disciplines: {}]", table.factors.len());
+                table
+            }
+            Err(error) => {
+                warn!("Can't read age-grading factors, results won't be age-graded [filepath: {filepath}]");
+                warn!("{}", error);
+                AgeGradingTable::default()
+            }
+        }
+    }
+
+    fn from_raw_factors(raw_factors: Vec<RawAgeGradingFactors>) -> Self {
+        let mut factors = HashMap::new();
+        for raw_factors in raw_factors {
+            let gender = match raw_factors.gender.as_deref().map(Gender::from_string).transpose() {
+                Ok(gender) => { gender }
+                Err(error) => {
+                    warn!("Can't read age-grading factors gender, they will be ignored [discipline: {}]: {error}", raw_factors.discipline);
+                    continue;
+                }
+            };
+            let open_standard = ResultValue::from_string(&raw_factors.open_standard);
+            if open_standard.as_number().filter(|standard| *standard > 0.0).is_none() {
+                warn!("Invalid age-grading open standard, factors will be ignored [discipline: {}, open_standard: {}]", raw_factors.discipline, raw_factors.open_standard);
+                continue;
+            }
+            if raw_factors.factors.values().any(|factor| *factor <= 0.0) {
+                warn!("Age-grading factors should be positive, they will be ignored [discipline: {}]", raw_factors.discipline);
+                continue;
+            }
+
+            let discipline = Discipline::from_string(&raw_factors.discipline);
+            factors.insert((discipline, gender), AgeGradingFactors { open_standard, factors: raw_factors.factors });
+        }
+
+        Self { factors }
+    }
+
+    /// Age-grade a performance, as a percentage of the open standard: 100% is the best performance possible at that age.
+    /// Only individual performances with a known age and a number as value can be age-graded.
+    pub fn grade(&self, performance: &Performance, result_directions: &ResultDirections) -> Option<f64> {
+        let Competitor::IndividualCompetitor(competitor) = performance.competitor() else { return None; };
+        let age = (*competitor.age())?;
+        let value = performance.result().as_ref()?;
+        let number = value.as_number().filter(|number| *number > 0.0)?;
+
+        let discipline = Discipline::from_competition(performance.competition());
+        let gender = competitor.gender().clone();
+        let factors = self.factors.get(&(discipline.clone(), gender))
+            .or_else(|| self.factors.get(&(discipline, None)))?;
+        if std::mem::discriminant(value) != std::mem::discriminant(&factors.open_standard) {
+            return None;
+        }
+        let factor = factors.factor_at(age)?;
+        let open_standard = factors.open_standard.as_number()?;
+
+        // Factors lower the performance expected as age goes by: a time is divided by the factor, a distance is multiplied by it
+        match result_directions.higher_is_better(performance.competition(), value)? {
+            true => { Some(100.0 * number / (open_standard * factor)) }
+            false => { Some(100.0 * open_standard / (number * factor)) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::performance::Performance;
+    use crate::record::age_grading::{AgeGradingTable, RawAgeGradingFactors};
+    use crate::result::result_direction::ResultDirections;
+
+    fn create_table() -> AgeGradingTable {
+        AgeGradingTable::from_raw_factors(vec![
+            RawAgeGradingFactors {
+                discipline: "100m".to_string(),
+                gender: Some("Male".to_string()),
+                open_standard: "00:12.000".to_string(),
+                factors: BTreeMap::from([(30, 1.0), (50, 0.8)]),
+            },
+            RawAgeGradingFactors {
+                discipline: "High Jump".to_string(),
+                gender: None,
+                open_standard: "100 cm".to_string(),
+                factors: BTreeMap::from([(30, 1.0), (60, 0.5)]),
+            },
+        ])
+    }
+
+    fn create_performance(competition: &str, value: &str, gender: &str, age: u8) -> Performance {
+        Performance::from_result(&CompetitionResult::create_test_instance(1, competition, "1", value).with_individual_competitor("", Some(gender), Some(age)))
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!((actual - expected).abs() < 0.001, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn should_grade_time() {
+        let table = create_table();
+
+        assert_close(table.grade(&create_performance("100m Male", "00:12.000", "Male", 30), &ResultDirections::default()), 100.0);
+        assert_close(table.grade(&create_performance("100m Male", "00:15.000", "Male", 50), &ResultDirections::default()), 100.0);
+        assert_close(table.grade(&create_performance("100m Male", "00:24.000", "Male", 20), &ResultDirections::default()), 50.0);
+    }
+
+    #[test]
+    fn should_interpolate_factors() {
+        let table = create_table();
+
+        assert_close(table.grade(&create_performance("100m", "00:12.000", "Male", 40), &ResultDirections::default()), 100.0 / 0.9);
+    }
+
+    #[test]
+    fn should_grade_distance_for_any_gender() {
+        let table = create_table();
+
+        assert_close(table.grade(&create_performance("High Jump", "50 cm", "Female", 60), &ResultDirections::default()), 100.0);
+    }
+
+    #[test]
+    fn should_not_grade_unknown_discipline_or_gender() {
+        let table = create_table();
+
+        assert_eq!(table.grade(&create_performance("200m", "00:30.000", "Male", 30), &ResultDirections::default()), None);
+        assert_eq!(table.grade(&create_performance("100m", "00:12.000", "Female", 30), &ResultDirections::default()), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use crate::competition::discipline::Discipline;
+use crate::competitor::competitor::Competitor;
+use crate::export::{write_csv_file, write_json_file, write_markdown_file};
+use crate::person::person::Person;
+use crate::record::age_grading::AgeGradingTable;
+use crate::record::personal_record::{Mark, personal_records};
+use crate::result::result_direction::ResultDirections;
+
+const LEADERBOARDS_FILENAME: &str = "leaderboards";
+const AGE_GRADED_LEADERBOARDS_FILENAME: &str = "age_graded_leaderboards";
+
+/// The best mark of a person in a [Leaderboard], along with its age-graded score if it has been age-graded.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct LeaderboardEntry<'p, 'a> {
+    rank: usize,
+    person: &'p Person<'a>,
+    mark: Mark<'a>,
+    age_graded_score: Option<f64>,
+}
+
+/// People ranked by their best mark in a discipline. People with equal marks share the same rank.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Leaderboard<'p, 'a> {
+    discipline: Discipline,
+    entries: Vec<LeaderboardEntry<'p, 'a>>,
+}
+
+/// Rank people by their personal record in every discipline.
+pub fn compute_leaderboards<'p, 'a>(people: &'p [Person<'a>], result_directions: &ResultDirections) -> Vec<Leaderboard<'p, 'a>> {
+    let mut marks_by_discipline: HashMap<Discipline, Vec<(&Person, Mark)>> = HashMap::new();
+    for person in people {
+        for record in personal_records(person, result_directions) {
+            marks_by_discipline.entry(record.discipline().clone())
+                .or_default()
+                .push((person, record.best().clone()));
+        }
+    }
+
+    create_leaderboards(marks_by_discipline, |marks| {
+        marks.sort_by(|(a_person, a), (b_person, b)| {
+            compare_marks(a, b, result_directions)
+                .then_with(|| a_person.identity().person_name().name().cmp(b_person.identity().person_name().name()))
+        });
+        let mut entries: Vec<LeaderboardEntry> = vec![];
+        for (person, mark) in marks.drain(..) {
+            let rank = match entries.last() {
+                Some(previous) if compare_marks(&previous.mark, &mark, result_directions) == Ordering::Equal => { previous.rank }
+                _ => { entries.len() + 1 }
+            };
+            entries.push(LeaderboardEntry { rank, person, mark, age_graded_score: None });
+        }
+        entries
+    })
+}
+
+/// Rank people by their best age-graded mark in every discipline the factor table covers.
+/// When several marks of a person have the same score, the earliest one is kept.
+pub fn compute_age_graded_leaderboards<'p, 'a>(people: &'p [Person<'a>], age_grading_table: &AgeGradingTable, result_directions: &ResultDirections) -> Vec<Leaderboard<'p, 'a>> {
+    let mut marks_by_discipline: HashMap<Discipline, Vec<(&Person, Mark, f64)>> = HashMap::new();
+    for person in people {
+        let mut best_marks: HashMap<Discipline, (Mark, f64)> = HashMap::new();
+        for (convention, performances) in person.performances() {
+            for performance in performances {
+                let Some(score) = age_grading_table.grade(performance, result_directions) else { continue; };
+                let mark = Mark::new(convention, performance.clone());
+                let discipline = Discipline::from_competition(performance.competition());
+                let is_best = match best_marks.get(&discipline) {
+                    None => { true }
+                    Some((best_mark, best_score)) => {
+                        score > *best_score || (score == *best_score && mark.convention().compare_chronologically(best_mark.convention()) == Ordering::Less)
+                    }
+                };
+                if is_best {
+                    best_marks.insert(discipline, (mark, score));
+                }
+            }
+        }
+        for (discipline, (mark, score)) in best_marks {
+            marks_by_discipline.entry(discipline).or_default().push((person, mark, score));
+        }
+    }
+
+    create_leaderboards(marks_by_discipline, |marks| {
+        marks.sort_by(|(a_person, _, a_score), (b_person, _, b_score)| {
+            b_score.total_cmp(a_score)
+                .then_with(|| a_person.identity().person_name().name().cmp(b_person.identity().person_name().name()))
+        });
+        let mut entries: Vec<LeaderboardEntry> = vec![];
+        for (person, mark, score) in marks.drain(..) {
+            let rank = match entries.last() {
+                Some(previous) if previous.age_graded_score == Some(score) => { previous.rank }
+                _ => { entries.len() + 1 }
+            };
+            entries.push(LeaderboardEntry { rank, person, mark, age_graded_score: Some(score) });
+        }
+        entries
+    })
+}
+
+fn create_leaderboards<'p, 'a, T>(
+    marks_by_discipline: HashMap<Discipline, Vec<T>>,
+    rank: impl Fn(&mut Vec<T>) -> Vec<LeaderboardEntry<'p, 'a>>,
+) -> Vec<Leaderboard<'p, 'a>> {
+    let mut leaderboards: Vec<Leaderboard> = marks_by_discipline.into_iter()
+        .map(|(discipline, mut marks)| Leaderboard { discipline, entries: rank(&mut marks) })
+        .collect();
+    leaderboards.sort_by(|a, b| a.discipline.cmp(&b.discipline));
+
+    leaderboards
+}
+
+/// Better marks first. Marks that can't be compared are considered equal.
+fn compare_marks(a: &Mark, b: &Mark, result_directions: &ResultDirections) -> Ordering {
+    let a_value = a.performance().result().as_ref().unwrap();
+    let b_value = b.performance().result().as_ref().unwrap();
+    match (a_value.compare(b_value), result_directions.higher_is_better(a.performance().competition(), a_value)) {
+        (Ok(ordering), Some(true)) => { ordering.reverse() }
+        (Ok(ordering), Some(false)) => { ordering }
+        _ => { Ordering::Equal }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedLeaderboardEntry<'a> {
+    discipline: &'a str,
+    rank: usize,
+    id: String,
+    name: &'a str,
+    age: String,
+    result: String,
+    age_graded_score: String,
+    convention: &'a str,
+    competition: &'a str,
+}
+
+/// Export leaderboards as JSON, CSV and Markdown in `{folder}`.
+/// Raw leaderboards are written to `leaderboards.{json,csv,md}` and age-graded ones to `age_graded_leaderboards.{json,csv,md}`.
+pub fn export_leaderboards(folder: &str, leaderboards: &[Leaderboard], age_graded: bool) -> Result<(), ()> {
+    let (filename, title) = match age_graded {
+        true => { (AGE_GRADED_LEADERBOARDS_FILENAME, "Age-graded leaderboards") }
+        false => { (LEADERBOARDS_FILENAME, "Leaderboards") }
+    };
+    let exported_entries: Vec<ExportedLeaderboardEntry> = leaderboards.iter()
+        .flat_map(|leaderboard| leaderboard.entries.iter().map(|entry| {
+            let age = match entry.mark.performance().competitor() {
+                Competitor::IndividualCompetitor(competitor) => { competitor.age().map(|age| age.to_string()).unwrap_or_default() }
+                _ => { String::new() }
+            };
+            ExportedLeaderboardEntry {
+                discipline: leaderboard.discipline.name(),
+                rank: entry.rank,
+                id: entry.person.id().to_string(),
+                name: entry.person.identity().person_name().name(),
+                age,
+                result: entry.mark.performance().result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                age_graded_score: entry.age_graded_score.map(|score| format!("{score:.2}%")).unwrap_or_default(),
+                convention: entry.mark.convention().tag(),
+                competition: entry.mark.performance().competition().name(),
+            }
+        }))
+        .collect();
+
+    let json_result = write_json_file(folder, &format!("{filename}.json"), &exported_entries);
+    let csv_result = write_csv_file(folder, &format!("{filename}.csv"), &exported_entries);
+    let markdown_result = write_markdown_file(folder, &format!("{filename}.md"), title, &exported_entries);
+
+    json_result.and(csv_result).and(markdown_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::leaderboard::{compute_leaderboards, Leaderboard};
+    use crate::result::result_direction::ResultDirections;
+
+    fn get_ranking(leaderboard: &Leaderboard) -> Vec<(usize, String)> {
+        leaderboard.entries().iter()
+            .map(|entry| (*entry.rank(), entry.person().identity().person_name().name().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn should_rank_people_with_ties() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let people = vec![
+            Person::create_test_instance("Jack Doe").with_birthday(1990, 1, 1)
+                .with_results(&convention, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:15.000").with_individual_competitor("Jack Doe", Some("Male"), Some(30))]),
+            Person::create_test_instance("John Doe").with_birthday(1990, 1, 1)
+                .with_results(&convention, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:14.000").with_individual_competitor("John Doe", Some("Male"), Some(30))]),
+            Person::create_test_instance("Jane Doe").with_birthday(1990, 1, 1)
+                .with_results(&convention, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:14.000").with_individual_competitor("Jane Doe", Some("Male"), Some(30))]),
+        ];
+
+        let leaderboards = compute_leaderboards(&people, &ResultDirections::default());
+
+        assert_eq!(leaderboards.len(), 1);
+        assert_eq!(get_ranking(&leaderboards[0]), vec![(1, "Jane Doe".to_string()), (1, "John Doe".to_string()), (3, "Jack Doe".to_string())]);
+    }
+}
@@ -0,0 +1,235 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use crate::competition::performance::Performance;
+use crate::convention::convention::Convention;
+use crate::country::country::Country;
+use crate::export::{write_csv_file, write_markdown_file};
+use crate::person::person::Person;
+use crate::record::record_table::RecordScope;
+use crate::result::place::Place;
+use crate::result::result_type::ResultType;
+
+const PEOPLE_MEDALS_FILENAME: &str = "medals_people";
+const COUNTRIES_MEDALS_FILENAME: &str = "medals_countries";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Getters)]
+pub struct MedalCount {
+    gold: u16,
+    silver: u16,
+    bronze: u16,
+}
+
+impl MedalCount {
+    pub fn total(&self) -> u16 {
+        self.gold + self.silver + self.bronze
+    }
+
+    fn add(&mut self, rank: u16) {
+        match rank {
+            1 => { self.gold += 1 }
+            2 => { self.silver += 1 }
+            3 => { self.bronze += 1 }
+            _ => {}
+        }
+    }
+
+    /// More gold medals first, then more silver medals, then more bronze medals.
+    fn compare(&self, other: &Self) -> Ordering {
+        other.gold.cmp(&self.gold)
+            .then_with(|| other.silver.cmp(&self.silver))
+            .then_with(|| other.bronze.cmp(&self.bronze))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MedalHolder<'p, 'a> {
+    Person(&'p Person<'a>),
+    Country(Country),
+}
+
+impl<'p, 'a> MedalHolder<'p, 'a> {
+    fn name(&self) -> String {
+        match self {
+            MedalHolder::Person(person) => { person.identity().person_name().name().to_string() }
+            MedalHolder::Country(country) => { country.code().to_string() }
+        }
+    }
+}
+
+/// The medals a person or a country won for a [ResultType].
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct MedalTableRow<'p, 'a> {
+    holder: MedalHolder<'p, 'a>,
+    result_type: ResultType,
+    medals: MedalCount,
+}
+
+/// Medals won per person and per country over the conventions of the scope.
+/// Rows are sorted by result type, and then the usual way: by gold, silver and bronze medals.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct MedalTable<'p, 'a> {
+    scope: RecordScope<'a>,
+    people: Vec<MedalTableRow<'p, 'a>>,
+    countries: Vec<MedalTableRow<'p, 'a>>,
+}
+
+/// Compute the medal table of the scope from the first three overall and age group places of every performance.
+/// A team medal is counted for each of its members in the people table, but only once for their country.
+pub fn compute_medal_table<'p, 'a>(people: &'p [Person<'a>], scope: RecordScope<'a>) -> MedalTable<'p, 'a> {
+    let mut people_medals: HashMap<(usize, ResultType), MedalCount> = HashMap::new();
+    let mut countries_medals: HashMap<(Country, ResultType), MedalCount> = HashMap::new();
+    let mut counted_country_medals = HashSet::new();
+
+    for (index, person) in people.iter().enumerate() {
+        for (convention, performances) in person.performances() {
+            if let RecordScope::Convention(scope_convention) = scope {
+                if scope_convention != *convention {
+                    continue;
+                }
+            }
+            for performance in performances {
+                for result_type in [ResultType::Overall, ResultType::AgeGroup] {
+                    let Some(Place::Rank(rank @ 1..=3)) = performance.place(result_type) else { continue; };
+
+                    people_medals.entry((index, result_type)).or_default().add(*rank);
+                    if let Some(country) = person.country_at(convention) {
+                        if counted_country_medals.insert((country.clone(), get_medal_key(convention, performance, result_type))) {
+                            countries_medals.entry((country.clone(), result_type)).or_default().add(*rank);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let people_rows = people_medals.into_iter()
+        .map(|((index, result_type), medals)| MedalTableRow { holder: MedalHolder::Person(&people[index]), result_type, medals })
+        .collect();
+    let countries_rows = countries_medals.into_iter()
+        .map(|((country, result_type), medals)| MedalTableRow { holder: MedalHolder::Country(country), result_type, medals })
+        .collect();
+
+    MedalTable { scope, people: sort_rows(people_rows), countries: sort_rows(countries_rows) }
+}
+
+/// Identifies a medal, so that a team medal is the same for every member of the team.
+fn get_medal_key(convention: &Convention, performance: &Performance, result_type: ResultType) -> (String, String, ResultType, String, Vec<u16>) {
+    (
+        convention.tag().clone(),
+        performance.competition().name().clone(),
+        result_type,
+        performance.age_group().as_ref().map(|age_group| age_group.to_string()).unwrap_or_default(),
+        performance.ids(),
+    )
+}
+
+fn sort_rows<'p, 'a>(mut rows: Vec<MedalTableRow<'p, 'a>>) -> Vec<MedalTableRow<'p, 'a>> {
+    rows.sort_by(|a, b| {
+        // Overall medals first, then age group medals
+        a.result_type.cmp(&b.result_type)
+            .then_with(|| a.medals.compare(&b.medals))
+            .then_with(|| a.holder.name().cmp(&b.holder.name()))
+    });
+    rows
+}
+
+#[derive(Serialize)]
+struct ExportedMedalTableRow {
+    id: String,
+    name: String,
+    result_type: String,
+    gold: u16,
+    silver: u16,
+    bronze: u16,
+    total: u16,
+}
+
+impl ExportedMedalTableRow {
+    fn from_row(row: &MedalTableRow) -> Self {
+        let id = match &row.holder {
+            MedalHolder::Person(person) => { person.id().to_string() }
+            MedalHolder::Country(country) => { country.code().to_string() }
+        };
+        Self {
+            id,
+            name: row.holder.name(),
+            result_type: row.result_type.to_string(),
+            gold: row.medals.gold,
+            silver: row.medals.silver,
+            bronze: row.medals.bronze,
+            total: row.medals.total(),
+        }
+    }
+}
+
+/// Export the medal table as CSV and Markdown in `{folder}`.
+/// All-time medals are written to `medals_people.{csv,md}` and `medals_countries.{csv,md}`,
+/// and convention medals to `medals_people_{tag}.{csv,md}` and `medals_countries_{tag}.{csv,md}`.
+pub fn export_medal_table(folder: &str, medal_table: &MedalTable) -> Result<(), ()> {
+    let (suffix, scope_title) = match medal_table.scope {
+        RecordScope::AllTime => { (String::new(), "all time".to_string()) }
+        RecordScope::Convention(convention) => { (format!("_{}", convention.tag()), convention.name().clone()) }
+    };
+    let tables = [
+        (PEOPLE_MEDALS_FILENAME, "Medals per person", &medal_table.people),
+        (COUNTRIES_MEDALS_FILENAME, "Medals per country", &medal_table.countries),
+    ];
+
+    let mut export_result = Ok(());
+    for (filename, title, rows) in tables {
+        let exported_rows: Vec<ExportedMedalTableRow> = rows.iter().map(ExportedMedalTableRow::from_row).collect();
+        let csv_result = write_csv_file(folder, &format!("{filename}{suffix}.csv"), &exported_rows);
+        let markdown_result = write_markdown_file(folder, &format!("{filename}{suffix}.md"), &format!("{title} ({scope_title})"), &exported_rows);
+        export_result = export_result.and(csv_result).and(markdown_result);
+    }
+
+    export_result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competitor::competitor::Competitor;
+    use crate::competitor::team::Team;
+    use crate::competitor::unknown_individual_competitor::UnknownIndividualCompetitor;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::medal_table::{compute_medal_table, MedalHolder};
+    use crate::record::record_table::RecordScope;
+    use crate::result::result_type::ResultType;
+
+    #[test]
+    fn should_count_team_medals_once_per_country() {
+        let convention = Convention::create_test_instance("cfm2022");
+        let team = Competitor::Team(Team::new("Team Doe".to_string(), None, vec![
+            Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(1)),
+            Competitor::UnknownIndividualCompetitor(UnknownIndividualCompetitor::new(2)),
+        ]));
+        let relay = CompetitionResult::create_test_instance(1, "Relay", "1", "00:14.990").with_competitor(team);
+        let people = vec![
+            Person::create_test_instance("John Doe").with_country(&convention, "France").with_results(&convention, &[
+                relay.clone(),
+                CompetitionResult::create_test_instance(1, "100m", "2", "00:14.990"),
+                CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990").with_result_type(ResultType::AgeGroup),
+                CompetitionResult::create_test_instance(1, "200m", "4", "00:14.990"),
+            ]),
+            Person::create_test_instance("Jane Doe").with_country(&convention, "France").with_results(&convention, &[relay]),
+        ];
+
+        let medal_table = compute_medal_table(&people, RecordScope::AllTime);
+
+        let john_overall = medal_table.people().iter()
+            .find(|row| matches!(row.holder(), MedalHolder::Person(person) if person.identity().person_name().name() == "John Doe") && row.result_type() == &ResultType::Overall)
+            .unwrap();
+        assert_eq!((*john_overall.medals().gold(), *john_overall.medals().silver()), (1, 1));
+        assert_eq!(medal_table.people().len(), 3);
+        let france_overall = &medal_table.countries()[0];
+        assert_eq!(france_overall.result_type(), &ResultType::Overall);
+        assert_eq!(france_overall.medals().total(), 2);
+        assert_eq!(medal_table.countries()[1].medals().total(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod personal_record;
+pub mod record_table;
+pub mod age_grading;
+pub mod leaderboard;
+pub mod medal_table;
+pub mod rating;
+pub mod season_ranking;
\ No newline at end of file
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use derive_getters::Getters;
+use log::warn;
+use serde::Serialize;
+
+use crate::competition::discipline::Discipline;
+use crate::competition::performance::Performance;
+use crate::convention::convention::Convention;
+use crate::export::privacy::BirthdayPrecision;
+use crate::export::write_json_file;
+use crate::person::person::Person;
+use crate::person::person_name::PersonName;
+use crate::result::result_direction::ResultDirections;
+
+const PERSONAL_RECORDS_FILE: &str = "personal_records.json";
+
+/// A [Mark] is a ranked performance a person got at a convention.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Mark<'a> {
+    convention: &'a Convention,
+    performance: Performance,
+}
+
+impl<'a> Mark<'a> {
+    pub fn new(convention: &'a Convention, performance: Performance) -> Self {
+        Self { convention, performance }
+    }
+}
+
+/// The best [Mark] of a person in a [Discipline], along with every mark they got in this discipline.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct PersonalRecord<'a> {
+    discipline: Discipline,
+    best: Mark<'a>,
+    progression: Vec<Mark<'a>>,
+}
+
+impl<'a> PersonalRecord<'a> {
+    pub fn new(discipline: Discipline, best: Mark<'a>, progression: Vec<Mark<'a>>) -> Self {
+        Self { discipline, best, progression }
+    }
+}
+
+/// Compute the personal record of a person for every discipline they have a comparable result in.
+/// Records are sorted by discipline, and each progression is sorted chronologically.
+pub fn personal_records<'a>(person: &Person<'a>, result_directions: &ResultDirections) -> Vec<PersonalRecord<'a>> {
+    let mut marks_by_discipline: HashMap<Discipline, Vec<Mark<'a>>> = HashMap::new();
+    for (convention, performances) in person.performances() {
+        for performance in performances {
+            let is_comparable = performance.result().as_ref()
+                .and_then(|value| result_directions.higher_is_better(performance.competition(), value))
+                .is_some();
+            if !is_comparable {
+                continue;
+            }
+
+            marks_by_discipline.entry(Discipline::from_competition(performance.competition()))
+                .or_default()
+                .push(Mark::new(convention, performance.clone()));
+        }
+    }
+
+    let mut records: Vec<PersonalRecord> = marks_by_discipline.into_iter()
+        .filter_map(|(discipline, mut marks)| {
+            marks.sort_by(|a, b| compare_chronologically(a, b));
+            let best = find_best_mark(&marks, result_directions)?.clone();
+            Some(PersonalRecord::new(discipline, best, marks))
+        })
+        .collect();
+    records.sort_by(|a, b| a.discipline.cmp(&b.discipline));
+
+    records
+}
+
+fn compare_chronologically(a: &Mark, b: &Mark) -> std::cmp::Ordering {
+    a.convention.compare_chronologically(b.convention)
+}
+
+/// Find the best mark. When several marks are equal, the earliest one is kept.
+fn find_best_mark<'m, 'a>(marks: &'m [Mark<'a>], result_directions: &ResultDirections) -> Option<&'m Mark<'a>> {
+    let mut best_mark: Option<&Mark> = None;
+    for mark in marks {
+        let Some(best_so_far) = best_mark else {
+            best_mark = Some(mark);
+            continue;
+        };
+
+        let value = mark.performance.result().as_ref().unwrap();
+        let best_value = best_so_far.performance.result().as_ref().unwrap();
+        match result_directions.is_better_than(mark.performance.competition(), value, best_value) {
+            Ok(true) => { best_mark = Some(mark); }
+            Ok(false) => {}
+            Err(error) => {
+                warn!("Can't compare marks [best_so_far: {:?}, mark: {:?}]", best_so_far, mark);
+                warn!("{error}");
+            }
+        }
+    }
+
+    best_mark
+}
+
+#[derive(Serialize)]
+struct ExportedMark<'a> {
+    convention: &'a str,
+    convention_name: &'a str,
+    competition: &'a str,
+    overall_place: String,
+    age_group_place: String,
+    result: String,
+}
+
+impl<'a> ExportedMark<'a> {
+    fn from_mark(mark: &'a Mark) -> Self {
+        Self {
+            convention: mark.convention.tag(),
+            convention_name: mark.convention.name(),
+            competition: mark.performance.competition().name(),
+            overall_place: mark.performance.overall_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+            age_group_place: mark.performance.age_group_place().as_ref().map(|place| place.to_string()).unwrap_or_default(),
+            result: mark.performance.result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedPersonalRecord<'a> {
+    discipline: &'a str,
+    best: ExportedMark<'a>,
+    progression: Vec<ExportedMark<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedPersonalRecords<'a> {
+    id: String,
+    name: &'a str,
+    #[serde(flatten)]
+    name_parts: &'a PersonName,
+    birthday: String,
+    records: Vec<ExportedPersonalRecord<'a>>,
+}
+
+/// Export the personal records of every person as JSON in `{folder}/personal_records.json`,
+/// with their birthday written with the given precision.
+pub fn export_personal_records(folder: &str, people: &[Person], birthday_precision: BirthdayPrecision, result_directions: &ResultDirections) -> Result<(), ()> {
+    let records: Vec<(&Person, Vec<PersonalRecord>)> = people.iter()
+        .map(|person| (person, personal_records(person, result_directions)))
+        .filter(|(_, records)| !records.is_empty())
+        .collect();
+
+    let mut exported_records: Vec<ExportedPersonalRecords> = records.iter()
+        .map(|(person, records)| ExportedPersonalRecords {
+            id: person.id().to_string(),
+            name: person.identity().person_name().name(),
+            name_parts: person.identity().person_name(),
+            birthday: birthday_precision.format(person.identity().birthday()),
+            records: records.iter()
+                .map(|record| ExportedPersonalRecord {
+                    discipline: record.discipline.name(),
+                    best: ExportedMark::from_mark(&record.best),
+                    progression: record.progression.iter().map(ExportedMark::from_mark).collect(),
+                })
+                .collect(),
+        })
+        .collect();
+    exported_records.sort_by(|a, b| a.name.cmp(b.name));
+
+    write_json_file(folder, PERSONAL_RECORDS_FILE, &exported_records)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::discipline::Discipline;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::personal_record::personal_records;
+    use crate::result::result_direction::ResultDirections;
+
+    #[test]
+    fn should_find_best_mark_across_conventions() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let person = Person::create_test_instance("John Doe")
+            .with_results(&convention1, &[
+                CompetitionResult::create_test_instance(1, "100m Male", "1", "00:14.99"),
+                CompetitionResult::create_test_instance(1, "Slow", "1", "00:30.00"),
+            ])
+            .with_results(&convention2, &[
+                CompetitionResult::create_test_instance(1, "100 m", "1", "00:15.50"),
+                CompetitionResult::create_test_instance(1, "Freestyle", "1", "Custom"),
+            ]);
+
+        let records = personal_records(&person, &ResultDirections::default());
+
+        assert_eq!(records.len(), 2);
+        let record = records.iter().find(|record| record.discipline() == &Discipline::from_string("100m")).unwrap();
+        assert_eq!(record.best().convention(), &convention1);
+        assert_eq!(record.progression().len(), 2);
+        assert_eq!(record.progression()[0].convention(), &convention1);
+        assert_eq!(record.progression()[1].convention(), &convention2);
+    }
+
+    #[test]
+    fn should_find_slowest_mark_in_slow_races() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let person = Person::create_test_instance("John Doe")
+            .with_results(&convention1, &[
+                CompetitionResult::create_test_instance(1, "Slow Forward Male", "1", "00:30.00"),
+                CompetitionResult::create_test_instance(1, "Slow Backward", "1", "00:20.00"),
+            ])
+            .with_results(&convention2, &[
+                CompetitionResult::create_test_instance(1, "Slow Forward", "1", "00:40.00"),
+                CompetitionResult::create_test_instance(1, "Slow Backward Male", "1", "00:10.00"),
+            ]);
+
+        let records = personal_records(&person, &ResultDirections::default());
+
+        assert_eq!(records.len(), 2);
+        let slow_forward = records.iter().find(|record| record.discipline() == &Discipline::from_string("Slow Forward")).unwrap();
+        assert_eq!(slow_forward.best().convention(), &convention2);
+        let slow_backward = records.iter().find(|record| record.discipline() == &Discipline::from_string("Slow Backward")).unwrap();
+        assert_eq!(slow_backward.best().convention(), &convention1);
+    }
+}
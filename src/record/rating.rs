@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use derive_getters::Getters;
+use serde::Serialize;
+
+use crate::competition::competition::Competition;
+use crate::competition::discipline::Discipline;
+use crate::competition::discipline_family::DisciplineFamilies;
+use crate::competitor::competitor::Competitor;
+use crate::convention::convention::Convention;
+use crate::export::{write_csv_file, write_json_file, write_markdown_file};
+use crate::person::person::Person;
+use crate::result::place::Place;
+use crate::result::result_type::ResultType;
+
+const RATINGS_FILENAME: &str = "ratings";
+const RATING_HISTORY_FILENAME: &str = "rating_history";
+
+/// The rating of someone who has never been rated.
+const INITIAL_RATING: f64 = 1500.0;
+/// How much a single competition may change a rating.
+const K_FACTOR: f64 = 32.0;
+/// Name of the rating pool gathering every discipline.
+const ALL_DISCIPLINES: &str = "All";
+
+/// The current rating of a person, and the number of competitions it has been computed from.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Rating<'p, 'a> {
+    rank: usize,
+    person: &'p Person<'a>,
+    rating: f64,
+    competitions: u16,
+}
+
+/// The rating of a person after a convention, along with how much it changed during that convention.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct RatingHistoryEntry<'p, 'a> {
+    person: &'p Person<'a>,
+    convention: &'a Convention,
+    rating: f64,
+    change: f64,
+}
+
+/// Ratings computed over the competitions of a discipline family, or over every competition.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct RatingTable<'p, 'a> {
+    family: Option<String>,
+    ratings: Vec<Rating<'p, 'a>>,
+    history: Vec<RatingHistoryEntry<'p, 'a>>,
+}
+
+/// The people who finished a competition, by their index in the people and their overall rank.
+struct Field<'p, 'a> {
+    convention: &'a Convention,
+    competition: &'p Competition,
+    finishers: Vec<(usize, u16)>,
+}
+
+/// Rate people from the finishing orders of every individual competition, processing conventions in chronological order.
+/// A rating table is computed over every discipline, and another one for each discipline family.
+pub fn compute_ratings<'p, 'a>(people: &'p [Person<'a>], families: &DisciplineFamilies) -> Vec<RatingTable<'p, 'a>> {
+    let fields = get_fields(people);
+
+    let mut rating_tables = vec![compute_rating_table(people, None, fields.iter().collect())];
+    for family in families.names() {
+        let family_fields = fields.iter()
+            .filter(|field| families.family_of(&Discipline::from_competition(field.competition)) == Some(family))
+            .collect();
+        rating_tables.push(compute_rating_table(people, Some(family.clone()), family_fields));
+    }
+    rating_tables
+}
+
+/// Gather the overall ranks of individual competitors into fields, sorted chronologically.
+/// Team competitions are left out, as well as competitions with fewer than two finishers.
+fn get_fields<'p, 'a>(people: &'p [Person<'a>]) -> Vec<Field<'p, 'a>> {
+    let mut finishers: HashMap<(&'a Convention, &'p Competition), Vec<(usize, u16)>> = HashMap::new();
+    for (index, person) in people.iter().enumerate() {
+        for (convention, performances) in person.performances() {
+            for performance in performances {
+                if !matches!(performance.competitor(), Competitor::IndividualCompetitor(_) | Competitor::UnknownIndividualCompetitor(_)) {
+                    continue;
+                }
+                let Some(Place::Rank(rank)) = performance.place(ResultType::Overall) else { continue; };
+                let field_finishers = finishers.entry((*convention, performance.competition())).or_default();
+                if field_finishers.iter().all(|(finisher, _)| *finisher != index) {
+                    field_finishers.push((index, *rank));
+                }
+            }
+        }
+    }
+
+    let mut fields: Vec<Field> = finishers.into_iter()
+        .filter(|(_, finishers)| finishers.len() >= 2)
+        .map(|((convention, competition), finishers)| Field { convention, competition, finishers })
+        .collect();
+    fields.sort_by(|a, b| a.convention.compare_chronologically(b.convention)
+        .then_with(|| a.competition.name().cmp(b.competition.name())));
+    fields
+}
+
+fn compute_rating_table<'p, 'a>(people: &'p [Person<'a>], family: Option<String>, fields: Vec<&Field<'p, 'a>>) -> RatingTable<'p, 'a> {
+    let mut ratings: HashMap<usize, (f64, u16)> = HashMap::new();
+    let mut history = vec![];
+    let mut ratings_before_convention: HashMap<usize, f64> = HashMap::new();
+
+    for (position, field) in fields.iter().enumerate() {
+        let field_ratings: Vec<(f64, u16)> = field.finishers.iter()
+            .map(|(index, rank)| (ratings.get(index).map_or(INITIAL_RATING, |(rating, _)| *rating), *rank))
+            .collect();
+        let changes = compute_rating_changes(&field_ratings);
+        for ((index, _), change) in field.finishers.iter().zip(changes) {
+            let (rating, competitions) = ratings.entry(*index).or_insert((INITIAL_RATING, 0));
+            ratings_before_convention.entry(*index).or_insert(*rating);
+            *rating += change;
+            *competitions += 1;
+        }
+
+        let is_last_of_convention = fields.get(position + 1).is_none_or(|next| next.convention != field.convention);
+        if is_last_of_convention {
+            let mut convention_history: Vec<RatingHistoryEntry> = ratings_before_convention.drain()
+                .map(|(index, rating_before)| {
+                    let rating = ratings[&index].0;
+                    RatingHistoryEntry { person: &people[index], convention: field.convention, rating, change: rating - rating_before }
+                })
+                .collect();
+            convention_history.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+            history.append(&mut convention_history);
+        }
+    }
+
+    let mut current_ratings: Vec<(usize, f64, u16)> = ratings.into_iter()
+        .map(|(index, (rating, competitions))| (index, rating, competitions))
+        .collect();
+    current_ratings.sort_by(|(a_index, a, _), (b_index, b, _)| b.total_cmp(a)
+        .then_with(|| people[*a_index].identity().person_name().name().cmp(people[*b_index].identity().person_name().name())));
+    let ratings = current_ratings.into_iter()
+        .enumerate()
+        .map(|(position, (index, rating, competitions))| Rating { rank: position + 1, person: &people[index], rating, competitions })
+        .collect();
+
+    RatingTable { family, ratings, history }
+}
+
+/// Multiplayer Elo: every finisher plays a game against every other finisher of the field,
+/// won by whoever finished ahead. The changes of all these games are averaged, so that a large field
+/// doesn't move ratings more than a small one.
+fn compute_rating_changes(field: &[(f64, u16)]) -> Vec<f64> {
+    if field.len() < 2 {
+        return vec![0.0; field.len()];
+    }
+
+    let opponents_count = (field.len() - 1) as f64;
+    field.iter().enumerate()
+        .map(|(position, (rating, rank))| {
+            let score_difference: f64 = field.iter().enumerate()
+                .filter(|(other_position, _)| *other_position != position)
+                .map(|(_, (other_rating, other_rank))| {
+                    let expected_score = 1.0 / (1.0 + 10_f64.powf((other_rating - rating) / 400.0));
+                    let score = match rank.cmp(other_rank) {
+                        std::cmp::Ordering::Less => { 1.0 }
+                        std::cmp::Ordering::Equal => { 0.5 }
+                        std::cmp::Ordering::Greater => { 0.0 }
+                    };
+                    score - expected_score
+                })
+                .sum();
+            K_FACTOR * score_difference / opponents_count
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ExportedRating<'a> {
+    family: &'a str,
+    rank: usize,
+    id: String,
+    name: &'a str,
+    rating: String,
+    competitions: u16,
+}
+
+#[derive(Serialize)]
+struct ExportedRatingHistoryEntry<'a> {
+    family: &'a str,
+    convention: &'a str,
+    id: String,
+    name: &'a str,
+    rating: String,
+    change: String,
+}
+
+/// Export current ratings as JSON, CSV and Markdown in `{folder}/ratings.{json,csv,md}`,
+/// and the rating of every person after each convention as JSON and CSV in `{folder}/rating_history.{json,csv}`.
+pub fn export_ratings(folder: &str, rating_tables: &[RatingTable]) -> Result<(), ()> {
+    let mut exported_ratings = vec![];
+    let mut exported_history = vec![];
+    for rating_table in rating_tables {
+        let family = rating_table.family.as_deref().unwrap_or(ALL_DISCIPLINES);
+        exported_ratings.extend(rating_table.ratings.iter().map(|rating| ExportedRating {
+            family,
+            rank: rating.rank,
+            id: rating.person.id().to_string(),
+            name: rating.person.identity().person_name().name(),
+            rating: format!("{:.0}", rating.rating),
+            competitions: rating.competitions,
+        }));
+        exported_history.extend(rating_table.history.iter().map(|entry| ExportedRatingHistoryEntry {
+            family,
+            convention: entry.convention.tag(),
+            id: entry.person.id().to_string(),
+            name: entry.person.identity().person_name().name(),
+            rating: format!("{:.0}", entry.rating),
+            change: format!("{:+.0}", entry.change),
+        }));
+    }
+
+    let json_result = write_json_file(folder, &format!("{RATINGS_FILENAME}.json"), &exported_ratings);
+    let csv_result = write_csv_file(folder, &format!("{RATINGS_FILENAME}.csv"), &exported_ratings);
+    let markdown_result = write_markdown_file(folder, &format!("{RATINGS_FILENAME}.md"), "Ratings", &exported_ratings);
+    let history_json_result = write_json_file(folder, &format!("{RATING_HISTORY_FILENAME}.json"), &exported_history);
+    let history_csv_result = write_csv_file(folder, &format!("{RATING_HISTORY_FILENAME}.csv"), &exported_history);
+
+    json_result.and(csv_result).and(markdown_result).and(history_json_result).and(history_csv_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::discipline_family::DisciplineFamilies;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::rating::{compute_rating_changes, compute_ratings, INITIAL_RATING};
+
+    #[test]
+    fn should_reward_upsets_more_than_expected_wins() {
+        let expected_win = compute_rating_changes(&[(1700.0, 1), (1300.0, 2)]);
+        let upset = compute_rating_changes(&[(1300.0, 1), (1700.0, 2)]);
+
+        assert!(expected_win[0] > 0.0 && expected_win[0] < upset[0]);
+        assert!((expected_win[0] + expected_win[1]).abs() < 1e-9);
+        assert_eq!(compute_rating_changes(&[(1500.0, 1), (1500.0, 1)]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn should_rate_people_chronologically_and_per_family() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let people = vec![
+            Person::create_test_instance("John Doe")
+                .with_results(&convention1, &[
+                    CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990"),
+                    CompetitionResult::create_test_instance(1, "Freestyle", "2", "00:14.990"),
+                ])
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990")]),
+            Person::create_test_instance("Jane Doe")
+                .with_results(&convention1, &[
+                    CompetitionResult::create_test_instance(1, "100m", "2", "00:14.990"),
+                    CompetitionResult::create_test_instance(1, "Freestyle", "1", "00:14.990"),
+                ])
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "2", "00:14.990")]),
+        ];
+        let families = DisciplineFamilies::from_raw_families(BTreeMap::from([("Track".to_string(), vec!["100m".to_string()])]));
+
+        let rating_tables = compute_ratings(&people, &families);
+
+        assert_eq!(rating_tables.len(), 2);
+        let all = &rating_tables[0];
+        assert_eq!(all.family(), &None);
+        assert_eq!(all.ratings()[0].person().identity().person_name().name(), "John Doe");
+        assert_eq!(*all.ratings()[0].competitions(), 3);
+        assert_eq!(all.history().len(), 4);
+        let track = &rating_tables[1];
+        assert_eq!(track.family(), &Some("Track".to_string()));
+        assert!(*track.ratings()[0].rating() > INITIAL_RATING + 16.0);
+        let last_change = track.history().iter()
+            .find(|entry| entry.convention().tag() == "cfm2023" && entry.person().identity().person_name().name() == "John Doe")
+            .unwrap();
+        assert!(*last_change.change() > 0.0 && *last_change.change() < 16.0);
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use derive_getters::Getters;
+use log::warn;
+use serde::Serialize;
+
+use crate::competition::discipline::Discipline;
+use crate::competition::performance::Performance;
+use crate::competitor::gender::Gender;
+use crate::convention::convention::Convention;
+use crate::country::country::Country;
+use crate::export::{write_csv_file, write_json_file, write_markdown_file};
+use crate::person::person::Person;
+use crate::record::personal_record::Mark;
+use crate::result::age_group::AgeGroup;
+use crate::result::result_direction::ResultDirections;
+
+const ALL_TIME_RECORDS_FILENAME: &str = "records";
+
+/// The conventions a [RecordTable] is computed over.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordScope<'a> {
+    AllTime,
+    Convention(&'a Convention),
+}
+
+/// A record is kept for each discipline, and then for each discipline by gender, by age group, by country
+/// and by country and gender.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Getters)]
+pub struct RecordCategory {
+    discipline: Discipline,
+    gender: Option<String>,
+    age_group: Option<String>,
+    country: Option<String>,
+}
+
+impl RecordCategory {
+    pub fn new(discipline: Discipline, gender: Option<&Gender>, age_group: Option<&AgeGroup>, country: Option<&Country>) -> Self {
+        Self {
+            discipline,
+            gender: gender.map(|gender| gender.to_string()),
+            age_group: age_group.map(|age_group| age_group.to_string()),
+            country: country.map(|country| country.code().to_string()),
+        }
+    }
+
+    fn all_for_mark(discipline: Discipline, gender: Option<&Gender>, age_group: Option<&AgeGroup>, country: Option<&Country>) -> Vec<Self> {
+        let mut categories = vec![RecordCategory::new(discipline.clone(), None, None, None)];
+        if gender.is_some() {
+            categories.push(RecordCategory::new(discipline.clone(), gender, None, None));
+        }
+        if age_group.is_some() {
+            categories.push(RecordCategory::new(discipline.clone(), None, age_group, None));
+        }
+        if country.is_some() {
+            categories.push(RecordCategory::new(discipline.clone(), None, None, country));
+            if gender.is_some() {
+                categories.push(RecordCategory::new(discipline, gender, None, country));
+            }
+        }
+        categories
+    }
+}
+
+/// A [Record] is the best [Mark] in a [RecordCategory], along with the person who holds it.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Record<'p, 'a> {
+    category: RecordCategory,
+    holder: &'p Person<'a>,
+    mark: Mark<'a>,
+}
+
+impl<'p, 'a> Record<'p, 'a> {
+    pub fn new(category: RecordCategory, holder: &'p Person<'a>, mark: Mark<'a>) -> Self {
+        Self { category, holder, mark }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct RecordTable<'p, 'a> {
+    scope: RecordScope<'a>,
+    records: Vec<Record<'p, 'a>>,
+}
+
+impl<'p, 'a> RecordTable<'p, 'a> {
+    pub fn new(scope: RecordScope<'a>, records: Vec<Record<'p, 'a>>) -> Self {
+        Self { scope, records }
+    }
+}
+
+/// Compute the record of every [RecordCategory] over the conventions of the scope.
+/// When several marks are equal, the earliest one holds the record.
+/// Records are sorted by category.
+pub fn compute_record_table<'p, 'a>(people: &'p [Person<'a>], scope: RecordScope<'a>, result_directions: &ResultDirections) -> RecordTable<'p, 'a> {
+    let mut marks: Vec<(&Person, &Convention, &Performance)> = vec![];
+    for person in people {
+        for (convention, performances) in person.performances() {
+            if let RecordScope::Convention(scope_convention) = scope {
+                if scope_convention != *convention {
+                    continue;
+                }
+            }
+            for performance in performances {
+                let is_comparable = performance.result().as_ref()
+                    .and_then(|value| result_directions.higher_is_better(performance.competition(), value))
+                    .is_some();
+                if is_comparable {
+                    marks.push((person, convention, performance));
+                }
+            }
+        }
+    }
+    marks.sort_by(|(_, a, _), (_, b, _)| a.compare_chronologically(b));
+
+    let mut best_marks: HashMap<RecordCategory, Record> = HashMap::new();
+    for (person, convention, performance) in marks {
+        let country = person.country_at(convention);
+        let categories = RecordCategory::all_for_mark(
+            Discipline::from_competition(performance.competition()),
+            performance.gender(),
+            performance.age_group().as_ref(),
+            country,
+        );
+
+        for category in categories {
+            let is_new_record = match best_marks.get(&category) {
+                None => { true }
+                Some(record) => {
+                    let value = performance.result().as_ref().unwrap();
+                    let record_value = record.mark.performance().result().as_ref().unwrap();
+                    match result_directions.is_better_than(performance.competition(), value, record_value) {
+                        Ok(is_better) => { is_better }
+                        Err(error) => {
+                            warn!("Can't compare performance with record [record: {:?}, performance: {:?}]", record.mark, performance);
+                            warn!("{error}");
+                            false
+                        }
+                    }
+                }
+            };
+            if is_new_record {
+                let record = Record::new(category.clone(), person, Mark::new(convention, performance.clone()));
+                best_marks.insert(category, record);
+            }
+        }
+    }
+
+    let mut records: Vec<Record> = best_marks.into_values().collect();
+    records.sort_by(|a, b| a.category.cmp(&b.category));
+
+    RecordTable::new(scope, records)
+}
+
+#[derive(Serialize)]
+struct ExportedRecord<'a> {
+    discipline: &'a str,
+    gender: &'a str,
+    age_group: &'a str,
+    country: &'a str,
+    holder_id: String,
+    holder: &'a str,
+    result: String,
+    convention: &'a str,
+    competition: &'a str,
+}
+
+impl<'a> ExportedRecord<'a> {
+    fn from_record(record: &'a Record) -> Self {
+        Self {
+            discipline: record.category.discipline.name(),
+            gender: record.category.gender.as_deref().unwrap_or_default(),
+            age_group: record.category.age_group.as_deref().unwrap_or_default(),
+            country: record.category.country.as_deref().unwrap_or_default(),
+            holder_id: record.holder.id().to_string(),
+            holder: record.holder.identity().person_name().name(),
+            result: record.mark.performance().result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+            convention: record.mark.convention().tag(),
+            competition: record.mark.performance().competition().name(),
+        }
+    }
+}
+
+/// Export the record table as JSON, CSV and Markdown in `{folder}`.
+/// All-time records are written to `records.{json,csv,md}` and convention records to `records_{tag}.{json,csv,md}`.
+pub fn export_record_table(folder: &str, record_table: &RecordTable) -> Result<(), ()> {
+    let (filename, title) = match record_table.scope {
+        RecordScope::AllTime => { (ALL_TIME_RECORDS_FILENAME.to_string(), "All-time records".to_string()) }
+        RecordScope::Convention(convention) => {
+            (format!("{ALL_TIME_RECORDS_FILENAME}_{}", convention.tag()), format!("Records of {}", convention.name()))
+        }
+    };
+    let exported_records: Vec<ExportedRecord> = record_table.records.iter()
+        .map(ExportedRecord::from_record)
+        .collect();
+
+    let json_result = write_json_file(folder, &format!("{filename}.json"), &exported_records);
+    let csv_result = write_csv_file(folder, &format!("{filename}.csv"), &exported_records);
+    let markdown_result = write_markdown_file(folder, &format!("{filename}.md"), &title, &exported_records);
+
+    json_result.and(csv_result).and(markdown_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::discipline::Discipline;
+    use crate::competitor::gender::Gender;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::record_table::{compute_record_table, Record, RecordCategory, RecordScope};
+    use crate::result::result_direction::ResultDirections;
+
+    fn get_holder(records: &[Record], gender: Option<&str>) -> String {
+        let gender = gender.map(|gender| Gender::from_string(gender).unwrap());
+        let category = RecordCategory::new(Discipline::from_string("100m"), gender.as_ref(), None, None);
+        let record = records.iter().find(|record| record.category() == &category).unwrap();
+        record.holder().identity().person_name().name().clone()
+    }
+
+    #[test]
+    fn should_keep_best_mark_per_category() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let people = vec![
+            Person::create_test_instance("John Doe")
+                .with_results(&convention1, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990").with_individual_competitor("", Some("Male"), None)])
+                .with_country(&convention1, "France"),
+            Person::create_test_instance("Jane Doe")
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:15.990").with_individual_competitor("", Some("Female"), None)])
+                .with_country(&convention2, "France"),
+            Person::create_test_instance("Jack Doe")
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:16.990").with_individual_competitor("", Some("Male"), None)])
+                .with_country(&convention2, "France"),
+        ];
+
+        let all_time = compute_record_table(&people, RecordScope::AllTime, &ResultDirections::default());
+        let convention = compute_record_table(&people, RecordScope::Convention(&convention2), &ResultDirections::default());
+
+        assert_eq!(all_time.records().len(), 6);
+        assert_eq!(get_holder(all_time.records(), None), "John Doe");
+        assert_eq!(get_holder(all_time.records(), Some("Female")), "Jane Doe");
+        assert_eq!(get_holder(convention.records(), None), "Jane Doe");
+        assert_eq!(get_holder(convention.records(), Some("Male")), "Jack Doe");
+        let french_male_record = all_time.records().iter()
+            .find(|record| record.category().gender() == &Some("Male".to_string()) && record.category().country() == &Some("FR".to_string()))
+            .unwrap();
+        assert_eq!(french_male_record.holder().identity().person_name().name(), "John Doe");
+    }
+}
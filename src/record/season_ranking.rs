@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::BufReader;
+
+use derive_getters::Getters;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::competition::discipline::Discipline;
+use crate::convention::convention::Convention;
+use crate::export::{write_csv_file, write_json_file, write_markdown_file};
+use crate::person::person::Person;
+use crate::result::place::Place;
+use crate::result::result_type::ResultType;
+
+const SEASON_RANKINGS_FILE: &str = "season_rankings.json";
+const SEASON_FILENAME_PREFIX: &str = "season";
+
+/// How a season is scored: which conventions and disciplines count, how many points each place is worth,
+/// and how many of their best results count for each person.
+#[derive(Debug, Clone, PartialEq, Deserialize, Getters)]
+pub struct SeasonRankingConfig {
+    tag: String,
+    name: String,
+    conventions: Vec<String>,
+    /// Every discipline counts if none is given.
+    #[serde(default)]
+    disciplines: Vec<String>,
+    /// Points of the first place, of the second place, and so on. Places further down are worth no point.
+    points: Vec<u16>,
+    /// Every result counts if not given.
+    #[serde(default)]
+    best_of: Option<usize>,
+}
+
+impl SeasonRankingConfig {
+    /// Load the seasons to rank from `{folder}/season_rankings.json`. If there is none, no season is ranked.
+    pub fn load_from_folder(folder: &str) -> Vec<Self> {
+        let filepath = format!("{folder}/{SEASON_RANKINGS_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No season rankings [filepath: {filepath}]");
+                return vec![];
+            }
+        };
+        let reader = BufReader::new(file);
+
+        match serde_json::from_reader::<_, Vec<Self>>(reader) {
+            Ok(configs) => {
+                info!("Season rankings loaded [seasons: {}]", configs.len());
+                configs
+            }
+            Err(error) => {
+                warn!("Can't read season rankings, seasons won't be ranked [filepath: {filepath}]");
+                warn!("{}", error);
+                vec![]
+            }
+        }
+    }
+
+    fn points_of(&self, rank: u16) -> u16 {
+        (rank as usize).checked_sub(1)
+            .and_then(|index| self.points.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Points scored by a person in a competition of the season, and whether they count in their total.
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct SeasonScore<'a> {
+    convention: &'a Convention,
+    competition: String,
+    rank: u16,
+    points: u16,
+    counted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct SeasonStanding<'p, 'a> {
+    rank: usize,
+    person: &'p Person<'a>,
+    points: u16,
+    scores: Vec<SeasonScore<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct SeasonRanking<'p, 'a> {
+    config: SeasonRankingConfig,
+    standings: Vec<SeasonStanding<'p, 'a>>,
+}
+
+/// Rank people by the points they scored with their overall places in the conventions and disciplines of the season.
+/// Only the best `best_of` scores count. People with equal points are told apart by countback:
+/// the one with the best counted score wins, then the second best, and so on. People still equal share the same rank.
+pub fn compute_season_ranking<'p, 'a>(people: &'p [Person<'a>], config: &SeasonRankingConfig) -> SeasonRanking<'p, 'a> {
+    let disciplines: Vec<Discipline> = config.disciplines.iter().map(|discipline| Discipline::from_string(discipline)).collect();
+
+    let mut standings: Vec<SeasonStanding> = vec![];
+    for person in people {
+        let mut scores: Vec<SeasonScore> = vec![];
+        for (convention, performances) in person.performances() {
+            if !config.conventions.contains(convention.tag()) {
+                continue;
+            }
+            for performance in performances {
+                if !disciplines.is_empty() && !disciplines.contains(&Discipline::from_competition(performance.competition())) {
+                    continue;
+                }
+                let Some(Place::Rank(rank)) = performance.place(ResultType::Overall) else { continue; };
+                let points = config.points_of(*rank);
+                if points > 0 {
+                    scores.push(SeasonScore { convention, competition: performance.competition().name().clone(), rank: *rank, points, counted: false });
+                }
+            }
+        }
+        if scores.is_empty() {
+            continue;
+        }
+
+        scores.sort_by(|a, b| b.points.cmp(&a.points)
+            .then_with(|| a.convention.compare_chronologically(b.convention))
+            .then_with(|| a.competition.cmp(&b.competition)));
+        let counted_scores = config.best_of.unwrap_or(scores.len());
+        for score in scores.iter_mut().take(counted_scores) {
+            score.counted = true;
+        }
+        let points = scores.iter().filter(|score| score.counted).map(|score| score.points).sum();
+        standings.push(SeasonStanding { rank: 0, person, points, scores });
+    }
+
+    standings.sort_by(|a, b| compare_standings(a, b)
+        .then_with(|| a.person.identity().person_name().name().cmp(b.person.identity().person_name().name())));
+    for index in 0..standings.len() {
+        standings[index].rank = match index.checked_sub(1).map(|previous| &standings[previous]) {
+            Some(previous) if compare_standings(previous, &standings[index]) == Ordering::Equal => { previous.rank }
+            _ => { index + 1 }
+        };
+    }
+
+    SeasonRanking { config: config.clone(), standings }
+}
+
+/// More points first, and then countback on the counted scores, which are sorted from best to worst.
+fn compare_standings(a: &SeasonStanding, b: &SeasonStanding) -> Ordering {
+    let counted_points = |standing: &SeasonStanding| -> Vec<u16> {
+        standing.scores.iter().filter(|score| score.counted).map(|score| score.points).collect()
+    };
+    b.points.cmp(&a.points)
+        .then_with(|| counted_points(b).cmp(&counted_points(a)))
+}
+
+#[derive(Serialize)]
+struct ExportedSeasonScore<'a> {
+    convention: &'a str,
+    competition: &'a str,
+    rank: u16,
+    points: u16,
+    counted: bool,
+}
+
+#[derive(Serialize)]
+struct ExportedSeasonStanding<'a> {
+    rank: usize,
+    id: String,
+    name: &'a str,
+    points: u16,
+    scores: Vec<ExportedSeasonScore<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedSeasonStandingRow<'a> {
+    rank: usize,
+    id: String,
+    name: &'a str,
+    points: u16,
+    counted_results: usize,
+    breakdown: String,
+}
+
+/// Export the standings of the season in `{folder}/season_{tag}.{json,csv,md}`.
+/// The JSON file lists every score of each person, the CSV and Markdown files sum them up in a breakdown column
+/// where scores that don't count are in brackets.
+pub fn export_season_ranking(folder: &str, ranking: &SeasonRanking) -> Result<(), ()> {
+    let filename = format!("{SEASON_FILENAME_PREFIX}_{}", ranking.config.tag);
+    let exported_standings: Vec<ExportedSeasonStanding> = ranking.standings.iter()
+        .map(|standing| ExportedSeasonStanding {
+            rank: standing.rank,
+            id: standing.person.id().to_string(),
+            name: standing.person.identity().person_name().name(),
+            points: standing.points,
+            scores: standing.scores.iter()
+                .map(|score| ExportedSeasonScore {
+                    convention: score.convention.tag(),
+                    competition: &score.competition,
+                    rank: score.rank,
+                    points: score.points,
+                    counted: score.counted,
+                })
+                .collect(),
+        })
+        .collect();
+    let exported_rows: Vec<ExportedSeasonStandingRow> = ranking.standings.iter()
+        .map(|standing| ExportedSeasonStandingRow {
+            rank: standing.rank,
+            id: standing.person.id().to_string(),
+            name: standing.person.identity().person_name().name(),
+            points: standing.points,
+            counted_results: standing.scores.iter().filter(|score| score.counted).count(),
+            breakdown: standing.scores.iter()
+                .map(|score| {
+                    let score_text = format!("{} {} #{}: {}", score.convention.tag(), score.competition, score.rank, score.points);
+                    if score.counted { score_text } else { format!("({score_text})") }
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+        })
+        .collect();
+
+    let json_result = write_json_file(folder, &format!("{filename}.json"), &exported_standings);
+    let csv_result = write_csv_file(folder, &format!("{filename}.csv"), &exported_rows);
+    let markdown_result = write_markdown_file(folder, &format!("{filename}.md"), &ranking.config.name, &exported_rows);
+
+    json_result.and(csv_result).and(markdown_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::record::season_ranking::{compute_season_ranking, SeasonRankingConfig};
+
+    #[test]
+    fn should_count_best_results_and_break_ties_by_countback() {
+        let convention1 = Convention::create_test_instance("cfm2022");
+        let convention2 = Convention::create_test_instance("cfm2023");
+        let convention3 = Convention::create_test_instance("unicon2022");
+        let config: SeasonRankingConfig = serde_json::from_str(r#"{
+            "tag": "cup2022",
+            "name": "Cup 2022",
+            "conventions": ["cfm2022", "cfm2023"],
+            "disciplines": ["100m", "400m"],
+            "points": [25, 20, 15],
+            "best_of": 2
+        }"#).unwrap();
+        let people = vec![
+            Person::create_test_instance("John Doe")
+                .with_results(&convention1, &[
+                    CompetitionResult::create_test_instance(1, "100m Male", "1", "00:14.990"),
+                    CompetitionResult::create_test_instance(1, "400m", "3", "00:14.990"),
+                    CompetitionResult::create_test_instance(1, "Freestyle", "1", "00:14.990"),
+                ])
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "3", "00:14.990")])
+                .with_results(&convention3, &[CompetitionResult::create_test_instance(1, "100m", "1", "00:14.990")]),
+            Person::create_test_instance("Jane Doe")
+                .with_results(&convention1, &[
+                    CompetitionResult::create_test_instance(1, "100m", "2", "00:14.990"),
+                    CompetitionResult::create_test_instance(1, "400m", "2", "00:14.990"),
+                ])
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "4", "00:14.990")]),
+            Person::create_test_instance("Jack Doe")
+                .with_results(&convention2, &[CompetitionResult::create_test_instance(1, "100m", "5", "00:14.990")]),
+        ];
+
+        let ranking = compute_season_ranking(&people, &config);
+
+        let standings = ranking.standings();
+        assert_eq!(standings.len(), 2);
+        assert_eq!(standings[0].person().identity().person_name().name(), "John Doe");
+        assert_eq!((*standings[0].rank(), *standings[0].points()), (1, 40));
+        assert_eq!(standings[0].scores().iter().filter(|score| !score.counted()).count(), 1);
+        assert_eq!((*standings[1].rank(), *standings[1].points()), (2, 40));
+    }
+}
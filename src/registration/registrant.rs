@@ -18,10 +18,24 @@ pub struct Registrant {
     last_name: String,
     #[serde(alias = "Country")]
     country: String,
+    #[serde(alias = "Club", default)]
+    club: String,
     #[serde(alias = "Birthday (dd/mm/yyyy)", with = "birthday_date_format")]
     birthday: NaiveDate,
 }
 
+impl Registrant {
+    pub fn with_names(self, first_name: String, last_name: String) -> Self {
+        Self { first_name, last_name, ..self }
+    }
+
+    /// Age in full years at the given date.
+    pub fn age_at(&self, date: NaiveDate) -> Option<u8> {
+        let age = date.years_since(self.birthday)?;
+        u8::try_from(age).ok()
+    }
+}
+
 pub fn load_registrants_for_conventions(conventions: &HashSet<Convention>) -> Result<HashMap<&Convention, Vec<Registrant>>, String> {
     let mut registrants = HashMap::new();
     for convention in conventions {
@@ -84,4 +98,24 @@ mod birthday_date_format {
         let dt = NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)?;
         Ok(dt)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::registration::registrant::Registrant;
+
+    impl Registrant {
+        pub fn create_test_instance(id: u16, first_name: &str, last_name: &str, birthday: NaiveDate) -> Self {
+            Registrant {
+                id,
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+                country: "France".to_string(),
+                club: String::new(),
+                birthday,
+            }
+        }
+    }
 }
\ No newline at end of file
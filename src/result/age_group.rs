@@ -1,11 +1,171 @@
-#[derive(Debug, Clone, PartialEq)]
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+use deunicode::deunicode;
+use regex::Regex;
+
+thread_local!(static NUMERIC_RANGE_SEPARATOR_REGEX: Regex = Regex::new(r"(\d) +- +(\d)").unwrap());
+thread_local!(static BOUNDED_RANGE_REGEX: Regex = Regex::new(r"(?P<min>\d{1,3}) *(-|–|to) *(?P<max>\d{1,3})").unwrap());
+thread_local!(static MIN_AGE_REGEX: Regex = Regex::new(r"(?P<min>\d{1,3}) *(\+|and (over|up|older)|or (over|older))|(over|above) (?P<over>\d{1,3})").unwrap());
+thread_local!(static MAX_AGE_REGEX: Regex = Regex::new(r"(\bu|\bunder|<) *(?P<under>\d{1,3})\b|(?P<max>\d{1,3}) *(and|&|or) *(under|younger)").unwrap());
+thread_local!(static OPEN_REGEX: Regex = Regex::new(r"\b(expert|open|all ages|overall)\b").unwrap());
+
+/// The ages an age group is made of, whatever the way a convention names it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AgeRange {
+    /// From `min` to `max` years old included, or with no upper limit.
+    Ages { min: u8, max: Option<u8> },
+    /// Every age may take part, e.g. "Expert" or "Open".
+    Open,
+    /// Any other name, which can't be compared with the age groups of other conventions.
+    Named(String),
+}
+
+impl AgeRange {
+    fn from_string(name: &str) -> Self {
+        let sanitized_name = deunicode(name).to_lowercase();
+        if let Some(range) = BOUNDED_RANGE_REGEX.with(|regex| regex.captures(&sanitized_name).and_then(|captures| {
+            let min = captures["min"].parse::<u8>().ok()?;
+            let max = captures["max"].parse::<u8>().ok()?;
+            Some(AgeRange::Ages { min, max: Some(max) }).filter(|_| min <= max)
+        })) {
+            return range;
+        }
+        if let Some(range) = MIN_AGE_REGEX.with(|regex| regex.captures(&sanitized_name).and_then(|captures| {
+            let min = captures.name("min").or(captures.name("over"))?.as_str().parse::<u8>().ok()?;
+            let min = if captures.name("over").is_some() { min.checked_add(1)? } else { min };
+            Some(AgeRange::Ages { min, max: None })
+        })) {
+            return range;
+        }
+        if let Some(range) = MAX_AGE_REGEX.with(|regex| regex.captures(&sanitized_name).and_then(|captures| {
+            let max = match captures.name("under") {
+                Some(under) => { under.as_str().parse::<u8>().ok()?.checked_sub(1)? }
+                None => { captures["max"].parse::<u8>().ok()? }
+            };
+            Some(AgeRange::Ages { min: 0, max: Some(max) })
+        })) {
+            return range;
+        }
+        if OPEN_REGEX.with(|regex| regex.is_match(&sanitized_name)) {
+            return AgeRange::Open;
+        }
+
+        AgeRange::Named(name.split_whitespace().collect::<Vec<&str>>().join(" "))
+    }
+
+    /// Tell whether someone of this age may take part, or [None] if the range doesn't say.
+    pub fn contains(&self, age: u8) -> Option<bool> {
+        match self {
+            AgeRange::Ages { min, max } => { Some(*min <= age && max.is_none_or(|max| age <= max)) }
+            AgeRange::Open => { Some(true) }
+            AgeRange::Named(_) => { None }
+        }
+    }
+}
+
+impl Display for AgeRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgeRange::Ages { min, max: Some(max) } => { write!(f, "{min}-{max}") }
+            AgeRange::Ages { min, max: None } => { write!(f, "{min}+") }
+            AgeRange::Open => { write!(f, "Open") }
+            AgeRange::Named(name) => { write!(f, "{name}") }
+        }
+    }
+}
+
+/// An age group may be in fact a gathering of multiple age groups, e.g. "Expert - 19+".
+/// Age groups are equal when their ranges are, so that "13 - 14" and "Age 13-14" are the same age group.
+#[derive(Debug, Clone)]
 pub struct AgeGroup {
-    groups_name: Vec<String>,
+    ranges: Vec<AgeRange>,
 }
 
 impl AgeGroup {
     pub fn from_string(groups_name: &str) -> Self {
-        let split_groups_name = groups_name.split(" - ");
-        Self { groups_name: split_groups_name.map(|s| String::from(s.trim())).collect() }
+        let groups_name = NUMERIC_RANGE_SEPARATOR_REGEX.with(|regex| regex.replace_all(groups_name, "$1-$2").to_string());
+        let ranges = groups_name.split(" - ").map(|name| AgeRange::from_string(name.trim())).collect();
+        Self { ranges }
+    }
+
+    /// Tell whether someone of this age may take part, or [None] if no range of the age group says.
+    pub fn contains(&self, age: u8) -> Option<bool> {
+        self.ranges.iter()
+            .filter_map(|range| range.contains(age))
+            .reduce(|a, b| a && b)
+    }
+}
+
+impl PartialEq for AgeGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges == other.ranges
+    }
+}
+
+impl Eq for AgeGroup {}
+
+impl Hash for AgeGroup {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ranges.hash(state);
+    }
+}
+
+impl Display for AgeGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ranges: Vec<String> = self.ranges.iter().map(AgeRange::to_string).collect();
+        write!(f, "{}", ranges.join(" - "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::result::age_group::{AgeGroup, AgeRange};
+
+    #[test]
+    fn should_read_bounded_range() {
+        assert_eq!(AgeGroup::from_string("13-14").ranges, vec![AgeRange::Ages { min: 13, max: Some(14) }]);
+        assert_eq!(AgeGroup::from_string("13 - 14").ranges, vec![AgeRange::Ages { min: 13, max: Some(14) }]);
+        assert_eq!(AgeGroup::from_string("Age 13 to 14").ranges, vec![AgeRange::Ages { min: 13, max: Some(14) }]);
+    }
+
+    #[test]
+    fn should_read_open_ended_ranges() {
+        assert_eq!(AgeGroup::from_string("30+").ranges, vec![AgeRange::Ages { min: 30, max: None }]);
+        assert_eq!(AgeGroup::from_string("Over 29").ranges, vec![AgeRange::Ages { min: 30, max: None }]);
+        assert_eq!(AgeGroup::from_string("U15").ranges, vec![AgeRange::Ages { min: 0, max: Some(14) }]);
+        assert_eq!(AgeGroup::from_string("14 and under").ranges, vec![AgeRange::Ages { min: 0, max: Some(14) }]);
+    }
+
+    #[test]
+    fn should_read_open_age_groups() {
+        assert_eq!(AgeGroup::from_string("Expert").ranges, vec![AgeRange::Open]);
+        assert_eq!(AgeGroup::from_string("Open").ranges, vec![AgeRange::Open]);
+    }
+
+    #[test]
+    fn should_read_gathered_age_groups() {
+        let age_group = AgeGroup::from_string("Expert - 19+");
+
+        assert_eq!(age_group.ranges, vec![AgeRange::Open, AgeRange::Ages { min: 19, max: None }]);
+        assert_eq!(age_group.to_string(), "Open - 19+");
+    }
+
+    #[test]
+    fn should_keep_unknown_names() {
+        assert_eq!(AgeGroup::from_string("Junior  Boys").ranges, vec![AgeRange::Named("Junior Boys".to_string())]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_compare_age_groups_named_differently() {
+        assert_eq!(AgeGroup::from_string("13 - 14"), AgeGroup::from_string("Age 13-14"));
+        assert_ne!(AgeGroup::from_string("13-14"), AgeGroup::from_string("15-16"));
+    }
+
+    #[test]
+    fn should_tell_whether_age_fits() {
+        assert_eq!(AgeGroup::from_string("13-14").contains(14), Some(true));
+        assert_eq!(AgeGroup::from_string("13-14").contains(15), Some(false));
+        assert_eq!(AgeGroup::from_string("Junior").contains(15), None);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod place;
 pub mod result_value;
 pub mod result_type;
-pub mod age_group;
\ No newline at end of file
+pub mod age_group;
+pub mod result_direction;
\ No newline at end of file
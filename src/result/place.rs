@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 const AUTHORIZED_DISQUALIFIED_STRINGS: [&str; 2] = ["DNF", "DQ"];
 
 /// A [Place] can either be a rank denoted as an unsigned integer
@@ -23,4 +25,13 @@ impl Place {
 
         Err(format!("Invalid place type [place: {}]", place))
     }
-}
\ No newline at end of file
+}
+
+impl Display for Place {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Place::Rank(rank) => { write!(f, "{rank}") }
+            Place::Disqualified(reason) => { write!(f, "{reason}") }
+        }
+    }
+}
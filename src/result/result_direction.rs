@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+
+use log::{debug, info, warn};
+
+use crate::competition::competition::Competition;
+use crate::competition::discipline::Discipline;
+use crate::result::result_value::ResultValue;
+
+const RESULT_DIRECTIONS_FILE: &str = "result_directions.json";
+
+/// Slow races are won by the competitor who takes the longest to cross the line.
+const SLOW_RACES: [&str; 2] = ["Slow Forward", "Slow Backward"];
+
+/// Whether a higher value is a better performance, for each discipline.
+/// Most disciplines rank times from the fastest and distances and points from the highest,
+/// and only the disciplines ranked the other way round need to be listed, e.g. slow races where the slowest time wins.
+#[derive(Debug)]
+pub struct ResultDirections {
+    higher_is_better_for_discipline: HashMap<Discipline, bool>,
+}
+
+impl Default for ResultDirections {
+    fn default() -> Self {
+        Self::from_raw_directions(BTreeMap::new())
+    }
+}
+
+impl ResultDirections {
+    /// Load the directions from `{folder}/result_directions.json`, e.g. `{"Stillstand": true}`,
+    /// on top of the slow races. If there is none, only slow races rank the highest values first among times.
+    pub fn load_from_folder(folder: &str) -> Self {
+        let filepath = format!("{folder}/{RESULT_DIRECTIONS_FILE}");
+        let file = match File::open(&filepath) {
+            Ok(file) => { file }
+            Err(_) => {
+                debug!("No result directions [filepath: {filepath}]");
+                return ResultDirections::default();
+            }
+        };
+        let reader = BufReader::new(file);
+
+        match serde_json::from_reader::<_, BTreeMap<String, bool>>(reader) {
+            Ok(raw_directions) => {
+                let directions = Self::from_raw_directions(raw_directions);
+                info!("Result directions loaded [disciplines: {}]", directions.higher_is_better_for_discipline.len());
+                directions
+            }
+            Err(error) => {
+                warn!("Can't read result directions, only slow races will be ranked from the highest time [filepath: {filepath}]");
+                warn!("{}", error);
+                ResultDirections::default()
+            }
+        }
+    }
+
+    pub fn from_raw_directions(raw_directions: BTreeMap<String, bool>) -> Self {
+        let mut higher_is_better_for_discipline: HashMap<Discipline, bool> = SLOW_RACES.iter()
+            .map(|slow_race| (Discipline::from_string(slow_race), true))
+            .collect();
+        for (discipline, higher_is_better) in raw_directions {
+            higher_is_better_for_discipline.insert(Discipline::from_string(&discipline), higher_is_better);
+        }
+
+        Self { higher_is_better_for_discipline }
+    }
+
+    /// Tells whether a higher value is a better performance in the discipline of the competition.
+    /// Disciplines that aren't listed fall back on the kind of value, see [ResultValue::higher_is_better_by_default].
+    /// Empty and custom values can't be ranked, hence [None].
+    pub fn higher_is_better(&self, competition: &Competition, value: &ResultValue) -> Option<bool> {
+        let default = value.higher_is_better_by_default()?;
+        let discipline = Discipline::from_competition(competition);
+        Some(self.higher_is_better_for_discipline.get(&discipline).copied().unwrap_or(default))
+    }
+
+    /// Tells whether the value is a strictly better performance than the other one in the discipline of the competition.
+    pub fn is_better_than(&self, competition: &Competition, value: &ResultValue, other: &ResultValue) -> Result<bool, String> {
+        match self.higher_is_better(competition, value) {
+            None => { Err(format!("Can't tell which value is better [value: {:?}, other: {:?}]", value, other)) }
+            Some(higher_is_better) => { value.is_better_than(other, higher_is_better) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::competition::competition::Competition;
+    use crate::result::result_direction::ResultDirections;
+    use crate::result::result_value::ResultValue;
+
+    #[test]
+    fn should_tell_slowest_time_is_better_in_slow_races() {
+        let directions = ResultDirections::default();
+        let faster = ResultValue::from_string("00:14.99");
+        let slower = ResultValue::from_string("00:18.00");
+
+        assert_eq!(directions.is_better_than(&Competition::new("Slow Forward Male"), &slower, &faster), Ok(true));
+        assert_eq!(directions.is_better_than(&Competition::new("Slow Backward - Women"), &slower, &faster), Ok(true));
+        assert_eq!(directions.is_better_than(&Competition::new("100m"), &slower, &faster), Ok(false));
+    }
+
+    #[test]
+    fn should_use_configured_directions() {
+        let directions = ResultDirections::from_raw_directions(BTreeMap::from([("Stillstand".to_string(), true)]));
+
+        assert_eq!(directions.higher_is_better(&Competition::new("Stillstand Female"), &ResultValue::from_string("00:30.00")), Some(true));
+        assert_eq!(directions.higher_is_better(&Competition::new("Stillstand Female"), &ResultValue::from_string("DNF")), None);
+        assert_eq!(directions.higher_is_better(&Competition::new("Slow Forward"), &ResultValue::from_string("00:30.00")), Some(true));
+    }
+}
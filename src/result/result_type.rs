@@ -1,19 +1,27 @@
-const AUTHORIZED_RESULT_TYPES: [&str; 2] = ["AgeGroup", "Overall"];
+use std::fmt::{Display, Formatter};
 
-/// A result type may only be one of [AUTHORIZED_RESULT_TYPES].
-#[derive(Debug, Clone, PartialEq)]
-pub struct ResultType {
-    result_type: String,
+/// Results are ranked either among every competitor of a competition, or among the competitors of an age group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResultType {
+    Overall,
+    AgeGroup,
 }
 
 impl ResultType {
     pub fn from_string(result_type: &str) -> Result<Self, String> {
-        let result_type = result_type.to_lowercase();
-        let authorized_result_type = AUTHORIZED_RESULT_TYPES.iter().find(|p| p.to_string().to_lowercase() == result_type);
-        if authorized_result_type.is_some() {
-            return Ok(ResultType { result_type: authorized_result_type.copied().unwrap().to_string() });
+        match result_type.to_lowercase().as_str() {
+            "overall" => { Ok(ResultType::Overall) }
+            "agegroup" => { Ok(ResultType::AgeGroup) }
+            _ => { Err(format!("Invalid result type [result_type: {}]", result_type)) }
         }
+    }
+}
 
-        Err(format!("Invalid result type [result_type: {}]", result_type))
+impl Display for ResultType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultType::Overall => { write!(f, "Overall") }
+            ResultType::AgeGroup => { write!(f, "AgeGroup") }
+        }
     }
 }
\ No newline at end of file
@@ -1,13 +1,10 @@
 use std::cmp::Ordering;
-use std::cmp::Ordering::{Equal, Greater, Less};
-use std::collections::HashMap;
+use std::cmp::Ordering::{Greater, Less};
+use std::fmt::{Display, Formatter};
 use std::time::Duration;
 
-use log::{error, trace, warn};
 use regex::{Match, Regex};
 
-use crate::competition::competition::Competition;
-use crate::competition::competition_result::CompetitionResult;
 use crate::result::result_value::ResultValue::{Distance, Empty, Points, Time};
 
 thread_local!(static TIME_REGEX: Regex = Regex::new(r"^((\d):)?(\d\d):(\d\d)(\.(\d\d\d?))?$").unwrap());
@@ -82,6 +79,60 @@ impl ResultValue {
             ResultValue::Custom(_) => { Err(format!("Can't compare custom value [self: {:?},other: {:?}]", self, other)) }
         }
     }
+
+    /// Tells whether a higher value is usually a better performance, based on the kind of value:
+    /// the faster the better for times, the further or the more the better for distances and points.
+    /// Some disciplines rank the other way round, see [ResultDirections](crate::result::result_direction::ResultDirections).
+    /// Empty and custom values can't be ranked, hence [None].
+    pub fn higher_is_better_by_default(&self) -> Option<bool> {
+        match self {
+            Empty => { None }
+            Time(_) => { Some(false) }
+            Points(_) => { Some(true) }
+            Distance(_) => { Some(true) }
+            ResultValue::Custom(_) => { None }
+        }
+    }
+
+    /// The value as a number, in seconds for times and in centimeters for distances. Empty and custom values have none.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Empty => { None }
+            Time(duration) => { Some(duration.as_secs_f64()) }
+            Points(points) => { Some(*points as f64) }
+            Distance(distance) => { Some(*distance as f64) }
+            ResultValue::Custom(_) => { None }
+        }
+    }
+
+    /// Tells whether this value is a strictly better performance than the other one, given whether a higher value is better.
+    pub fn is_better_than(&self, other: &Self, higher_is_better: bool) -> Result<bool, String> {
+        let ordering = self.compare(other)?;
+        match higher_is_better {
+            true => { Ok(ordering == Greater) }
+            false => { Ok(ordering == Less) }
+        }
+    }
+}
+
+impl Display for ResultValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Empty => { write!(f, "") }
+            Time(duration) => {
+                let seconds = duration.as_secs();
+                let thousands = duration.subsec_millis();
+                if seconds >= 3600 {
+                    write!(f, "{}:{:02}:{:02}.{:03}", seconds / 3600, (seconds % 3600) / 60, seconds % 60, thousands)
+                } else {
+                    write!(f, "{:02}:{:02}.{:03}", seconds / 60, seconds % 60, thousands)
+                }
+            }
+            Points(points) => { write!(f, "{points} pts") }
+            Distance(distance) => { write!(f, "{distance} cm") }
+            ResultValue::Custom(value) => { write!(f, "{value}") }
+        }
+    }
 }
 
 // region Duration
@@ -136,113 +187,30 @@ fn try_parse_distance(result: &str) -> Option<u16> {
 }
 // endregion
 
-pub fn get_best_results<'a>(results: &'a Vec<CompetitionResult>, higher_is_better_for_competition: &'a HashMap<Competition, bool>)
-                            -> HashMap<&'a Competition, &'a CompetitionResult> {
-    let mut best_values = HashMap::new();
-
-    for result in results {
-        if result.result().is_none() {
-            continue;
-        }
-
-        let competition = result.competition();
-        match best_values.get(competition) {
-            None => { best_values.insert(competition, result); }
-            Some(best_result_so_far) => {
-                let ordering = result.result().as_ref().unwrap().compare(best_result_so_far.result().as_ref().unwrap());
-                if ordering.is_ok() {
-                    let ordering = ordering.unwrap();
-                    let higher_is_better = higher_is_better_for_competition.get(competition);
-                    if higher_is_better.is_none() {
-                        warn!("No information on what's better for competition [competition: {:?}]", competition);
-                        continue;
-                    }
-                    let higher_is_better = *higher_is_better.unwrap();
-                    if higher_is_better {
-                        if ordering == Greater {
-                            trace!("New value is greater than previous, will replace previous [new: {:?}, previous: {:?}, higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                            best_values.insert(competition, result);
-                        } else if ordering == Equal {
-                            // FIXME: should be added
-                            trace!("New value is equal to previous [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                            warn!("New value is equal to previous, but new is ignored as of now [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                        } else if ordering == Less {
-                            // FIXME: add trace
-                            trace!("New value is less than previous, will not replace previous [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                        }
-                    } else {
-                        if ordering == Less {
-                            trace!("New value is less than previous, will replace previous [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                            best_values.insert(competition, result);
-                        } else if ordering == Equal {
-                            // FIXME: should be added
-                            trace!("New value is equal to previous [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                            warn!("New value is equal to previous, but new is ignored as of now [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                        } else if ordering == Greater {
-                            // FIXME: add trace
-                            trace!("New value is greater than previous, will not replace previous [new: {:?}, previous: {:?}, , higher_better: {}]",
-                                result, best_result_so_far, higher_is_better);
-                        }
-                    }
-                } else {
-                    error!("Can't compare results [best_result_so_far: {:?}, result: {:?}]", best_result_so_far, result);
-                    error!("{}", ordering.err().unwrap());
-                }
-            }
-        };
-    }
-    best_values
-}
-
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use crate::result::result_value::ResultValue;
 
-    use log::info;
+    #[test]
+    fn should_display_time() {
+        assert_eq!(ResultValue::from_string("01:14.990").to_string(), "01:14.990");
+        assert_eq!(ResultValue::from_string("1:01:14.990").to_string(), "1:01:14.990");
+    }
 
-    use crate::competition::competition::Competition;
-    use crate::competition::competition_result::CompetitionResult;
-    use crate::result::age_group::AgeGroup;
-    use crate::result::place::Place;
-    use crate::result::result_type::ResultType;
-    use crate::result::result_value::{get_best_results, ResultValue};
+    #[test]
+    fn should_tell_lower_time_is_better() {
+        let faster = ResultValue::from_string("00:14.99");
+        let slower = ResultValue::from_string("00:18.00");
+        assert_eq!(faster.higher_is_better_by_default(), Some(false));
+        assert_eq!(faster.is_better_than(&slower, false), Ok(true));
+        assert_eq!(slower.is_better_than(&faster, false), Ok(false));
+    }
 
     #[test]
-    fn should_get_best_result() {
-        let result1 = CompetitionResult::new(
-            1,
-            Competition::new("Competition"),
-            Place::from_string("1").unwrap(),
-            ResultType::from_string("Overall").unwrap(),
-            Some(ResultValue::from_string("00:14.99")),
-            None,
-            Some(AgeGroup::from_string("Senior")),
-        );
-        let result2 = CompetitionResult::new(
-            2,
-            Competition::new("Competition"),
-            Place::from_string("2").unwrap(),
-            ResultType::from_string("Overall").unwrap(),
-            Some(ResultValue::from_string("00:18.00")),
-            None,
-            Some(AgeGroup::from_string("Senior")),
-        );
-        let results = vec![result1, result2.clone()];
-
-        let mut higher_is_better_for_competition = HashMap::new();
-        higher_is_better_for_competition.insert(Competition::new("Competition"), true);
-
-        let best_results = get_best_results(&results, &higher_is_better_for_competition);
-        info!("{:?}", best_results);
-
-        let best_result = *best_results.get(&Competition::new("Competition")).unwrap();
-        assert_eq!(best_result, &result2);
+    fn should_tell_higher_distance_is_better() {
+        let further = ResultValue::from_string("120 cm");
+        let closer = ResultValue::from_string("100 cm");
+        assert_eq!(further.higher_is_better_by_default(), Some(true));
+        assert_eq!(further.is_better_than(&closer, true), Ok(true));
     }
 }
\ No newline at end of file
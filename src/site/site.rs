@@ -0,0 +1,499 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use deunicode::deunicode;
+use log::{info, warn};
+use minijinja::{context, Environment, Value};
+use serde::Serialize;
+
+use crate::competition::competition_result::CompetitionResult;
+use crate::competition::result_store::ResultStore;
+use crate::competitor::competitor::Competitor;
+use crate::convention::convention::Convention;
+use crate::export::write_file;
+use crate::person::person::Person;
+use crate::record::personal_record::personal_records;
+use crate::result::age_group::AgeGroup;
+use crate::result::place::Place;
+use crate::result::result_direction::ResultDirections;
+use crate::result::result_type::ResultType;
+
+pub const SITE_FOLDER: &str = "site";
+
+/// Templates are embedded in the binary, so that the site can be generated from anywhere.
+const TEMPLATES: [(&str, &str); 7] = [
+    ("base.html", include_str!("templates/base.html")),
+    ("macros.html", include_str!("templates/macros.html")),
+    ("index.html", include_str!("templates/index.html")),
+    ("people.html", include_str!("templates/people.html")),
+    ("convention.html", include_str!("templates/convention.html")),
+    ("competition.html", include_str!("templates/competition.html")),
+    ("person.html", include_str!("templates/person.html")),
+];
+const PODIUM_SIZE: u16 = 3;
+
+/// A link to a page of the site, or a mere name when there is no page to link to (e.g. an unidentified competitor).
+#[derive(Debug, Serialize)]
+struct PageLink {
+    name: String,
+    url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SiteCompetitor {
+    name: String,
+    url: Option<String>,
+    members: Vec<PageLink>,
+}
+
+impl SiteCompetitor {
+    fn from_link(link: PageLink, members: Vec<PageLink>) -> Self {
+        Self { name: link.name, url: link.url, members }
+    }
+}
+
+#[derive(Serialize)]
+struct SiteResultRow {
+    place: String,
+    competitor: SiteCompetitor,
+    result: String,
+    details: String,
+}
+
+#[derive(Serialize)]
+struct SiteResultGroup {
+    name: String,
+    rows: Vec<SiteResultRow>,
+}
+
+#[derive(Serialize)]
+struct SiteConvention {
+    page: PageLink,
+    name: String,
+    dates: String,
+    location: String,
+    competitions: usize,
+    search: String,
+}
+
+#[derive(Serialize)]
+struct SitePodium {
+    page: PageLink,
+    podium: Vec<SiteResultRow>,
+}
+
+#[derive(Serialize)]
+struct SitePerson {
+    page: PageLink,
+    countries: String,
+    conventions: usize,
+    search: String,
+}
+
+#[derive(Serialize)]
+struct SitePerformance {
+    competition: PageLink,
+    competitor: SiteCompetitor,
+    overall_place: String,
+    age_group_place: String,
+    age_group: String,
+    result: String,
+}
+
+#[derive(Serialize)]
+struct SitePersonConvention {
+    page: PageLink,
+    performances: Vec<SitePerformance>,
+}
+
+#[derive(Serialize)]
+struct SitePersonalRecord {
+    discipline: String,
+    result: String,
+    convention: PageLink,
+    competition: PageLink,
+}
+
+/// Render the conventions, their competitions and the people who took part in them as a static website in `{folder}`:
+/// index pages of conventions and people, with a search box, link to a page per convention, per competition and per person.
+/// Every page is generated even if some can't be, but then an error is returned.
+pub fn generate_site(folder: &str, conventions: &HashSet<Convention>, conventions_results: &HashMap<&Convention, ResultStore>, people: &[Person], result_directions: &ResultDirections) -> Result<(), ()> {
+    let site = Site::new(conventions_results, people, result_directions)?;
+    let mut conventions: Vec<&Convention> = conventions.iter().collect();
+    conventions.sort_by(|a, b| a.compare_chronologically(b));
+    let empty_store = ResultStore::default();
+
+    let mut pages_count = 0;
+    let mut failures_count = 0;
+    let mut record = |result: Result<(), ()>| {
+        pages_count += 1;
+        if result.is_err() {
+            failures_count += 1;
+        }
+    };
+
+    let mut site_conventions = vec![];
+    for convention in &conventions {
+        let store = conventions_results.get(convention).unwrap_or(&empty_store);
+        let competitions = group_by_competition(store);
+        for (competition, results) in &competitions {
+            record(site.render_competition_page(folder, convention, store, competition, results));
+        }
+        record(site.render_convention_page(folder, convention, &competitions));
+        site_conventions.push(SiteConvention {
+            page: convention_link(convention),
+            name: convention.name().clone(),
+            dates: format_dates(convention),
+            location: convention.location().clone().unwrap_or_default(),
+            competitions: competitions.len(),
+            search: to_search_text([convention.name().as_str(), convention.tag(), convention.location().as_deref().unwrap_or_default()]),
+        });
+    }
+    record(site.render_page(folder, "index.html", "index.html", context! { conventions => site_conventions }));
+
+    let mut site_people = vec![];
+    for person in people {
+        record(site.render_person_page(folder, person));
+        let countries = person_countries(person);
+        let clubs: BTreeSet<&str> = person.clubs().values().map(String::as_str).filter(|club| !club.is_empty()).collect();
+        let name = person_name(person);
+        site_people.push(SitePerson {
+            search: to_search_text(std::iter::once(name).chain(std::iter::once(countries.as_str())).chain(clubs)),
+            page: person_link(person),
+            countries,
+            conventions: person.registrations_id().len(),
+        });
+    }
+    site_people.sort_by(|a, b| a.page.name.cmp(&b.page.name));
+    record(site.render_page(folder, "people.html", "people.html", context! { people => site_people }));
+
+    if failures_count > 0 {
+        warn!("Some pages of the site couldn't be generated [folder: {folder}, pages: {pages_count}, failures: {failures_count}]");
+        return Err(());
+    }
+    info!("Site generated [folder: {folder}, pages: {pages_count}]");
+    Ok(())
+}
+
+struct Site<'p, 'a> {
+    environment: Environment<'static>,
+    // Results refer to competitors by their registration id at a convention, whereas pages are per person
+    people_by_registration: HashMap<(&'a str, u16), &'p Person<'a>>,
+    // Competitions are referred to by their convention tag and their name
+    competition_filenames: HashMap<(String, String), String>,
+    result_directions: &'p ResultDirections,
+}
+
+impl<'p, 'a> Site<'p, 'a> {
+    fn new(conventions_results: &HashMap<&Convention, ResultStore>, people: &'p [Person<'a>], result_directions: &'p ResultDirections) -> Result<Self, ()> {
+        let mut environment = Environment::new();
+        for (name, source) in TEMPLATES {
+            environment.add_template(name, source).map_err(|error| {
+                warn!("Can't load site template [template: {name}]");
+                warn!("{error}");
+            })?;
+        }
+        let people_by_registration = people.iter()
+            .flat_map(|person| person.registrations_id().iter()
+                .flat_map(move |(convention, ids)| ids.iter().map(move |id| ((convention.tag().as_str(), *id), person))))
+            .collect();
+
+        let competition_filenames = conventions_results.iter()
+            .flat_map(|(convention, store)| compute_competition_filenames(convention, store.competitions().map(|competition| competition.name().as_str())))
+            .collect();
+
+        Ok(Self { environment, people_by_registration, competition_filenames, result_directions })
+    }
+
+    fn render_page(&self, folder: &str, filename: &str, template: &str, context: Value) -> Result<(), ()> {
+        let html = self.environment.get_template(template)
+            .and_then(|template| template.render(context))
+            .map_err(|error| {
+                warn!("Can't render page [page: {filename}, template: {template}]");
+                warn!("{error}");
+            })?;
+        write_file(folder, filename, &html)
+    }
+
+    fn render_convention_page(&self, folder: &str, convention: &Convention, competitions: &BTreeMap<&str, Vec<&CompetitionResult>>) -> Result<(), ()> {
+        let podiums: Vec<SitePodium> = competitions.iter()
+            .map(|(competition, results)| SitePodium {
+                page: self.competition_link(convention, competition),
+                podium: sort_by_place(results.iter().copied()
+                    .filter(|result| *result.result_type() == ResultType::Overall)
+                    .filter(|result| matches!(result.place(), Place::Rank(rank) if *rank <= PODIUM_SIZE)))
+                    .into_iter()
+                    .map(|result| self.to_result_row(convention, result))
+                    .collect(),
+            })
+            .collect();
+        let context = context! {
+            convention => SiteConvention {
+                page: convention_link(convention),
+                name: convention.name().clone(),
+                dates: format_dates(convention),
+                location: convention.location().clone().unwrap_or_default(),
+                competitions: competitions.len(),
+                search: String::new(),
+            },
+            competitions => podiums,
+        };
+        self.render_page(folder, &convention_filename(convention), "convention.html", context)
+    }
+
+    /// Overall results come first, then the results of each age group in the order they appear in the results file.
+    fn render_competition_page(&self, folder: &str, convention: &Convention, store: &ResultStore, competition: &str, results: &[&CompetitionResult]) -> Result<(), ()> {
+        let mut age_groups: Vec<Option<&AgeGroup>> = vec![];
+        for result in results {
+            if *result.result_type() != ResultType::Overall && !age_groups.contains(&result.age_group().as_ref()) {
+                age_groups.push(result.age_group().as_ref());
+            }
+        }
+        let mut groups = vec![SiteResultGroup {
+            name: ResultType::Overall.to_string(),
+            rows: sort_by_place(results.iter().copied().filter(|result| *result.result_type() == ResultType::Overall))
+                .into_iter()
+                .map(|result| self.to_result_row(convention, result))
+                .collect(),
+        }];
+        groups.extend(age_groups.into_iter().map(|age_group| {
+            let age_group_results: Vec<&CompetitionResult> = match age_group {
+                None => { results.iter().copied().filter(|result| result.age_group().is_none()).collect() }
+                Some(age_group) => { store.by_age_group(age_group).filter(|result| result.competition().name() == competition).collect() }
+            };
+            SiteResultGroup {
+                name: age_group.map(AgeGroup::to_string).unwrap_or_default(),
+                rows: sort_by_place(age_group_results.into_iter().filter(|result| *result.result_type() != ResultType::Overall))
+                    .into_iter()
+                    .map(|result| self.to_result_row(convention, result))
+                    .collect(),
+            }
+        }));
+        groups.retain(|group| !group.rows.is_empty());
+
+        let context = context! {
+            competition => competition,
+            convention => convention_link(convention),
+            groups => groups,
+        };
+        self.render_page(folder, &self.competition_filename(convention, competition), "competition.html", context)
+    }
+
+    fn render_person_page(&self, folder: &str, person: &Person) -> Result<(), ()> {
+        let mut conventions: Vec<&&Convention> = person.performances().keys().collect();
+        conventions.sort_by(|a, b| a.compare_chronologically(b));
+        let conventions: Vec<SitePersonConvention> = conventions.into_iter()
+            .map(|convention| {
+                let mut performances: Vec<SitePerformance> = person.performances()[convention].iter()
+                    .map(|performance| SitePerformance {
+                        competition: self.competition_link(convention, performance.competition().name()),
+                        competitor: self.to_site_competitor(convention, performance.competitor()),
+                        overall_place: performance.overall_place().as_ref().map(Place::to_string).unwrap_or_default(),
+                        age_group_place: performance.age_group_place().as_ref().map(Place::to_string).unwrap_or_default(),
+                        age_group: performance.age_group().as_ref().map(|age_group| age_group.to_string()).unwrap_or_default(),
+                        result: performance.result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                    })
+                    .collect();
+                performances.sort_by(|a, b| a.competition.name.cmp(&b.competition.name));
+                SitePersonConvention { page: convention_link(convention), performances }
+            })
+            .collect();
+        let records: Vec<SitePersonalRecord> = personal_records(person, self.result_directions).iter()
+            .map(|record| SitePersonalRecord {
+                discipline: record.discipline().name().clone(),
+                result: record.best().performance().result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+                convention: convention_link(record.best().convention()),
+                competition: self.competition_link(record.best().convention(), record.best().performance().competition().name()),
+            })
+            .collect();
+
+        let context = context! {
+            name => person_name(person),
+            countries => person_countries(person),
+            records => records,
+            conventions => conventions,
+        };
+        self.render_page(folder, &person_filename(person), "person.html", context)
+    }
+
+    fn competition_link(&self, convention: &Convention, competition: &str) -> PageLink {
+        PageLink { name: competition.to_string(), url: Some(self.competition_filename(convention, competition)) }
+    }
+
+    fn competition_filename(&self, convention: &Convention, competition: &str) -> String {
+        self.competition_filenames.get(&(convention.tag().clone(), competition.to_string()))
+            .cloned()
+            .unwrap_or_else(|| format!("competition_{}_{}.html", slugify(convention.tag()), slugify(competition)))
+    }
+
+    fn to_result_row(&self, convention: &Convention, result: &CompetitionResult) -> SiteResultRow {
+        SiteResultRow {
+            place: result.place().to_string(),
+            competitor: self.to_site_competitor(convention, result.competitor()),
+            result: result.result().as_ref().map(|value| value.to_string()).unwrap_or_default(),
+            details: result.details().clone().unwrap_or_default(),
+        }
+    }
+
+    fn to_site_competitor(&self, convention: &Convention, competitor: &Competitor) -> SiteCompetitor {
+        match competitor {
+            Competitor::Team(team) => {
+                let members = team.members().iter().map(|member| self.to_competitor_link(convention, member)).collect();
+                SiteCompetitor::from_link(PageLink { name: team.name().clone(), url: None }, members)
+            }
+            _ => { SiteCompetitor::from_link(self.to_competitor_link(convention, competitor), vec![]) }
+        }
+    }
+
+    /// Link an individual competitor to the page of the person they are, if they have been identified.
+    fn to_competitor_link(&self, convention: &Convention, competitor: &Competitor) -> PageLink {
+        let id = competitor.ids().first().copied().unwrap_or_default();
+        let person = self.people_by_registration.get(&(convention.tag().as_str(), id));
+        let name = competitor.name().map(str::to_string)
+            .or_else(|| person.map(|person| person_name(person).to_string()))
+            .unwrap_or_else(|| format!("#{id}"));
+        PageLink { name, url: person.map(|person| person_filename(person)) }
+    }
+}
+
+/// The results of a convention per competition, sorted by competition name.
+fn group_by_competition(store: &ResultStore) -> BTreeMap<&str, Vec<&CompetitionResult>> {
+    store.competitions()
+        .map(|competition| (competition.name().as_str(), store.by_competition(competition).collect()))
+        .collect()
+}
+
+/// Ranks first, from the best, then disqualifications.
+fn sort_by_place<'r>(results: impl IntoIterator<Item=&'r CompetitionResult>) -> Vec<&'r CompetitionResult> {
+    let mut results: Vec<&CompetitionResult> = results.into_iter().collect();
+    results.sort_by_key(|result| match result.place() {
+        Place::Rank(rank) => { (false, *rank) }
+        Place::Disqualified(_) => { (true, 0) }
+    });
+    results
+}
+
+fn convention_link(convention: &Convention) -> PageLink {
+    PageLink { name: convention.name().clone(), url: Some(convention_filename(convention)) }
+}
+
+fn person_link(person: &Person) -> PageLink {
+    PageLink { name: person_name(person).to_string(), url: Some(person_filename(person)) }
+}
+
+fn convention_filename(convention: &Convention) -> String {
+    format!("convention_{}.html", slugify(convention.tag()))
+}
+
+/// The filename of the page of each competition of a convention, by convention tag and competition name.
+/// Names that slug the same, e.g. "100m Male" and "100m - Male", are told apart by a numeric suffix, in the order of names.
+fn compute_competition_filenames<'c>(convention: &Convention, competitions: impl Iterator<Item=&'c str>) -> Vec<((String, String), String)> {
+    let competitions: BTreeSet<&str> = competitions.collect();
+    let mut used_filenames = HashSet::new();
+    competitions.into_iter()
+        .map(|competition| {
+            let base_filename = format!("competition_{}_{}", slugify(convention.tag()), slugify(competition));
+            let mut filename = format!("{base_filename}.html");
+            let mut suffix = 2;
+            while !used_filenames.insert(filename.clone()) {
+                filename = format!("{base_filename}-{suffix}.html");
+                suffix += 1;
+            }
+            ((convention.tag().clone(), competition.to_string()), filename)
+        })
+        .collect()
+}
+
+fn person_filename(person: &Person) -> String {
+    format!("person_{}.html", person.id())
+}
+
+fn person_name<'p>(person: &'p Person) -> &'p str {
+    person.identity().person_name().name()
+}
+
+fn person_countries(person: &Person) -> String {
+    let countries: BTreeSet<String> = person.countries().values().map(|country| country.to_string()).collect();
+    countries.into_iter().collect::<Vec<String>>().join(", ")
+}
+
+fn format_dates(convention: &Convention) -> String {
+    match (convention.start_date(), convention.end_date()) {
+        (Some(start_date), Some(end_date)) if start_date != end_date => { format!("{start_date} to {end_date}") }
+        (Some(date), _) | (None, Some(date)) => { date.to_string() }
+        (None, None) => { String::new() }
+    }
+}
+
+/// Text the search boxes match against: lowercased and without accents, as queries are.
+fn to_search_text<'t>(texts: impl IntoIterator<Item=&'t str>) -> String {
+    let texts: Vec<&str> = texts.into_iter().filter(|text| !text.is_empty()).collect();
+    deunicode(&texts.join(" ")).to_lowercase()
+}
+
+/// Lowercased alphanumeric words joined by dashes, safe to be used in a filename or a URL.
+fn slugify(text: &str) -> String {
+    deunicode(text)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use crate::competition::competition_result::CompetitionResult;
+    use crate::competition::result_store::ResultStore;
+    use crate::convention::convention::Convention;
+    use crate::person::person::Person;
+    use crate::result::result_direction::ResultDirections;
+    use crate::site::site::{compute_competition_filenames, generate_site, slugify};
+
+    #[test]
+    fn should_slugify_names() {
+        assert_eq!(slugify("100m Male (Expert) - Finale"), "100m-male-expert-finale");
+        assert_eq!(slugify("Cross-Country Élite"), "cross-country-elite");
+    }
+
+    #[test]
+    fn should_link_results_to_people_pages() {
+        let folder = std::env::temp_dir().join(format!("site_{}", Uuid::new_v4())).to_string_lossy().to_string();
+        let convention = Convention::create_test_instance("cfm2023");
+        let results = vec![
+            CompetitionResult::create_test_instance(1, "100m Male", "1", "00:14.990").with_individual_competitor("John Doe", None, None),
+            CompetitionResult::create_test_instance(2, "100m Male", "DQ", "00:14.990").with_individual_competitor("<Jane> Doe", None, None),
+        ];
+        let person = Person::create_test_instance("John Doe").with_registrations_id(&convention, vec![1]).with_results(&convention, &results[..1]);
+        let conventions_results = HashMap::from([(&convention, ResultStore::new(results))]);
+
+        let result = generate_site(&folder, &HashSet::from([convention.clone()]), &conventions_results, std::slice::from_ref(&person), &ResultDirections::default());
+
+        assert!(result.is_ok());
+        let competition_page = fs::read_to_string(format!("{folder}/competition_cfm2023_100m-male.html")).unwrap();
+        assert!(competition_page.contains(&format!("<a href=\"person_{}.html\">John Doe</a>", person.id())));
+        assert!(competition_page.contains("&lt;Jane&gt; Doe"));
+        assert!(competition_page.find("John Doe").unwrap() < competition_page.find("Jane").unwrap());
+        let person_page = fs::read_to_string(format!("{folder}/person_{}.html", person.id())).unwrap();
+        assert!(person_page.contains("<a href=\"competition_cfm2023_100m-male.html\">100m Male</a>"));
+        assert!(fs::read_to_string(format!("{folder}/index.html")).unwrap().contains("convention_cfm2023.html"));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn should_tell_apart_competitions_whose_names_slug_the_same() {
+        let convention = Convention::create_test_instance("cfm2023");
+
+        let filenames = compute_competition_filenames(&convention, ["100m Male", "100m - Male", "200m Male"].into_iter());
+
+        assert_eq!(filenames.into_iter().map(|((_, competition), filename)| (competition, filename)).collect::<Vec<(String, String)>>(), vec![
+            ("100m - Male".to_string(), "competition_cfm2023_100m-male.html".to_string()),
+            ("100m Male".to_string(), "competition_cfm2023_100m-male-2.html".to_string()),
+            ("200m Male".to_string(), "competition_cfm2023_200m-male.html".to_string()),
+        ]);
+    }
+}
\ No newline at end of file
@@ -4,6 +4,7 @@ use log::error;
 
 pub mod credentials;
 pub mod env_manager;
+pub mod string_similarity;
 
 pub const DATA_FOLDER: &str = "data";
 
@@ -0,0 +1,60 @@
+/// Number of single-character insertions, deletions, substitutions or transpositions of adjacent characters
+/// needed to change a string into the other (optimal string alignment distance).
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b_chars.len() + 1]; a_chars.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a_chars.len() {
+        for j in 1..=b_chars.len() {
+            let substitution_cost = if a_chars[i - 1] == b_chars[j - 1] { 0 } else { 1 };
+            distances[i][j] = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a_chars[i - 1] == b_chars[j - 2] && a_chars[i - 2] == b_chars[j - 1] {
+                distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    distances[a_chars.len()][b_chars.len()]
+}
+
+/// Similarity between 0 (nothing in common) and 1 (equal), based on the [edit_distance].
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let max_length = a.chars().count().max(b.chars().count());
+    if max_length == 0 {
+        return 1.;
+    }
+    1. - edit_distance(a, b) as f32 / max_length as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::string_similarity::{edit_distance, similarity};
+
+    #[test]
+    fn should_compute_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "doe"), 3);
+        assert_eq!(edit_distance("doe", "doe"), 0);
+    }
+
+    #[test]
+    fn should_count_transposition_as_single_edit() {
+        assert_eq!(edit_distance("john", "jonh"), 1);
+    }
+
+    #[test]
+    fn should_compute_similarity() {
+        assert_eq!(similarity("doe", "doe"), 1.);
+        assert_eq!(similarity("", ""), 1.);
+        assert_eq!(similarity("abcd", "abce"), 0.75);
+    }
+}